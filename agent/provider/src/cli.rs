@@ -5,6 +5,8 @@ pub mod exe_unit;
 pub mod keystore;
 pub mod preset;
 pub mod profile;
+pub mod requestors;
+pub mod status;
 pub mod whitelist;
 
 use crate::startup_config::ProviderConfig;
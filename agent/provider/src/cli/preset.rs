@@ -4,7 +4,11 @@ use anyhow::{anyhow, bail, Result};
 use dialoguer::{Input, Select};
 use structopt::StructOpt;
 
+use crate::hardware::{ProfileError, Profiles};
 use crate::market::{Preset, PresetManager};
+use crate::payments::{
+    is_optional_pricing_param, pricing_model_params, pricing_offer, PRICING_MODELS,
+};
 use crate::startup_config::{PresetNoInteractive, ProviderConfig, UpdateNames};
 
 #[derive(StructOpt, Clone, Debug)]
@@ -129,6 +133,30 @@ impl PresetUpdater {
         Ok(())
     }
 
+    pub fn update_pricing_params(&mut self) -> Result<()> {
+        // Optional parameters can be set only from command line, so we keep them.
+        let model = &self.preset.pricing_model;
+        let mut pricing_params: HashMap<String, f64> = self
+            .preset
+            .pricing_params
+            .iter()
+            .filter(|(name, _)| is_optional_pricing_param(model, name))
+            .map(|(name, value)| (name.clone(), *value))
+            .collect();
+        for name in pricing_model_params(&self.preset.pricing_model)? {
+            let prev_value = self.preset.pricing_params.get(*name).cloned();
+            let mut input = Input::<f64>::new();
+            input.with_prompt(&format!("{} pricing: {}", self.preset.pricing_model, name));
+            if let Some(prev_value) = prev_value {
+                input.default(prev_value).show_default(true);
+            }
+            pricing_params.insert(name.to_string(), input.interact()?);
+        }
+
+        self.preset.pricing_params = pricing_params;
+        Ok(())
+    }
+
     pub fn update_metrics(&mut self, config: &ProviderConfig) -> Result<()> {
         let registry = config.registry()?;
         let mut usage_coeffs: HashMap<String, f64> = Default::default();
@@ -169,6 +197,7 @@ impl PresetUpdater {
         self.update_name()?;
         self.update_exeunit()?;
         self.update_pricing_model()?;
+        self.update_pricing_params()?;
         self.update_metrics(config)?;
 
         Ok(self.preset)
@@ -184,7 +213,7 @@ pub fn create_interactive(config: ProviderConfig) -> anyhow::Result<()> {
     let registry = config.registry()?;
 
     let exeunits = registry.list().into_iter().map(|desc| desc.name).collect();
-    let pricing_models = vec!["linear".to_string()];
+    let pricing_models = PRICING_MODELS.iter().map(ToString::to_string).collect();
 
     let preset =
         PresetUpdater::new(Preset::default(), exeunits, pricing_models).interact(&config)?;
@@ -233,6 +262,11 @@ pub fn create(config: ProviderConfig, params: PresetNoInteractive) -> anyhow::Re
             preset.usage_coeffs.insert(usage_coefficient, *price);
         }
     }
+    preset
+        .pricing_params
+        .extend(params.pricing_param.iter().cloned());
+    preset.slot = params.slot;
+    preset.availability.windows = params.availability;
 
     validate_preset(&config, &preset)?;
 
@@ -326,6 +360,15 @@ fn update_presets(
                         .insert(exe_unit_desc.resolve_coefficient(name)?, *price);
                 }
             }
+            preset
+                .pricing_params
+                .extend(params.pricing_param.iter().cloned());
+            if params.slot.is_some() {
+                preset.slot = params.slot;
+            }
+            if !params.availability.is_empty() {
+                preset.availability.windows = params.availability;
+            }
 
             validate_preset(config, preset)?;
 
@@ -351,8 +394,14 @@ fn validate_preset(config: &ProviderConfig, preset: &Preset) -> anyhow::Result<(
     let registry = config.registry()?;
     registry.find_exeunit(&preset.exeunit_name)?;
 
-    if preset.pricing_model != "linear" {
-        bail!("Not supported pricing model.")
+    // Checks pricing model and its parameters.
+    pricing_offer(preset)?;
+
+    if let Some(slot) = &preset.slot {
+        let profiles = Profiles::load_or_create(config)?;
+        if profiles.get_slot(slot).is_none() {
+            return Err(ProfileError::UnknownSlot(slot.clone()).into());
+        }
     }
 
     Ok(())
@@ -367,7 +416,7 @@ fn update_preset_interactive(config: ProviderConfig, name: String) -> anyhow::Re
     let registry = config.registry()?;
 
     let exeunits = registry.list().into_iter().map(|desc| desc.name).collect();
-    let pricing_models = vec!["linear".to_string()];
+    let pricing_models = PRICING_MODELS.iter().map(ToString::to_string).collect();
 
     let preset =
         PresetUpdater::new(presets.get(&name)?, exeunits, pricing_models).interact(&config)?;
@@ -1,5 +1,5 @@
 use crate::hardware::ProfileError;
-use crate::hardware::{Profiles, Resources, UpdateResources};
+use crate::hardware::{Profiles, Resources, Slot, UpdateResources};
 use crate::startup_config::{ProviderConfig, UpdateNames};
 use structopt::StructOpt;
 
@@ -27,6 +27,16 @@ pub enum ProfileConfig {
     Remove { name: String },
     /// Activate a profile
     Activate { name: String },
+    /// List resource slots offered by separate presets
+    Slots,
+    /// Create or update a resource slot
+    SetSlot {
+        name: String,
+        #[structopt(flatten)]
+        slot: Slot,
+    },
+    /// Remove a resource slot
+    RemoveSlot { name: String },
 }
 
 impl ProfileConfig {
@@ -57,12 +67,27 @@ impl ProfileConfig {
                 ProfileConfig::Activate { name } => {
                     let mut profiles = Profiles::load_or_create(&config)?;
                     profiles.set_active(name)?;
+                    profiles.validate_slots()?;
                     profiles.save(path)?;
                 }
                 ProfileConfig::Active => {
                     let profiles = Profiles::load_or_create(&config)?;
                     println!("{}", serde_json::to_string_pretty(profiles.active())?);
                 }
+                ProfileConfig::Slots => {
+                    let slots = Profiles::load_or_create(&config)?.list_slots();
+                    println!("{}", serde_json::to_string_pretty(&slots)?);
+                }
+                ProfileConfig::SetSlot { name, slot } => {
+                    let mut profiles = Profiles::load_or_create(&config)?;
+                    profiles.set_slot(name, slot)?;
+                    profiles.save(path)?;
+                }
+                ProfileConfig::RemoveSlot { name } => {
+                    let mut profiles = Profiles::load_or_create(&config)?;
+                    profiles.remove_slot(name)?;
+                    profiles.save(path)?;
+                }
             }
             Ok(())
         }
@@ -0,0 +1,94 @@
+use structopt::StructOpt;
+
+use ya_client::model::NodeId;
+use ya_utils_cli::{CommandOutput, ResponseTable};
+
+use crate::cli::println_conditional;
+use crate::config::requestors::RequestorLists;
+use crate::startup_config::ProviderConfig;
+
+#[derive(StructOpt, Clone, Debug)]
+#[structopt(
+    rename_all = "kebab-case",
+    help = "Requestor lists allow to negotiate only with chosen Requestors or to reject Demands
+from unwanted ones. If allowed list is empty, all Requestors except denied are accepted."
+)]
+pub enum RequestorsConfig {
+    /// List allowed and denied Requestors
+    List,
+    /// Add Requestors to allowed list
+    Allow {
+        /// Space separated Requestors' node ids
+        ids: Vec<NodeId>,
+    },
+    /// Add Requestors to denied list
+    Deny {
+        /// Space separated Requestors' node ids
+        ids: Vec<NodeId>,
+    },
+    /// Remove Requestors from allowed and denied lists
+    Remove {
+        /// Space separated Requestors' node ids
+        ids: Vec<NodeId>,
+    },
+}
+
+impl RequestorsConfig {
+    pub fn run(self, config: ProviderConfig) -> anyhow::Result<()> {
+        let mut lists = RequestorLists::load_or_create(&config.requestors_file)?;
+        let (changed, list) = match self {
+            RequestorsConfig::List => return RequestorsTable::from(&lists).print(&config),
+            RequestorsConfig::Allow { ids } => (lists.allow(&ids), "allowed"),
+            RequestorsConfig::Deny { ids } => (lists.deny(&ids), "denied"),
+            RequestorsConfig::Remove { ids } => (lists.remove(&ids), "removed"),
+        };
+        lists.save(&config.requestors_file)?;
+
+        if changed.is_empty() {
+            println_conditional(&config, "No changes to Requestor lists.");
+        } else {
+            println_conditional(&config, "Changed Requestors:");
+        }
+        let mut table = RequestorsTable::new();
+        table.add(&changed, list);
+        table.print(&config)
+    }
+}
+
+struct RequestorsTable {
+    table: ResponseTable,
+}
+
+impl RequestorsTable {
+    fn new() -> Self {
+        let columns = vec!["Requestor".to_string(), "List".to_string()];
+        let table = ResponseTable {
+            columns,
+            values: vec![],
+        };
+        Self { table }
+    }
+
+    fn add(&mut self, ids: &[NodeId], list: &str) {
+        for id in ids {
+            self.table
+                .values
+                .push(serde_json::json! {[ id.to_string(), list ]});
+        }
+    }
+
+    fn print(self, config: &ProviderConfig) -> anyhow::Result<()> {
+        let output = CommandOutput::from(self.table);
+        output.print(config.json)?;
+        Ok(())
+    }
+}
+
+impl From<&RequestorLists> for RequestorsTable {
+    fn from(lists: &RequestorLists) -> Self {
+        let mut table = RequestorsTable::new();
+        table.add(&lists.allowed, "allowed");
+        table.add(&lists.denied, "denied");
+        table
+    }
+}
@@ -0,0 +1,58 @@
+use chrono::Utc;
+use structopt::StructOpt;
+
+use ya_utils_cli::{CommandOutput, ResponseTable};
+
+use crate::cli::println_conditional;
+use crate::config::globals::GlobalsState;
+use crate::config::requestors::RequestorsUsage;
+use crate::startup_config::ProviderConfig;
+
+#[derive(StructOpt, Clone, Debug)]
+#[structopt(
+    help = "Shows Agreements counters of Requestors saved by running Provider
+together with configured per Requestor limits."
+)]
+pub struct StatusConfig {}
+
+impl StatusConfig {
+    pub fn run(self, config: ProviderConfig) -> anyhow::Result<()> {
+        let globals = GlobalsState::load(&config.globals_file)?;
+        let limits = globals.requestor_limits();
+        let usage = RequestorsUsage::load(&config.requestors_usage_file)?;
+
+        let describe = |limit: Option<u32>| match limit {
+            Some(limit) => limit.to_string(),
+            None => "unlimited".to_string(),
+        };
+        println_conditional(
+            &config,
+            &format!(
+                "Max Agreements per Requestor: {}\nMax new Agreements per Requestor per hour: {}\n",
+                describe(limits.max_agreements),
+                describe(limits.max_agreements_per_hour)
+            ),
+        );
+
+        let now = Utc::now();
+        let columns = vec![
+            "Requestor".to_string(),
+            "Active Agreements".to_string(),
+            "Agreements last hour".to_string(),
+        ];
+        let values = usage
+            .requestors
+            .keys()
+            .map(|id| {
+                serde_json::json! {[
+                    id.to_string(),
+                    usage.active_agreements(id),
+                    usage.hourly_agreements(id, now),
+                ]}
+            })
+            .collect();
+
+        CommandOutput::from(ResponseTable { columns, values }).print(config.json)?;
+        Ok(())
+    }
+}
@@ -1,2 +1,3 @@
 pub mod globals;
 pub mod presets;
+pub mod requestors;
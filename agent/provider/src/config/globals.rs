@@ -15,15 +15,26 @@ fn default_subnet() -> Option<String> {
 
 #[derive(Clone, Debug, Default, Serialize, derive_more::Display)]
 #[display(
-    fmt = "{}{}{}",
+    fmt = "{}{}{}{}{}",
     "node_name.as_ref().map(|nn| format!(\"Node name: {}\", nn)).unwrap_or_else(|| \"\".into())",
     "subnet.as_ref().map(|s| format!(\"\nSubnet: {}\", s)).unwrap_or_else(|| \"\".into())",
-    "account.as_ref().map(|a| format!(\"\nAccount: {}\", a)).unwrap_or_else(|| \"\".into())"
+    "account.as_ref().map(|a| format!(\"\nAccount: {}\", a)).unwrap_or_else(|| \"\".into())",
+    "max_requestor_agreements.map(|n| format!(\"\nMax Agreements per Requestor: {}\", n)).unwrap_or_else(|| \"\".into())",
+    "max_requestor_agreements_per_hour.map(|n| format!(\"\nMax new Agreements per Requestor per hour: {}\", n)).unwrap_or_else(|| \"\".into())"
 )]
 pub struct GlobalsState {
     pub node_name: Option<String>,
     pub subnet: Option<String>,
     pub account: Option<NodeId>,
+    pub max_requestor_agreements: Option<u32>,
+    pub max_requestor_agreements_per_hour: Option<u32>,
+}
+
+/// Limits applied to each Requestor separately.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RequestorLimits {
+    pub max_agreements: Option<u32>,
+    pub max_agreements_per_hour: Option<u32>,
 }
 
 impl<'de> Deserialize<'de> for GlobalsState {
@@ -54,6 +65,8 @@ impl<'de> Deserialize<'de> for GlobalsState {
             pub node_name: Option<String>,
             pub subnet: Option<String>,
             pub account: Option<Account>,
+            pub max_requestor_agreements: Option<u32>,
+            pub max_requestor_agreements_per_hour: Option<u32>,
         }
 
         let s = GenericGlobalsState::deserialize(deserializer)?;
@@ -61,10 +74,20 @@ impl<'de> Deserialize<'de> for GlobalsState {
             node_name: s.node_name,
             subnet: s.subnet,
             account: s.account.map(|a| a.address()),
+            max_requestor_agreements: s.max_requestor_agreements,
+            max_requestor_agreements_per_hour: s.max_requestor_agreements_per_hour,
         })
     }
 }
 
+/// Zero is used on command line to remove the limit.
+fn non_zero(limit: u32) -> Option<u32> {
+    match limit {
+        0 => None,
+        limit => Some(limit),
+    }
+}
+
 impl GlobalsState {
     pub fn load(path: &Path) -> anyhow::Result<Self> {
         if path.exists() {
@@ -103,9 +126,22 @@ impl GlobalsState {
         if node_config.account.account.is_some() {
             self.account = node_config.account.account;
         }
+        if let Some(limit) = node_config.max_requestor_agreements {
+            self.max_requestor_agreements = non_zero(limit);
+        }
+        if let Some(limit) = node_config.max_requestor_agreements_per_hour {
+            self.max_requestor_agreements_per_hour = non_zero(limit);
+        }
         self.save(path)
     }
 
+    pub fn requestor_limits(&self) -> RequestorLimits {
+        RequestorLimits {
+            max_agreements: self.max_requestor_agreements,
+            max_agreements_per_hour: self.max_requestor_agreements_per_hour,
+        }
+    }
+
     pub fn save(&self, path: &Path) -> anyhow::Result<()> {
         Ok(path.swap_save(serde_json::to_string_pretty(self)?)?)
     }
@@ -187,4 +223,25 @@ mod test {
         assert_eq!(g.subnet, Some("community.4".into()));
         assert!(g.account.is_none())
     }
+
+    #[test]
+    fn deserialize_requestor_limits() {
+        let g: GlobalsState = serde_json::from_str(GLOBALS_JSON_ALPHA_4).unwrap();
+        assert_eq!(g.requestor_limits(), RequestorLimits::default());
+
+        let g: GlobalsState = serde_json::from_str(
+            r#"
+    {
+      "node_name": "amusing-crate",
+      "subnet": "community.4",
+      "max_requestor_agreements": 2,
+      "max_requestor_agreements_per_hour": 10
+    }
+    "#,
+        )
+        .unwrap();
+
+        assert_eq!(g.requestor_limits().max_agreements, Some(2));
+        assert_eq!(g.requestor_limits().max_agreements_per_hour, Some(10));
+    }
 }
@@ -150,6 +150,9 @@ impl From<PresetV0> for Preset {
                     _ => None,
                 })
                 .collect(),
+            pricing_params: Default::default(),
+            slot: None,
+            availability: Default::default(),
         }
     }
 }
@@ -0,0 +1,315 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::{fs, io};
+
+use ya_client::model::NodeId;
+use ya_utils_path::SwapSave;
+
+use crate::config::globals::RequestorLimits;
+
+pub(crate) const REQUESTORS_JSON: &str = "requestors.json";
+pub(crate) const REQUESTORS_USAGE_JSON: &str = "requestors-usage.json";
+
+/// Requestors, that Provider wants or doesn't want to work for.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequestorLists {
+    /// If not empty, only these Requestors are allowed to negotiate.
+    #[serde(default)]
+    pub allowed: Vec<NodeId>,
+    #[serde(default)]
+    pub denied: Vec<NodeId>,
+}
+
+impl RequestorLists {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if path.exists() {
+            log::debug!("Loading Requestor lists from: {}", path.display());
+            Ok(serde_json::from_reader(io::BufReader::new(
+                fs::OpenOptions::new().read(true).open(path)?,
+            ))?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn load_or_create(path: &Path) -> anyhow::Result<Self> {
+        if path.exists() {
+            Self::load(path)
+        } else {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let lists = Self::default();
+            lists.save(path)?;
+            Ok(lists)
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        Ok(path.swap_save(serde_json::to_string_pretty(self)?)?)
+    }
+
+    /// Returns reason of rejection, if Requestor isn't allowed.
+    pub fn check(&self, requestor: &NodeId) -> Result<(), String> {
+        if self.denied.contains(requestor) {
+            return Err(format!("Requestor [{}] is denied by Provider.", requestor));
+        }
+        if !self.allowed.is_empty() && !self.allowed.contains(requestor) {
+            return Err(format!(
+                "Requestor [{}] is not on Provider's allowed list.",
+                requestor
+            ));
+        }
+        Ok(())
+    }
+
+    /// Adds Requestors to allowed list and removes them from denied list.
+    /// Returns Requestors, that weren't allowed before.
+    pub fn allow(&mut self, requestors: &[NodeId]) -> Vec<NodeId> {
+        self.denied.retain(|id| !requestors.contains(id));
+        add_unique(&mut self.allowed, requestors)
+    }
+
+    /// Adds Requestors to denied list and removes them from allowed list.
+    /// Returns Requestors, that weren't denied before.
+    pub fn deny(&mut self, requestors: &[NodeId]) -> Vec<NodeId> {
+        self.allowed.retain(|id| !requestors.contains(id));
+        add_unique(&mut self.denied, requestors)
+    }
+
+    /// Removes Requestors from both lists. Returns removed Requestors.
+    pub fn remove(&mut self, requestors: &[NodeId]) -> Vec<NodeId> {
+        let removed = requestors
+            .iter()
+            .filter(|id| self.allowed.contains(id) || self.denied.contains(id))
+            .cloned()
+            .collect();
+        self.allowed.retain(|id| !requestors.contains(id));
+        self.denied.retain(|id| !requestors.contains(id));
+        removed
+    }
+}
+
+fn add_unique(list: &mut Vec<NodeId>, requestors: &[NodeId]) -> Vec<NodeId> {
+    let mut added = Vec::new();
+    for id in requestors {
+        if !list.contains(id) {
+            list.push(*id);
+            added.push(*id);
+        }
+    }
+    added
+}
+
+/// Requestor lists shared with negotiator. Updated, when file changes.
+#[derive(Clone, Debug, Default)]
+pub struct RequestorListsState {
+    lists: Arc<RwLock<RequestorLists>>,
+}
+
+impl RequestorListsState {
+    pub fn new(lists: RequestorLists) -> Self {
+        RequestorListsState {
+            lists: Arc::new(RwLock::new(lists)),
+        }
+    }
+
+    pub fn replace(&self, lists: RequestorLists) {
+        *self.lists.write().unwrap() = lists;
+    }
+
+    pub fn check(&self, requestor: &NodeId) -> Result<(), String> {
+        self.lists.read().unwrap().check(requestor)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let lists = self.lists.read().unwrap();
+        lists.allowed.is_empty() && lists.denied.is_empty()
+    }
+}
+
+/// Agreements of single Requestor.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RequestorUsage {
+    /// Currently running Agreements.
+    #[serde(default)]
+    pub active: BTreeSet<String>,
+    /// Approval times of Agreements from last hour by Agreement id.
+    #[serde(default)]
+    pub approved: BTreeMap<String, DateTime<Utc>>,
+}
+
+/// Agreements counters used to apply `RequestorLimits`. Saved by running
+/// Provider, so they can be displayed by `status` command.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct RequestorsUsage {
+    pub requestors: BTreeMap<NodeId, RequestorUsage>,
+}
+
+impl RequestorsUsage {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if path.exists() {
+            log::debug!("Loading Requestors usage from: {}", path.display());
+            Ok(serde_json::from_reader(io::BufReader::new(
+                fs::OpenOptions::new().read(true).open(path)?,
+            ))?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        Ok(path.swap_save(serde_json::to_string_pretty(self)?)?)
+    }
+
+    pub fn active_agreements(&self, requestor: &NodeId) -> usize {
+        self.requestors
+            .get(requestor)
+            .map(|usage| usage.active.len())
+            .unwrap_or(0)
+    }
+
+    pub fn hourly_agreements(&self, requestor: &NodeId, now: DateTime<Utc>) -> usize {
+        let hour_ago = now - Duration::hours(1);
+        self.requestors
+            .get(requestor)
+            .map(|usage| usage.approved.values().filter(|ts| **ts > hour_ago).count())
+            .unwrap_or(0)
+    }
+
+    /// Returns reason of rejection, if Requestor reached any of limits.
+    pub fn check(
+        &self,
+        requestor: &NodeId,
+        limits: &RequestorLimits,
+        now: DateTime<Utc>,
+    ) -> Result<(), String> {
+        if let Some(max) = limits.max_agreements {
+            if self.active_agreements(requestor) >= max as usize {
+                return Err(format!(
+                    "Reached limit of {} simultaneous Agreements per Requestor.",
+                    max
+                ));
+            }
+        }
+        if let Some(max) = limits.max_agreements_per_hour {
+            if self.hourly_agreements(requestor, now) >= max as usize {
+                return Err(format!(
+                    "Reached limit of {} new Agreements per Requestor within an hour.",
+                    max
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn approve(&mut self, requestor: NodeId, agreement_id: &str, now: DateTime<Utc>) {
+        let usage = self.requestors.entry(requestor).or_default();
+        usage.active.insert(agreement_id.to_string());
+        usage.approved.insert(agreement_id.to_string(), now);
+        self.prune(now);
+    }
+
+    /// Forgets Agreement, that was approved by us, but wasn't created in market.
+    /// Such Agreement doesn't count towards any of limits.
+    pub fn approval_failed(&mut self, agreement_id: &str, now: DateTime<Utc>) {
+        for usage in self.requestors.values_mut() {
+            usage.active.remove(agreement_id);
+            usage.approved.remove(agreement_id);
+        }
+        self.prune(now);
+    }
+
+    pub fn terminate(&mut self, agreement_id: &str, now: DateTime<Utc>) {
+        for usage in self.requestors.values_mut() {
+            usage.active.remove(agreement_id);
+        }
+        self.prune(now);
+    }
+
+    /// Forgets running Agreements. Used on startup, since Agreements
+    /// from previous run won't be reported as terminated.
+    pub fn clear_active(&mut self, now: DateTime<Utc>) {
+        for usage in self.requestors.values_mut() {
+            usage.active.clear();
+        }
+        self.prune(now);
+    }
+
+    /// Removes approvals older than an hour and Requestors without Agreements.
+    fn prune(&mut self, now: DateTime<Utc>) {
+        let hour_ago = now - Duration::hours(1);
+        for usage in self.requestors.values_mut() {
+            usage.approved.retain(|_, ts| *ts > hour_ago);
+        }
+        self.requestors
+            .retain(|_, usage| !usage.active.is_empty() || !usage.approved.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn node(n: u8) -> NodeId {
+        format!("0x{:040x}", n).parse().unwrap()
+    }
+
+    #[test]
+    fn check_requestor_lists() {
+        let mut lists = RequestorLists::default();
+        assert!(lists.check(&node(1)).is_ok());
+
+        assert_eq!(lists.deny(&[node(1)]), vec![node(1)]);
+        assert!(lists.check(&node(1)).is_err());
+        assert!(lists.check(&node(2)).is_ok());
+
+        assert_eq!(lists.allow(&[node(1), node(2)]), vec![node(1), node(2)]);
+        assert!(lists.denied.is_empty());
+        assert!(lists.check(&node(1)).is_ok());
+        assert!(lists.check(&node(3)).is_err());
+
+        assert_eq!(lists.remove(&[node(1), node(3)]), vec![node(1)]);
+        assert_eq!(lists.allowed, vec![node(2)]);
+    }
+
+    #[test]
+    fn check_requestor_limits() {
+        let limits = RequestorLimits {
+            max_agreements: Some(2),
+            max_agreements_per_hour: Some(3),
+        };
+        let now = Utc::now();
+        let mut usage = RequestorsUsage::default();
+
+        usage.approve(node(1), "a1", now);
+        assert!(usage.check(&node(1), &limits, now).is_ok());
+        usage.approve(node(1), "a2", now);
+        assert!(usage.check(&node(1), &limits, now).is_err());
+        assert!(usage.check(&node(2), &limits, now).is_ok());
+
+        usage.terminate("a1", now);
+        assert_eq!(usage.active_agreements(&node(1)), 1);
+        assert!(usage.check(&node(1), &limits, now).is_ok());
+
+        usage.approve(node(1), "a3", now);
+        usage.terminate("a2", now);
+        usage.terminate("a3", now);
+        assert_eq!(usage.hourly_agreements(&node(1), now), 3);
+        assert!(usage.check(&node(1), &limits, now).is_err());
+
+        usage.approve(node(2), "a4", now);
+        usage.approval_failed("a4", now);
+        assert_eq!(usage.hourly_agreements(&node(2), now), 0);
+        assert!(!usage.requestors.contains_key(&node(2)));
+
+        let later = now + Duration::minutes(61);
+        assert!(usage.check(&node(1), &limits, later).is_ok());
+        usage.clear_active(later);
+        assert!(usage.requestors.is_empty());
+    }
+}
@@ -21,6 +21,8 @@ use crate::startup_config::{FileMonitor, ProviderConfig};
 
 pub const DEFAULT_PROFILE_NAME: &str = "default";
 pub const CPU_THREADS_RESERVED: i32 = 1;
+/// Offer property with name of offered resource slot.
+pub const SLOT_PROPERTY: &str = "golem.inf.slot";
 pub static MIN_CAPS: Resources = Resources {
     cpu_threads: 1,
     mem_gib: 0.1,
@@ -35,6 +37,10 @@ pub enum ProfileError {
     AlreadyExists(String),
     #[error("profile is active: '{0}'")]
     Active(String),
+    #[error("unknown slot: '{0}'")]
+    UnknownSlot(String),
+    #[error("slots exceed resources of active profile '{0}'")]
+    SlotsExceedProfile(String),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -45,6 +51,8 @@ pub enum Error {
     AlreadyAllocated(String),
     #[error("resources not allocated for id {0}")]
     NotAllocated(String),
+    #[error("all '{0}' slots are allocated")]
+    SlotsDepleted(String),
     #[error("profile error: {0}")]
     Profile(#[from] ProfileError),
     #[error("serialization error: {0}")]
@@ -215,10 +223,35 @@ impl From<Resources> for InfNodeInfo {
     }
 }
 
+/// Part of the machine offered separately, so a few smaller Agreements
+/// can run in parallel. Presets are bound to slots by name.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, StructOpt, PartialEq)]
+#[structopt(rename_all = "kebab-case")]
+pub struct Slot {
+    #[serde(flatten)]
+    #[structopt(flatten)]
+    pub resources: Resources,
+    /// Number of slots of this size
+    #[structopt(long)]
+    pub count: u32,
+}
+
+impl Slot {
+    pub fn total(&self) -> Resources {
+        Resources {
+            cpu_threads: self.resources.cpu_threads * self.count as i32,
+            mem_gib: self.resources.mem_gib * self.count as f64,
+            storage_gib: self.resources.storage_gib * self.count as f64,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Profiles {
     active: String,
     profiles: HashMap<String, Resources>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    slots: HashMap<String, Slot>,
 }
 
 impl Profiles {
@@ -263,7 +296,11 @@ impl Profiles {
         let resources = Resources::try_with_config(path.as_ref(), config)?;
         let active = DEFAULT_PROFILE_NAME.to_string();
         let profiles = vec![(active.clone(), resources)].into_iter().collect();
-        Ok(Profiles { active, profiles })
+        Ok(Profiles {
+            active,
+            profiles,
+            slots: HashMap::new(),
+        })
     }
 }
 
@@ -317,6 +354,53 @@ impl Profiles {
         self.active = name;
         Ok(())
     }
+
+    #[inline]
+    pub fn list_slots(&self) -> HashMap<String, Slot> {
+        self.slots.clone()
+    }
+
+    #[inline]
+    pub fn get_slot(&self, name: impl ToString) -> Option<&Slot> {
+        self.slots.get(&name.to_string())
+    }
+
+    /// Adds or replaces slot. All slots together must fit into active profile.
+    pub fn set_slot(&mut self, name: impl ToString, slot: Slot) -> Result<(), Error> {
+        if slot.count == 0 || slot.resources.depleted() {
+            return Err(Error::InsufficientResources);
+        }
+        let previous = self.slots.insert(name.to_string(), slot);
+        if let Err(e) = self.validate_slots() {
+            match previous {
+                Some(previous) => self.slots.insert(name.to_string(), previous),
+                None => self.slots.remove(&name.to_string()),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn remove_slot(&mut self, name: impl ToString) -> Result<(), Error> {
+        let name = name.to_string();
+        if self.slots.remove(&name).is_none() {
+            return Err(ProfileError::UnknownSlot(name).into());
+        }
+        Ok(())
+    }
+
+    /// Checks if all slots fit into active profile.
+    pub fn validate_slots(&self) -> Result<(), Error> {
+        let total = self
+            .slots
+            .values()
+            .fold(Resources::new_empty(), |total, slot| total + slot.total());
+        match self.profiles.get(&self.active) {
+            Some(profile) if *profile >= total => Ok(()),
+            _ => Err(ProfileError::SlotsExceedProfile(self.active.clone()).into()),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -334,13 +418,16 @@ struct ManagerState {
     res_cap: Resources,
     res_remaining: Resources,
     res_alloc: HashMap<String, Resources>,
+    slot_alloc: HashMap<String, String>,
 }
 
 impl ManagerState {
     #[inline]
     fn update(&mut self, profiles: Profiles) -> Result<bool, Error> {
+        let slots_changed = self.profiles.slots != profiles.slots;
         self.profiles = profiles;
-        self.change_profile(self.profiles.active.clone())
+        let profile_changed = self.change_profile(self.profiles.active.clone())?;
+        Ok(profile_changed || slots_changed)
     }
 
     fn change_profile(&mut self, name: impl ToString) -> Result<bool, Error> {
@@ -362,9 +449,71 @@ impl ManagerState {
             self.res_remaining = self.res_remaining - delta;
             log::info!("Hardware resources cap: {:?}", self.res_cap);
             log::info!("Hardware resources remaining: {:?}", self.res_remaining);
+            if let Err(e) = self.profiles.validate_slots() {
+                log::warn!("Invalid resource slots: {}", e);
+            }
             Ok(true)
         }
     }
+
+    fn slots(&self) -> HashMap<String, Slot> {
+        self.profiles
+            .slots
+            .iter()
+            .map(|(name, slot)| {
+                let slot = Slot {
+                    resources: slot.resources.cap(&self.res_cap),
+                    count: slot.count,
+                };
+                (name.clone(), slot)
+            })
+            .collect()
+    }
+
+    fn check_slot(&self, name: &str) -> Result<Resources, Error> {
+        let slot = self
+            .profiles
+            .get_slot(name)
+            .ok_or_else(|| ProfileError::UnknownSlot(name.to_string()))?;
+        let used = self.slot_alloc.values().filter(|s| *s == name).count();
+        if used >= slot.count as usize {
+            return Err(Error::SlotsDepleted(name.to_string()));
+        }
+        // Reserve the same resources, that were advertised in Offer.
+        let resources = slot.resources.cap(&self.res_cap);
+        if self.res_remaining < resources {
+            return Err(Error::InsufficientResources);
+        }
+        Ok(resources)
+    }
+
+    fn allocate_slot(&mut self, id: String, name: &str) -> Result<(), Error> {
+        let res = self.check_slot(name)?;
+        self.allocate(id.clone(), res)?;
+        self.slot_alloc.insert(id, name.to_string());
+        Ok(())
+    }
+
+    fn allocate(&mut self, id: String, res: Resources) -> Result<(), Error> {
+        if self.res_alloc.contains_key(&id) {
+            return Err(Error::AlreadyAllocated(id));
+        }
+        if self.res_remaining < res {
+            return Err(Error::InsufficientResources);
+        }
+        self.res_remaining = self.res_remaining - res;
+        self.res_alloc.insert(id, res);
+        Ok(())
+    }
+
+    fn release(&mut self, id: String) -> Result<(), Error> {
+        match self.res_alloc.remove(&id) {
+            Some(res) => self.res_remaining = self.res_remaining + res,
+            _ => return Err(Error::NotAllocated(id)),
+        }
+        self.slot_alloc.remove(&id);
+        Ok(())
+    }
 }
 
 impl Manager {
@@ -377,6 +526,7 @@ impl Manager {
             res_cap: Resources::new_empty(),
             res_remaining: Resources::new_empty(),
             res_alloc: HashMap::new(),
+            slot_alloc: HashMap::new(),
         };
         state.change_profile(state.profiles.active.clone())?;
 
@@ -415,6 +565,13 @@ impl Manager {
     pub fn event_receiver(&self) -> watch::Receiver<Event> {
         self.receiver.clone()
     }
+
+    /// Handle allowing to allocate resources outside of `Manager`.
+    pub fn allocator(&self) -> Allocator {
+        Allocator {
+            state: self.state.clone(),
+        }
+    }
 }
 
 impl Manager {
@@ -424,28 +581,59 @@ impl Manager {
         state.res_cap
     }
 
+    /// Resource slots of active profile, capped by available hardware.
+    pub fn slots(&self) -> HashMap<String, Slot> {
+        self.state.lock().unwrap().slots()
+    }
+
     #[allow(dead_code)]
     pub fn allocate(&mut self, id: String, res: Resources) -> Result<(), Error> {
-        let mut state = self.state.lock().unwrap();
-        if state.res_alloc.contains_key(&id) {
-            return Err(Error::AlreadyAllocated(id));
-        }
-        if state.res_remaining < res {
-            return Err(Error::InsufficientResources);
-        }
-        state.res_remaining = state.res_remaining - res;
-        state.res_alloc.insert(id, res);
-        Ok(())
+        self.state.lock().unwrap().allocate(id, res)
     }
 
     #[allow(dead_code)]
     pub fn release(&mut self, id: String) -> Result<(), Error> {
-        let mut state = self.state.lock().unwrap();
-        match state.res_alloc.remove(&id) {
-            Some(res) => state.res_remaining = state.res_remaining + res,
-            _ => return Err(Error::NotAllocated(id)),
-        }
-        Ok(())
+        self.state.lock().unwrap().release(id)
+    }
+}
+
+/// Allocates resources from the active profile. Shares state with `Manager`,
+/// so profile changes are reflected in remaining resources.
+#[derive(Clone, Debug)]
+pub struct Allocator {
+    state: Arc<Mutex<ManagerState>>,
+}
+
+impl Allocator {
+    pub fn remaining(&self) -> Resources {
+        self.state.lock().unwrap().res_remaining
+    }
+
+    pub fn can_allocate(&self, res: &Resources) -> bool {
+        self.remaining() >= *res
+    }
+
+    pub fn allocate(&self, id: String, res: Resources) -> Result<(), Error> {
+        self.state.lock().unwrap().allocate(id, res)
+    }
+
+    /// Checks if slot is defined and not all of its instances are allocated.
+    pub fn can_allocate_slot(&self, name: &str) -> Result<(), Error> {
+        self.state.lock().unwrap().check_slot(name).map(|_| ())
+    }
+
+    pub fn allocate_slot(&self, id: String, name: &str) -> Result<(), Error> {
+        self.state.lock().unwrap().allocate_slot(id, name)
+    }
+
+    pub fn release(&self, id: String) -> Result<(), Error> {
+        self.state.lock().unwrap().release(id)
+    }
+
+    /// Total number of slot instances in active profile.
+    pub fn slots_count(&self) -> u32 {
+        let state = self.state.lock().unwrap();
+        state.profiles.slots.values().map(|slot| slot.count).sum()
     }
 }
 
@@ -515,7 +703,11 @@ mod tests {
             storage_gib: 100.,
         };
         let profiles = vec![(active.clone(), resources)].into_iter().collect();
-        Profiles { active, profiles }
+        Profiles {
+            active,
+            profiles,
+            slots: HashMap::new(),
+        }
     }
 
     #[test]
@@ -584,6 +776,7 @@ mod tests {
             res_cap: res,
             res_remaining: res,
             res_alloc: HashMap::new(),
+            slot_alloc: HashMap::new(),
             profiles: profiles(),
         };
         let (tx, rx) = watch::channel(Event::Initialized);
@@ -624,6 +817,7 @@ mod tests {
             res_cap: res,
             res_remaining: res,
             res_alloc: HashMap::new(),
+            slot_alloc: HashMap::new(),
             profiles: profiles(),
         };
         let (tx, rx) = watch::channel(Event::Initialized);
@@ -653,4 +847,131 @@ mod tests {
             )
             .is_err());
     }
+
+    #[test]
+    fn allocator_shares_state() {
+        let res = Resources {
+            cpu_threads: 8,
+            mem_gib: 24.,
+            storage_gib: 200.,
+        };
+        let state = ManagerState {
+            res_available: res,
+            res_cap: res,
+            res_remaining: res,
+            res_alloc: HashMap::new(),
+            slot_alloc: HashMap::new(),
+            profiles: profiles(),
+        };
+        let (tx, rx) = watch::channel(Event::Initialized);
+        let mut man = Manager {
+            state: Arc::new(Mutex::new(state)),
+            monitor: None,
+            sender: Some(tx),
+            receiver: rx,
+        };
+        let allocator = man.allocator();
+        let alloc = Resources {
+            cpu_threads: 6,
+            mem_gib: 16.,
+            storage_gib: 100.,
+        };
+
+        assert!(allocator.can_allocate(&alloc));
+        allocator.allocate("1".into(), alloc).unwrap();
+        assert!(!allocator.can_allocate(&alloc));
+        assert!(man.allocate("2".into(), alloc).is_err());
+
+        man.release("1".into()).unwrap();
+        assert!(allocator.can_allocate(&alloc));
+        assert_eq!(allocator.remaining(), res);
+    }
+
+    #[test]
+    fn slots() {
+        let slot = Slot {
+            resources: Resources {
+                cpu_threads: 1,
+                mem_gib: 2.,
+                storage_gib: 20.,
+            },
+            count: 3,
+        };
+        let mut profiles = profiles();
+        profiles.set_slot("small", slot).unwrap();
+        assert!(profiles
+            .set_slot("large", Slot { count: 2, ..slot })
+            .is_err());
+        assert!(profiles.get_slot("large").is_none());
+
+        let res = profiles.get(DEFAULT_PROFILE_NAME).cloned().unwrap();
+        let state = ManagerState {
+            res_available: res,
+            res_cap: res,
+            res_remaining: res,
+            res_alloc: HashMap::new(),
+            slot_alloc: HashMap::new(),
+            profiles,
+        };
+        let (tx, rx) = watch::channel(Event::Initialized);
+        let man = Manager {
+            state: Arc::new(Mutex::new(state)),
+            monitor: None,
+            sender: Some(tx),
+            receiver: rx,
+        };
+        let allocator = man.allocator();
+
+        allocator.allocate_slot("1".into(), "small").unwrap();
+        allocator.allocate_slot("2".into(), "small").unwrap();
+        allocator.allocate_slot("3".into(), "small").unwrap();
+        assert!(allocator.can_allocate_slot("small").is_err());
+        assert!(allocator.can_allocate_slot("large").is_err());
+
+        allocator.release("2".into()).unwrap();
+        assert!(allocator.can_allocate_slot("small").is_ok());
+        assert_eq!(allocator.remaining().cpu_threads, 2);
+        assert_eq!(allocator.slots_count(), 3);
+    }
+
+    #[test]
+    fn capped_slots() {
+        let slot = Slot {
+            resources: Resources {
+                cpu_threads: 2,
+                mem_gib: 2.,
+                storage_gib: 20.,
+            },
+            count: 1,
+        };
+        let mut profiles = profiles();
+        profiles.set_slot("small", slot).unwrap();
+
+        let res = profiles.get(DEFAULT_PROFILE_NAME).cloned().unwrap();
+        let cap = Resources {
+            cpu_threads: 1,
+            ..res
+        };
+        let state = ManagerState {
+            res_available: cap,
+            res_cap: cap,
+            res_remaining: cap,
+            res_alloc: HashMap::new(),
+            slot_alloc: HashMap::new(),
+            profiles,
+        };
+        let (tx, rx) = watch::channel(Event::Initialized);
+        let man = Manager {
+            state: Arc::new(Mutex::new(state)),
+            monitor: None,
+            sender: Some(tx),
+            receiver: rx,
+        };
+        let allocator = man.allocator();
+
+        // Slot advertises and reserves resources capped by hardware.
+        assert_eq!(man.slots()["small"].resources.cpu_threads, 1);
+        allocator.allocate_slot("1".into(), "small").unwrap();
+        assert_eq!(allocator.remaining().cpu_threads, 0);
+    }
 }
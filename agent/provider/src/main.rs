@@ -26,6 +26,8 @@ async fn main() -> anyhow::Result<()> {
     config.globals_file = data_dir.join(config.globals_file);
     config.presets_file = data_dir.join(config.presets_file);
     config.hardware_file = data_dir.join(config.hardware_file);
+    config.requestors_file = data_dir.join(config.requestors_file);
+    config.requestors_usage_file = data_dir.join(config.requestors_usage_file);
 
     match cli_args.commands {
         Commands::Run(args) => {
@@ -45,6 +47,8 @@ async fn main() -> anyhow::Result<()> {
         Commands::ExeUnit(exe_unit_cmd) => exe_unit_cmd.run(config),
         Commands::Keystore(keystore_cmd) => keystore_cmd.run(config),
         Commands::Whitelist(whitelist_cmd) => whitelist_cmd.run(config),
+        Commands::Requestors(requestors_cmd) => requestors_cmd.run(config),
+        Commands::Status(status_cmd) => status_cmd.run(config),
         Commands::Clean(clean_cmd) => clean_cmd.run(config),
     }
 }
@@ -22,4 +22,11 @@ pub struct MarketConfig {
     pub process_market_events_timeout: std::time::Duration,
     #[structopt(skip)]
     pub keystore: Keystore,
+    /// How often Offers are checked against availability windows of presets.
+    #[structopt(long, env, parse(try_from_str = humantime::parse_duration), default_value = "1min")]
+    pub availability_check_interval: std::time::Duration,
+    /// If set, Agreements are terminated this long before availability window
+    /// of their preset ends. New Agreements aren't accepted within this period.
+    #[structopt(long, env, parse(try_from_str = humantime::parse_duration))]
+    pub terminate_before_window_end: Option<std::time::Duration>,
 }
@@ -1,11 +1,19 @@
 pub mod expiration;
+pub mod external;
 mod manifest;
 pub mod max_agreements;
 pub mod note_interval;
 pub mod payment_timeout;
+pub mod requestor_filter;
+pub mod requestor_limits;
+pub mod resources;
 
 pub use expiration::LimitExpiration;
+pub use external::ExternalNegotiator;
 pub use manifest::ManifestSignature;
 pub use max_agreements::MaxAgreements;
 pub use note_interval::DebitNoteInterval;
 pub use payment_timeout::PaymentTimeout;
+pub use requestor_filter::RequestorFilter;
+pub use requestor_limits::RequestorLimiter;
+pub use resources::ResourceReservation;
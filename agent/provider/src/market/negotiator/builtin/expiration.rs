@@ -200,6 +200,7 @@ mod test_expiration_negotiator {
     use super::*;
     use ya_agreement_utils::agreement::expand;
     use ya_agreement_utils::{InfNodeInfo, NodeInfo, OfferTemplate, ServiceInfo};
+    use ya_client::model::NodeId;
 
     fn expiration_config() -> AgreementExpirationNegotiatorConfig {
         AgreementExpirationNegotiatorConfig {
@@ -211,10 +212,11 @@ mod test_expiration_negotiator {
     }
 
     fn properties_to_proposal(value: serde_json::Value) -> ProposalView {
-        ProposalView {
-            agreement_id: "2332850934yer".to_string(),
-            json: expand(value),
-        }
+        ProposalView::new(
+            "2332850934yer".to_string(),
+            expand(value),
+            NodeId::default(),
+        )
     }
 
     fn example_offer() -> OfferDefinition {
@@ -232,10 +234,11 @@ mod test_expiration_negotiator {
 
     impl ToProposal for OfferDefinition {
         fn to_proposal(self) -> ProposalView {
-            ProposalView {
-                agreement_id: "sagdshgdfgd".to_string(),
-                json: expand(self.into_json()),
-            }
+            ProposalView::new(
+                "sagdshgdfgd".to_string(),
+                expand(self.into_json()),
+                NodeId::default(),
+            )
         }
     }
 
@@ -0,0 +1,351 @@
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use ya_agreement_utils::agreement::{expand, flatten_value};
+use ya_agreement_utils::{OfferDefinition, OfferTemplate};
+use ya_client::model::NodeId;
+
+use crate::market::negotiator::factory::ExternalNegotiatorConfig;
+use crate::market::negotiator::{
+    AgreementResult, NegotiationResult, NegotiatorComponent, ProposalView,
+};
+
+/// Request sent to external negotiator as single line of json.
+/// Properties are always passed in flat form.
+#[derive(Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum Request<'a> {
+    NegotiateStep {
+        proposal_id: &'a str,
+        requestor_id: &'a NodeId,
+        demand: Value,
+        offer: Value,
+    },
+    FillTemplate {
+        properties: Value,
+        constraints: String,
+    },
+    AgreementApproved {
+        agreement_id: &'a str,
+    },
+    AgreementTerminated {
+        agreement_id: &'a str,
+        result: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+}
+
+/// Response for `negotiate_step`. If `offer` is missing,
+/// our Proposal is left unchanged.
+#[derive(Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+enum StepResponse {
+    Ready {
+        offer: Option<Value>,
+    },
+    Negotiating {
+        offer: Option<Value>,
+    },
+    Reject {
+        message: String,
+        #[serde(default)]
+        is_final: bool,
+    },
+}
+
+/// Response for `fill_template`. Properties and constraints are added to Offer.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct TemplateResponse {
+    properties: serde_json::Map<String, Value>,
+    constraints: String,
+}
+
+struct Process {
+    child: Child,
+    stdin: ChildStdin,
+    responses: Receiver<std::io::Result<String>>,
+}
+
+impl Process {
+    fn spawn(config: &ExternalNegotiatorConfig, path: &std::path::Path) -> anyhow::Result<Self> {
+        let mut child = Command::new(path)
+            .args(&config.external_negotiator_args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| anyhow!("Failed to spawn external negotiator {:?}: {}", path, e))?;
+
+        let stdin = child.stdin.take().ok_or_else(|| anyhow!("No stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| anyhow!("No stdout"))?;
+
+        // Reading in separate thread allows us to wait for responses with timeout.
+        let (sender, responses) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        log::info!(
+            "Started external negotiator {:?} [pid {}].",
+            path,
+            child.id()
+        );
+        Ok(Process {
+            child,
+            stdin,
+            responses,
+        })
+    }
+
+    fn call(&mut self, request: &Request, timeout: Duration) -> anyhow::Result<Value> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes())?;
+        self.stdin.flush()?;
+
+        let line = match self.responses.recv_timeout(timeout) {
+            Ok(line) => line?,
+            Err(RecvTimeoutError::Timeout) => bail!("No response within {:?}", timeout),
+            Err(RecvTimeoutError::Disconnected) => bail!("Process closed stdout"),
+        };
+
+        serde_json::from_str(&line).map_err(|e| anyhow!("Invalid response [{}]: {}", line, e))
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+/// Negotiator delegating decisions to external executable. Requests and responses
+/// are exchanged as json lines through process stdin and stdout.
+/// Process is restarted, if it fails to respond. Errors reported by process
+/// in `{"error": ..}` response don't break communication, so they are only passed
+/// to the caller.
+pub struct ExternalNegotiator {
+    config: ExternalNegotiatorConfig,
+    process: Option<Process>,
+}
+
+impl ExternalNegotiator {
+    pub fn new(config: &ExternalNegotiatorConfig) -> anyhow::Result<ExternalNegotiator> {
+        let mut negotiator = ExternalNegotiator {
+            config: config.clone(),
+            process: None,
+        };
+        negotiator.process = Some(negotiator.spawn()?);
+        Ok(negotiator)
+    }
+
+    fn spawn(&self) -> anyhow::Result<Process> {
+        let path = self
+            .config
+            .external_negotiator
+            .as_ref()
+            .ok_or_else(|| anyhow!("External negotiator path not set"))?;
+        Process::spawn(&self.config, path)
+    }
+
+    fn call(&mut self, request: Request) -> anyhow::Result<Value> {
+        let process = match self.process.as_mut() {
+            Some(process) => process,
+            None => self.process.insert(self.spawn()?),
+        };
+
+        let response = match process.call(&request, self.config.external_negotiator_timeout) {
+            Ok(response) => response,
+            Err(e) => {
+                // We can't be sure, that late response won't be taken as
+                // response for the next request, so we start from scratch.
+                log::warn!("External negotiator failed: {}. Restarting.", e);
+                self.process = None;
+                return Err(e);
+            }
+        };
+
+        if let Some(error) = response.get("error") {
+            bail!("External negotiator error: {}", error);
+        }
+        Ok(response)
+    }
+}
+
+impl NegotiatorComponent for ExternalNegotiator {
+    fn negotiate_step(
+        &mut self,
+        demand: &ProposalView,
+        offer: ProposalView,
+    ) -> anyhow::Result<NegotiationResult> {
+        let response = self.call(Request::NegotiateStep {
+            proposal_id: &demand.agreement_id,
+            requestor_id: &demand.issuer,
+            demand: flatten_value(demand.json.clone()),
+            offer: flatten_value(offer.json.clone()),
+        })?;
+
+        let update = |mut offer: ProposalView, properties: Option<Value>| {
+            if let Some(properties) = properties {
+                offer.json = expand(properties);
+            }
+            offer
+        };
+
+        Ok(match serde_json::from_value(response)? {
+            StepResponse::Ready { offer: properties } => NegotiationResult::Ready {
+                offer: update(offer, properties),
+            },
+            StepResponse::Negotiating { offer: properties } => NegotiationResult::Negotiating {
+                offer: update(offer, properties),
+            },
+            StepResponse::Reject { message, is_final } => {
+                log::info!(
+                    "External negotiator rejected Proposal [{}]: {}",
+                    demand.agreement_id,
+                    message
+                );
+                NegotiationResult::Reject { message, is_final }
+            }
+        })
+    }
+
+    fn fill_template(
+        &mut self,
+        mut offer_template: OfferDefinition,
+    ) -> anyhow::Result<OfferDefinition> {
+        let template = offer_template.clone().into_template();
+        let response = self.call(Request::FillTemplate {
+            properties: template.properties,
+            constraints: template.constraints,
+        })?;
+
+        let response: TemplateResponse = serde_json::from_value(response)?;
+        let mut additions = OfferTemplate::new(Value::Object(response.properties));
+        additions.constraints = response.constraints;
+        offer_template.offer = offer_template.offer.patch(additions);
+        Ok(offer_template)
+    }
+
+    fn on_agreement_terminated(
+        &mut self,
+        agreement_id: &str,
+        result: &AgreementResult,
+    ) -> anyhow::Result<()> {
+        let (result, reason) = match result {
+            AgreementResult::ApprovalFailed => ("approval_failed", None),
+            AgreementResult::ClosedByUs => ("closed_by_us", None),
+            AgreementResult::ClosedByRequestor => ("closed_by_requestor", None),
+            AgreementResult::Broken { reason } => ("broken", Some(reason.to_string())),
+        };
+        self.call(Request::AgreementTerminated {
+            agreement_id,
+            result,
+            reason,
+        })?;
+        Ok(())
+    }
+
+    fn on_agreement_approved(&mut self, agreement_id: &str) -> anyhow::Result<()> {
+        self.call(Request::AgreementApproved { agreement_id })?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn negotiator(script: &str) -> ExternalNegotiator {
+        let config = ExternalNegotiatorConfig {
+            external_negotiator: Some(PathBuf::from("sh")),
+            external_negotiator_args: vec!["-c".to_string(), script.to_string()],
+            external_negotiator_timeout: Duration::from_secs(5),
+        };
+        ExternalNegotiator::new(&config).unwrap()
+    }
+
+    fn proposal(properties: Value) -> ProposalView {
+        ProposalView::new(
+            "proposal-id".to_string(),
+            expand(properties),
+            NodeId::default(),
+        )
+    }
+
+    #[test]
+    fn test_external_negotiator_ready() {
+        let mut negotiator = negotiator(
+            r#"while read line; do echo '{"result": "ready", "offer": {"golem.x": 2}}'; done"#,
+        );
+
+        let result = negotiator
+            .negotiate_step(
+                &proposal(json!({"golem.y": 1})),
+                proposal(json!({"golem.x": 1})),
+            )
+            .unwrap();
+        assert_eq!(
+            result,
+            NegotiationResult::Ready {
+                offer: proposal(json!({"golem.x": 2}))
+            }
+        );
+    }
+
+    #[test]
+    fn test_external_negotiator_reject() {
+        let mut negotiator = negotiator(
+            r#"while read line; do echo '{"result": "reject", "message": "Not allowed"}'; done"#,
+        );
+
+        let result = negotiator
+            .negotiate_step(
+                &proposal(json!({"golem.y": 1})),
+                proposal(json!({"golem.x": 1})),
+            )
+            .unwrap();
+        assert_eq!(
+            result,
+            NegotiationResult::Reject {
+                message: "Not allowed".to_string(),
+                is_final: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_external_negotiator_error() {
+        let mut negotiator = negotiator(r#"while read line; do echo '{"error": "Failure"}'; done"#);
+        let pid = negotiator.process.as_ref().unwrap().child.id();
+
+        assert!(negotiator.on_agreement_approved("agreement-id").is_err());
+        assert!(negotiator.on_agreement_approved("agreement-id").is_err());
+        // Error response shouldn't cause restart.
+        assert_eq!(negotiator.process.as_ref().unwrap().child.id(), pid);
+    }
+
+    #[test]
+    fn test_external_negotiator_restart() {
+        let mut negotiator = negotiator(r#"read line; echo '{}'"#);
+        assert!(negotiator.on_agreement_approved("agreement-id").is_ok());
+        // Process exited, so call fails and process will be spawned again.
+        assert!(negotiator.on_agreement_approved("agreement-id").is_err());
+        assert!(negotiator.process.is_none());
+        assert!(negotiator.on_agreement_approved("agreement-id").is_ok());
+    }
+}
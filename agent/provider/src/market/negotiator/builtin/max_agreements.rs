@@ -3,27 +3,44 @@ use std::collections::HashSet;
 
 use ya_agreement_utils::OfferDefinition;
 
+use crate::hardware::Allocator;
 use crate::market::negotiator::factory::LimitAgreementsNegotiatorConfig;
 use crate::market::negotiator::{
     AgreementResult, NegotiationResult, NegotiatorComponent, ProposalView,
 };
 
 /// Negotiator that can limit number of running agreements.
+/// If resource slots are configured, limit is raised to total number of slots,
+/// so all of them can be used in parallel.
 pub struct MaxAgreements {
     active_agreements: HashSet<String>,
     max_agreements: u32,
+    hardware: Option<Allocator>,
 }
 
 impl MaxAgreements {
-    pub fn new(config: &LimitAgreementsNegotiatorConfig) -> MaxAgreements {
+    pub fn new(
+        config: &LimitAgreementsNegotiatorConfig,
+        hardware: Option<Allocator>,
+    ) -> MaxAgreements {
         MaxAgreements {
             max_agreements: config.max_simultaneous_agreements,
             active_agreements: HashSet::new(),
+            hardware,
         }
     }
 
+    pub fn max_agreements(&self) -> u32 {
+        let slots = self
+            .hardware
+            .as_ref()
+            .map(|allocator| allocator.slots_count())
+            .unwrap_or(0);
+        self.max_agreements.max(slots)
+    }
+
     pub fn has_free_slot(&self) -> bool {
-        self.active_agreements.len() < self.max_agreements as usize
+        self.active_agreements.len() < self.max_agreements() as usize
     }
 }
 
@@ -43,7 +60,7 @@ impl NegotiatorComponent for MaxAgreements {
             Ok(NegotiationResult::Reject {
                 message: format!(
                     "No capacity available. Reached Agreements limit: {}",
-                    self.max_agreements
+                    self.max_agreements()
                 ),
                 is_final: false,
             })
@@ -64,7 +81,8 @@ impl NegotiatorComponent for MaxAgreements {
     ) -> anyhow::Result<()> {
         self.active_agreements.remove(agreement_id);
 
-        let free_slots = self.max_agreements as usize - self.active_agreements.len();
+        let free_slots =
+            (self.max_agreements() as usize).saturating_sub(self.active_agreements.len());
         log::info!("Negotiator: {} free slot(s) for agreements.", free_slots);
         Ok(())
     }
@@ -0,0 +1,64 @@
+use ya_agreement_utils::OfferDefinition;
+
+use crate::config::requestors::RequestorListsState;
+use crate::market::negotiator::{
+    AgreementResult, NegotiationResult, NegotiatorComponent, ProposalView,
+};
+
+/// Negotiator rejecting Demands from Requestors denied by Provider
+/// or not present on allowed list.
+pub struct RequestorFilter {
+    lists: RequestorListsState,
+}
+
+impl RequestorFilter {
+    pub fn new(lists: RequestorListsState) -> RequestorFilter {
+        RequestorFilter { lists }
+    }
+}
+
+impl NegotiatorComponent for RequestorFilter {
+    fn negotiate_step(
+        &mut self,
+        demand: &ProposalView,
+        offer: ProposalView,
+    ) -> anyhow::Result<NegotiationResult> {
+        if self.lists.is_empty() {
+            return Ok(NegotiationResult::Ready { offer });
+        }
+
+        match self.lists.check(&demand.issuer) {
+            Ok(()) => Ok(NegotiationResult::Ready { offer }),
+            Err(message) => {
+                log::info!(
+                    "'RequestorFilter' negotiator: Reject proposal [{}]. {}",
+                    demand.agreement_id,
+                    message
+                );
+                Ok(NegotiationResult::Reject {
+                    message,
+                    is_final: true,
+                })
+            }
+        }
+    }
+
+    fn fill_template(
+        &mut self,
+        offer_template: OfferDefinition,
+    ) -> anyhow::Result<OfferDefinition> {
+        Ok(offer_template)
+    }
+
+    fn on_agreement_terminated(
+        &mut self,
+        _agreement_id: &str,
+        _result: &AgreementResult,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn on_agreement_approved(&mut self, _agreement_id: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::anyhow;
+use chrono::Utc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use ya_agreement_utils::OfferDefinition;
+use ya_client::model::NodeId;
+
+use crate::config::globals::GlobalsState;
+use crate::config::requestors::RequestorsUsage;
+use crate::market::negotiator::{
+    AgreementResult, NegotiationResult, NegotiatorComponent, ProposalView,
+};
+
+/// Negotiator limiting number of simultaneous and new Agreements per Requestor,
+/// so single Requestor can't take all Provider's capacity.
+/// Limits are taken from global configuration and can change at runtime.
+pub struct RequestorLimiter {
+    globals: Arc<Mutex<GlobalsState>>,
+    usage: RequestorsUsage,
+    usage_file: PathBuf,
+    /// Requestor, that passed last negotiation step. Agreement approval
+    /// is always preceded by negotiation step for this Agreement.
+    last_requestor: Option<NodeId>,
+}
+
+impl RequestorLimiter {
+    pub fn new(globals: Arc<Mutex<GlobalsState>>, usage_file: PathBuf) -> RequestorLimiter {
+        let mut usage = RequestorsUsage::load(&usage_file).unwrap_or_else(|e| {
+            log::warn!(
+                "Failed to load Requestors usage from {:?}: {}",
+                usage_file,
+                e
+            );
+            RequestorsUsage::default()
+        });
+        usage.clear_active(Utc::now());
+
+        let limiter = RequestorLimiter {
+            globals,
+            usage,
+            usage_file,
+            last_requestor: None,
+        };
+        limiter.save();
+        limiter
+    }
+
+    fn save(&self) {
+        if let Err(e) = self.usage.save(&self.usage_file) {
+            log::warn!(
+                "Failed to save Requestors usage to {:?}: {}",
+                self.usage_file,
+                e
+            );
+        }
+    }
+}
+
+impl NegotiatorComponent for RequestorLimiter {
+    fn negotiate_step(
+        &mut self,
+        demand: &ProposalView,
+        offer: ProposalView,
+    ) -> anyhow::Result<NegotiationResult> {
+        let limits = self.globals.lock().unwrap().requestor_limits();
+        let requestor = demand.issuer;
+
+        match self.usage.check(&requestor, &limits, Utc::now()) {
+            Ok(()) => {
+                self.last_requestor = Some(requestor);
+                Ok(NegotiationResult::Ready { offer })
+            }
+            Err(message) => {
+                log::info!(
+                    "'RequestorLimits' negotiator: Reject proposal [{}] from [{}]. {}",
+                    demand.agreement_id,
+                    requestor,
+                    message
+                );
+                Ok(NegotiationResult::Reject {
+                    message,
+                    is_final: false,
+                })
+            }
+        }
+    }
+
+    fn fill_template(
+        &mut self,
+        offer_template: OfferDefinition,
+    ) -> anyhow::Result<OfferDefinition> {
+        Ok(offer_template)
+    }
+
+    fn on_agreement_terminated(
+        &mut self,
+        agreement_id: &str,
+        result: &AgreementResult,
+    ) -> anyhow::Result<()> {
+        match result {
+            AgreementResult::ApprovalFailed => self.usage.approval_failed(agreement_id, Utc::now()),
+            _ => self.usage.terminate(agreement_id, Utc::now()),
+        }
+        self.save();
+        Ok(())
+    }
+
+    fn on_agreement_approved(&mut self, agreement_id: &str) -> anyhow::Result<()> {
+        let requestor = self
+            .last_requestor
+            .take()
+            .ok_or_else(|| anyhow!("Unknown Requestor of Agreement [{}].", agreement_id))?;
+
+        self.usage.approve(requestor, agreement_id, Utc::now());
+        self.save();
+        Ok(())
+    }
+}
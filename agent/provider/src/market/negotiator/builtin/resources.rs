@@ -0,0 +1,151 @@
+use anyhow::anyhow;
+
+use ya_agreement_utils::OfferDefinition;
+
+use crate::hardware::{Allocator, Error, Resources};
+use crate::market::negotiator::{
+    AgreementResult, NegotiationResult, NegotiatorComponent, ProposalView,
+};
+
+const CPU_THREADS_POINTER: &str = "/golem/inf/cpu/threads";
+const MEM_GIB_POINTER: &str = "/golem/inf/mem/gib";
+const STORAGE_GIB_POINTER: &str = "/golem/inf/storage/gib";
+const SLOT_POINTER: &str = "/golem/inf/slot";
+
+#[derive(Clone, Debug)]
+enum Reservation {
+    Resources(Resources),
+    Slot(String),
+}
+
+/// Negotiator reserving hardware resources declared in Offer, when Agreement
+/// is approved. Offers bound to resource slot reserve one instance of the slot.
+/// Rejects Proposals, if active hardware profile doesn't have enough resources left.
+pub struct ResourceReservation {
+    allocator: Allocator,
+    /// Reservation for Offer, that passed last negotiation step. Agreement approval
+    /// is always preceded by negotiation step for this Agreement.
+    last_reservation: Option<Reservation>,
+}
+
+impl ResourceReservation {
+    pub fn new(allocator: Allocator) -> ResourceReservation {
+        ResourceReservation {
+            allocator,
+            last_reservation: None,
+        }
+    }
+
+    fn check(&self, reservation: &Reservation) -> Result<(), String> {
+        match reservation {
+            Reservation::Resources(resources) => match self.allocator.can_allocate(resources) {
+                true => Ok(()),
+                false => Err(format!(
+                    "Insufficient resources: {:?} remaining.",
+                    self.allocator.remaining()
+                )),
+            },
+            Reservation::Slot(name) => self
+                .allocator
+                .can_allocate_slot(name)
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+fn requested_reservation(offer: &ProposalView) -> anyhow::Result<Reservation> {
+    if let Ok(slot) = offer.pointer_typed::<String>(SLOT_POINTER) {
+        return Ok(Reservation::Slot(slot));
+    }
+    Ok(Reservation::Resources(offered_resources(offer)?))
+}
+
+fn offered_resources(offer: &ProposalView) -> anyhow::Result<Resources> {
+    Ok(Resources {
+        cpu_threads: offer.pointer_typed(CPU_THREADS_POINTER)?,
+        mem_gib: offer.pointer_typed(MEM_GIB_POINTER)?,
+        storage_gib: offer.pointer_typed(STORAGE_GIB_POINTER).unwrap_or(0.),
+    })
+}
+
+impl NegotiatorComponent for ResourceReservation {
+    fn negotiate_step(
+        &mut self,
+        demand: &ProposalView,
+        offer: ProposalView,
+    ) -> anyhow::Result<NegotiationResult> {
+        let reservation = requested_reservation(&offer)
+            .map_err(|e| anyhow!("Can't find resources declared in Offer. {}", e))?;
+
+        match self.check(&reservation) {
+            Ok(()) => {
+                self.last_reservation = Some(reservation);
+                Ok(NegotiationResult::Ready { offer })
+            }
+            Err(reason) => {
+                log::info!(
+                    "'ResourceReservation' negotiator: Reject proposal [{}]. {}",
+                    demand.agreement_id,
+                    reason
+                );
+                Ok(NegotiationResult::Reject {
+                    message: "No capacity available. Hardware resources are already reserved."
+                        .to_string(),
+                    is_final: false,
+                })
+            }
+        }
+    }
+
+    fn fill_template(
+        &mut self,
+        offer_template: OfferDefinition,
+    ) -> anyhow::Result<OfferDefinition> {
+        Ok(offer_template)
+    }
+
+    fn on_agreement_terminated(
+        &mut self,
+        agreement_id: &str,
+        _result: &AgreementResult,
+    ) -> anyhow::Result<()> {
+        match self.allocator.release(agreement_id.to_string()) {
+            // Agreement could be terminated before approval.
+            Err(Error::NotAllocated(_)) => return Ok(()),
+            result => result?,
+        };
+        log::debug!(
+            "Released resources of Agreement [{}]. Remaining: {:?}",
+            agreement_id,
+            self.allocator.remaining()
+        );
+        Ok(())
+    }
+
+    fn on_agreement_approved(&mut self, agreement_id: &str) -> anyhow::Result<()> {
+        let reservation = self
+            .last_reservation
+            .take()
+            .ok_or_else(|| anyhow!("Unknown resources of Agreement [{}].", agreement_id))?;
+
+        let id = agreement_id.to_string();
+        let result = match &reservation {
+            Reservation::Resources(resources) => self.allocator.allocate(id, *resources),
+            Reservation::Slot(name) => self.allocator.allocate_slot(id, name),
+        };
+        result.map_err(|e| {
+            anyhow!(
+                "Agreement [{}] approved, but resources can't be reserved: {}",
+                agreement_id,
+                e
+            )
+        })?;
+        log::debug!(
+            "Reserved {:?} for Agreement [{}]. Remaining: {:?}",
+            reservation,
+            agreement_id,
+            self.allocator.remaining()
+        );
+        Ok(())
+    }
+}
@@ -1,12 +1,46 @@
 use anyhow::anyhow;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
 
 use ya_agreement_utils::{AgreementView, OfferDefinition};
+use ya_client::model::NodeId;
 
 use crate::market::negotiator::AgreementResult;
 
-pub type ProposalView = AgreementView;
+/// Proposal content passed to `NegotiatorComponent`s. Node id of Proposal
+/// issuer isn't part of Proposal properties, so it is passed in separate field.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalView {
+    pub content: AgreementView,
+    pub issuer: NodeId,
+}
+
+impl ProposalView {
+    pub fn new(id: String, properties: serde_json::Value, issuer: NodeId) -> ProposalView {
+        ProposalView {
+            content: AgreementView {
+                json: properties,
+                agreement_id: id,
+            },
+            issuer,
+        }
+    }
+}
+
+impl Deref for ProposalView {
+    type Target = AgreementView;
+
+    fn deref(&self) -> &AgreementView {
+        &self.content
+    }
+}
+
+impl DerefMut for ProposalView {
+    fn deref_mut(&mut self) -> &mut AgreementView {
+        &mut self.content
+    }
+}
 
 /// Result returned by `NegotiatorComponent` during Proposals evaluation.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -7,7 +7,8 @@ use ya_agreement_utils::AgreementView;
 use ya_client::model::market::NewOffer;
 
 use super::builtin::{
-    DebitNoteInterval, LimitExpiration, ManifestSignature, MaxAgreements, PaymentTimeout,
+    DebitNoteInterval, ExternalNegotiator, LimitExpiration, ManifestSignature, MaxAgreements,
+    PaymentTimeout, RequestorFilter, RequestorLimiter, ResourceReservation,
 };
 use super::common::{offer_definition_to_offer, AgreementResponse, Negotiator, ProposalResponse};
 use super::{NegotiationResult, NegotiatorsPack};
@@ -28,10 +29,13 @@ impl CompositeNegotiator {
         _market: Addr<ProviderMarket>,
         config: &CompositeNegotiatorConfig,
     ) -> anyhow::Result<CompositeNegotiator> {
-        let components = NegotiatorsPack::default()
+        let mut components = NegotiatorsPack::default()
             .add_component(
                 "LimitAgreements",
-                Box::new(MaxAgreements::new(&config.limit_agreements_config)),
+                Box::new(MaxAgreements::new(
+                    &config.limit_agreements_config,
+                    config.hardware.clone(),
+                )),
             )
             .add_component(
                 "LimitExpiration",
@@ -48,7 +52,32 @@ impl CompositeNegotiator {
             .add_component(
                 "ManifestSignature",
                 Box::new(ManifestSignature::from(config.policy_config.clone())),
+            )
+            .add_component(
+                "RequestorFilter",
+                Box::new(RequestorFilter::new(config.requestor_lists.clone())),
+            )
+            .add_component(
+                "RequestorLimits",
+                Box::new(RequestorLimiter::new(
+                    config.globals.clone(),
+                    config.requestors_usage_file.clone(),
+                )),
+            );
+
+        if let Some(allocator) = &config.hardware {
+            components = components.add_component(
+                "ResourceReservation",
+                Box::new(ResourceReservation::new(allocator.clone())),
             );
+        }
+
+        if config.external_config.external_negotiator.is_some() {
+            components = components.add_component(
+                "External",
+                Box::new(ExternalNegotiator::new(&config.external_config)?),
+            );
+        }
 
         Ok(CompositeNegotiator { components })
     }
@@ -71,15 +100,17 @@ impl Handler<ReactToProposal> for CompositeNegotiator {
         // them from initial Offer.
         let constraints = msg.prev_proposal.constraints;
 
-        let proposal = ProposalView {
-            agreement_id: msg.demand.proposal_id,
-            json: expand(msg.demand.properties),
-        };
+        let proposal = ProposalView::new(
+            msg.demand.proposal_id,
+            expand(msg.demand.properties),
+            msg.demand.issuer_id,
+        );
 
-        let offer_proposal = ProposalView {
-            json: expand(msg.prev_proposal.properties),
-            agreement_id: msg.prev_proposal.proposal_id,
-        };
+        let offer_proposal = ProposalView::new(
+            msg.prev_proposal.proposal_id,
+            expand(msg.prev_proposal.properties),
+            msg.prev_proposal.issuer_id,
+        );
 
         let result = self.components.negotiate_step(&proposal, offer_proposal)?;
         match result {
@@ -94,7 +125,7 @@ impl Handler<ReactToProposal> for CompositeNegotiator {
             }
             NegotiationResult::Ready { offer } | NegotiationResult::Negotiating { offer } => {
                 let offer = NewOffer {
-                    properties: flatten_value(offer.json),
+                    properties: flatten_value(offer.content.json),
                     constraints,
                 };
                 Ok(ProposalResponse::CounterProposal { offer })
@@ -110,6 +141,8 @@ pub fn to_proposal_views(
     // TODO: We should get ProposalId here, but Agreement doen't store it anywhere.
     let offer_id = agreement.pointer_typed("/offer/offerId")?;
     let demand_id = agreement.pointer_typed("/demand/demandId")?;
+    let requestor_id = agreement.pointer_typed("/demand/requestorId")?;
+    let provider_id = agreement.pointer_typed("/offer/providerId")?;
     let offer_proposal = agreement
         .json
         .pointer_mut("/offer/properties")
@@ -122,16 +155,8 @@ pub fn to_proposal_views(
         .map(Value::take)
         .unwrap_or(Value::Null);
 
-    let offer_proposal = ProposalView {
-        json: offer_proposal,
-        agreement_id: offer_id,
-    };
-
-    let demand_proposal = ProposalView {
-        json: demand_proposal,
-        agreement_id: demand_id,
-    };
-
+    let offer_proposal = ProposalView::new(offer_id, offer_proposal, provider_id);
+    let demand_proposal = ProposalView::new(demand_id, demand_proposal, requestor_id);
     Ok((demand_proposal, offer_proposal))
 }
 
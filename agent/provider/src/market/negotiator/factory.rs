@@ -1,10 +1,15 @@
-use actix::Addr;
+use actix::{Addr, Arbiter};
 use humantime;
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use structopt::StructOpt;
 
 use ya_manifest_utils::PolicyConfig;
 
+use crate::config::globals::GlobalsState;
+use crate::config::requestors::RequestorListsState;
+use crate::hardware;
+
 use super::common::NegotiatorAddr;
 use crate::market::config::MarketConfig;
 use crate::market::negotiator::{AcceptAllNegotiator, CompositeNegotiator};
@@ -54,6 +59,20 @@ pub struct PaymentTimeoutConfig {
     pub payment_timeout_required_duration: std::time::Duration,
 }
 
+/// Configuration for negotiator delegating decisions to external process
+#[derive(StructOpt, Clone, Debug)]
+pub struct ExternalNegotiatorConfig {
+    /// Executable implementing negotiation logic. Communicates using json lines on stdin and stdout
+    #[structopt(long, env)]
+    pub external_negotiator: Option<PathBuf>,
+    /// Arguments passed to external negotiator executable
+    #[structopt(long, env, use_delimiter = true)]
+    pub external_negotiator_args: Vec<String>,
+    /// How long to wait for external negotiator response
+    #[structopt(long, env, parse(try_from_str = humantime::parse_duration), default_value = "5s")]
+    pub external_negotiator_timeout: std::time::Duration,
+}
+
 /// Configuration for LimitAgreements Negotiator.
 #[derive(StructOpt, Clone, Debug)]
 pub struct CompositeNegotiatorConfig {
@@ -67,6 +86,18 @@ pub struct CompositeNegotiatorConfig {
     pub payment_timeout_config: PaymentTimeoutConfig,
     #[structopt(flatten)]
     pub policy_config: PolicyConfig,
+    #[structopt(flatten)]
+    pub external_config: ExternalNegotiatorConfig,
+    #[structopt(skip)]
+    pub requestor_lists: RequestorListsState,
+    #[structopt(skip)]
+    pub globals: Arc<Mutex<GlobalsState>>,
+    #[structopt(skip)]
+    pub requestors_usage_file: PathBuf,
+    /// Hardware resources reserved for approved Agreements.
+    /// Reservation is disabled, if not set.
+    #[structopt(skip)]
+    pub hardware: Option<hardware::Allocator>,
 }
 
 #[derive(StructOpt, Clone, Debug)]
@@ -78,15 +109,36 @@ pub struct NegotiatorsConfig {
 pub fn create_negotiator(
     market: Addr<ProviderMarket>,
     config: &MarketConfig,
-) -> Arc<NegotiatorAddr> {
+) -> anyhow::Result<Arc<NegotiatorAddr>> {
     let negotiator = match &config.negotiator_type[..] {
-        "Composite" => NegotiatorAddr::from(
-            CompositeNegotiator::new(market, &config.negotiator_config.composite_config).unwrap(),
-        ),
+        "Composite" => start_composite(market, &config.negotiator_config.composite_config)?,
         "AcceptAll" => NegotiatorAddr::from(AcceptAllNegotiator::default()),
         _ => Default::default(),
     };
-    Arc::new(negotiator)
+    Ok(Arc::new(negotiator))
+}
+
+/// `NegotiatorComponent`s are synchronous and some of them (external negotiator)
+/// wait for responses from other processes. CompositeNegotiator gets its own
+/// arbiter, so slow components don't block other provider actors.
+fn start_composite(
+    market: Addr<ProviderMarket>,
+    config: &CompositeNegotiatorConfig,
+) -> anyhow::Result<NegotiatorAddr> {
+    let arbiter = Arbiter::new();
+    let config = config.clone();
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    arbiter.spawn_fn(move || {
+        let negotiator = CompositeNegotiator::new(market, &config).map(NegotiatorAddr::from);
+        sender.send(negotiator).ok();
+    });
+
+    let negotiator = receiver.recv()?;
+    if negotiator.is_err() {
+        arbiter.stop();
+    }
+    negotiator
 }
 
 impl Default for NegotiatorAddr {
@@ -8,6 +8,8 @@ use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use tokio::sync::watch;
 
+use ya_utils_scheduler::Schedule;
+
 pub use crate::config::presets::Presets;
 use crate::events::Event;
 use crate::execution::ExeUnitsRegistry;
@@ -22,6 +24,15 @@ pub struct Preset {
     pub pricing_model: String,
     pub initial_price: f64,
     pub usage_coeffs: HashMap<String, f64>,
+    /// Parameters of pricing models other than linear.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub pricing_params: HashMap<String, f64>,
+    /// Resource slot offered instead of whole hardware profile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<String>,
+    /// Time windows, when Offers for this preset are subscribed.
+    #[serde(default, skip_serializing_if = "Schedule::is_empty")]
+    pub availability: Schedule,
 }
 
 impl Preset {
@@ -225,6 +236,9 @@ impl Default for Preset {
             exeunit_name: "wasmtime".to_string(),
             pricing_model: "linear".to_string(),
             usage_coeffs,
+            pricing_params: Default::default(),
+            slot: None,
+            availability: Default::default(),
         }
     }
 }
@@ -235,6 +249,9 @@ impl PartialEq for Preset {
             && self.exeunit_name == other.exeunit_name
             && self.pricing_model == other.pricing_model
             && self.usage_coeffs == other.usage_coeffs
+            && self.pricing_params == other.pricing_params
+            && self.slot == other.slot
+            && self.availability == other.availability
     }
 }
 
@@ -272,6 +289,15 @@ fn display_preset(
         preset.pricing_model,
         width = align
     )?;
+    if let Some(slot) = &preset.slot {
+        writeln!(f, "{:width$}{}", "Resource slot:", slot, width = align)?;
+    }
+    if !preset.availability.is_empty() {
+        writeln!(f, "Availability:")?;
+        for window in preset.availability.windows.iter() {
+            writeln!(f, "    {}", window)?;
+        }
+    }
     writeln!(f, "Coefficients:")?;
 
     let exe_unit = registry.find_exeunit(&preset.exeunit_name).ok();
@@ -290,5 +316,12 @@ fn display_preset(
         )?;
     }
 
+    if !preset.pricing_params.is_empty() {
+        writeln!(f, "Pricing parameters:")?;
+        for (name, value) in preset.pricing_params.iter() {
+            writeln!(f, "    {:width$}{}", name, value, width = align_coeff)?;
+        }
+    }
+
     Ok(())
 }
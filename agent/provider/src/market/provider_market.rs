@@ -2,7 +2,7 @@ use actix::prelude::*;
 use actix::AsyncContext;
 use anyhow::{anyhow, Error, Result};
 use backoff::backoff::Backoff;
-use chrono::Utc;
+use chrono::{DateTime, Local, Utc};
 use derive_more::Display;
 use futures::prelude::*;
 use futures_util::FutureExt;
@@ -82,6 +82,18 @@ struct Subscription {
     offer: NewOffer,
 }
 
+/// Offer created for preset, which is outside of its availability window.
+#[derive(Debug, Clone, Message)]
+#[rtype(result = "Result<()>")]
+struct ScheduleOffer {
+    preset: Preset,
+    offer: NewOffer,
+}
+
+#[derive(Message)]
+#[rtype(result = "Result<()>")]
+struct CheckAvailability;
+
 #[derive(Message)]
 #[rtype(result = "Result<()>")]
 struct AgreementFinalized {
@@ -115,6 +127,10 @@ pub struct ProviderMarket {
     subscriptions: HashMap<String, Subscription>,
     postponed_demands: Vec<SubscriptionProposal>,
     config: Arc<MarketConfig>,
+    /// Offers waiting for availability window of their preset.
+    scheduled_offers: HashMap<String, ScheduleOffer>,
+    /// Presets of running Agreements with availability windows.
+    scheduled_agreements: HashMap<String, Preset>,
 
     /// External actors can listen on this signal.
     pub agreement_signed_signal: SignalSlot<NewAgreement>,
@@ -144,12 +160,23 @@ impl ProviderMarket {
             config: Arc::new(config),
             subscriptions: HashMap::new(),
             postponed_demands: Vec::new(),
+            scheduled_offers: HashMap::new(),
+            scheduled_agreements: HashMap::new(),
             agreement_signed_signal: SignalSlot::<NewAgreement>::default(),
             agreement_terminated_signal: SignalSlot::<CloseAgreement>::default(),
             handles: HashMap::new(),
         }
     }
 
+    /// Starts market actor together with configured negotiator.
+    pub fn try_start(api: MarketProviderApi, config: MarketConfig) -> Result<Addr<ProviderMarket>> {
+        let ctx = Context::new();
+        let mut market = ProviderMarket::new(api, config);
+        market.negotiator = factory::create_negotiator(ctx.address(), &market.config)
+            .map_err(|e| anyhow!("Failed to create negotiator. {}", e))?;
+        Ok(ctx.run(market))
+    }
+
     fn async_context(&self, ctx: &mut Context<Self>) -> AsyncCtx {
         AsyncCtx {
             config: self.config.clone(),
@@ -181,11 +208,41 @@ impl ProviderMarket {
 
     fn on_agreement_approved(&mut self, msg: NewAgreement, _ctx: &mut Context<Self>) -> Result<()> {
         log::info!("Got approved agreement [{}].", msg.agreement.agreement_id,);
+
+        let offer_id = msg.agreement.pointer_typed::<String>("/offer/offerId").ok();
+        if let Some(subscription) = offer_id.and_then(|id| self.subscriptions.get(&id)) {
+            if !subscription.preset.availability.is_empty() {
+                self.scheduled_agreements.insert(
+                    msg.agreement.agreement_id.clone(),
+                    subscription.preset.clone(),
+                );
+            }
+        }
         // At this moment we only forward agreement to outside world.
         self.agreement_signed_signal.send_signal(msg)
     }
 }
 
+/// Checks if preset can take new Agreements at given time. If Agreements are terminated
+/// before window ends, availability ends earlier.
+fn is_available(preset: &Preset, config: &MarketConfig, now: DateTime<Local>) -> bool {
+    if !preset.availability.contains(&now) {
+        return false;
+    }
+    match preset.availability.end_of(&now) {
+        // Schedule without gaps never ends.
+        None => true,
+        Some(end) => match config.terminate_before_window_end {
+            Some(margin) => {
+                let margin = chrono::Duration::from_std(margin)
+                    .unwrap_or_else(|_| chrono::Duration::max_value());
+                end - now > margin
+            }
+            None => true,
+        },
+    }
+}
+
 async fn subscribe(
     market: Addr<ProviderMarket>,
     api: Arc<MarketProviderApi>,
@@ -290,6 +347,19 @@ async fn process_proposal(
         subscription.preset.name,
     );
 
+    if !is_available(&subscription.preset, &ctx.config, Local::now()) {
+        log::info!(
+            "Rejecting proposal [{}]. Preset [{}] is outside of its availability window.",
+            proposal_id,
+            subscription.preset.name
+        );
+        let reason = Some(Reason::new("Provider is not available at this time."));
+        ctx.api
+            .reject_proposal(&subscription.id, proposal_id, &reason)
+            .await?;
+        return Ok(());
+    }
+
     let prev_proposal = match &demand.prev_proposal_id {
         Some(prev_proposal_id) => ctx
             .api
@@ -384,6 +454,19 @@ async fn process_agreement(
     let agreement = AgreementView::try_from(agreement)
         .map_err(|e| anyhow!("Invalid agreement. Error: {}", e))?;
 
+    if !is_available(&subscription.preset, &config, Local::now()) {
+        log::info!(
+            "Rejecting agreement [{}]. Preset [{}] is outside of its availability window.",
+            agreement.agreement_id,
+            subscription.preset.name
+        );
+        let reason = Some(Reason::new("Provider is not available at this time."));
+        ctx.api
+            .reject_agreement(&agreement.agreement_id, &reason)
+            .await?;
+        return Ok(());
+    }
+
     let action = ctx
         .negotiator
         .react_to_agreement(&agreement)
@@ -591,8 +674,12 @@ impl Actor for ProviderMarket {
             "collect-agreement-events".to_string(),
             ctx.spawn(collect_agreement_events(actx).into_actor(self)),
         );
-
-        self.negotiator = factory::create_negotiator(ctx.address(), &self.config);
+        self.handles.insert(
+            "check-availability".to_string(),
+            ctx.run_interval(self.config.availability_check_interval, |_, ctx| {
+                ctx.address().do_send(CheckAvailability)
+            }),
+        );
     }
 }
 
@@ -673,6 +760,15 @@ impl Handler<CreateOffer> for ProviderMarket {
 
             log::debug!("Offer created: {}", offer.display());
 
+            if !is_available(&msg.preset, &ctx.config, Local::now()) {
+                log::info!(
+                    "Preset [{}] is outside of its availability window. Offer will be subscribed later.",
+                    msg.preset.name
+                );
+                let preset = msg.preset;
+                return ctx.market.send(ScheduleOffer { preset, offer }).await?;
+            }
+
             log::info!("Subscribing to events... [{}]", msg.preset.name);
 
             let preset_name = msg.preset.name.clone();
@@ -797,6 +893,7 @@ impl Handler<AgreementFinalized> for ProviderMarket {
         let ctx = self.async_context(ctx);
         let agreement_id = msg.id.clone();
         let result = msg.result.clone();
+        self.scheduled_agreements.remove(&agreement_id);
 
         if let AgreementResult::ApprovalFailed = &msg.result {
             self.agreement_terminated_signal
@@ -868,12 +965,15 @@ impl Handler<Unsubscribe> for ProviderMarket {
         let subscriptions = match msg.0 {
             OfferKind::Any => {
                 log::info!("Unsubscribing all active offers");
+                self.scheduled_offers.clear();
                 std::mem::take(&mut self.subscriptions)
                     .into_iter()
                     .map(|(k, _)| k)
                     .collect::<Vec<_>>()
             }
             OfferKind::WithPresets(preset_names) => {
+                self.scheduled_offers
+                    .retain(|name, _| !preset_names.contains(name));
                 let subs = self
                     .subscriptions
                     .iter()
@@ -906,6 +1006,116 @@ impl Handler<Unsubscribe> for ProviderMarket {
     }
 }
 
+impl Handler<ScheduleOffer> for ProviderMarket {
+    type Result = Result<()>;
+
+    fn handle(&mut self, msg: ScheduleOffer, _ctx: &mut Context<Self>) -> Self::Result {
+        self.scheduled_offers.insert(msg.preset.name.clone(), msg);
+        Ok(())
+    }
+}
+
+impl Handler<CheckAvailability> for ProviderMarket {
+    type Result = Result<()>;
+
+    fn handle(&mut self, _msg: CheckAvailability, ctx: &mut Context<Self>) -> Self::Result {
+        let now = Local::now();
+
+        // Offers of presets outside their windows are moved to scheduled ones.
+        let unavailable = self
+            .subscriptions
+            .values()
+            .filter(|sub| !is_available(&sub.preset, &self.config, now))
+            .map(|sub| sub.id.clone())
+            .collect::<Vec<_>>();
+        for id in unavailable.iter() {
+            if let Some(sub) = self.subscriptions.remove(id) {
+                log::info!(
+                    "Availability window of preset [{}] ended. Unsubscribing offer [{}].",
+                    sub.preset.name,
+                    sub.id
+                );
+                if let Some(handle) = self.handles.remove(id) {
+                    ctx.cancel_future(handle);
+                }
+                let scheduled = ScheduleOffer {
+                    preset: sub.preset,
+                    offer: sub.offer,
+                };
+                self.scheduled_offers
+                    .insert(scheduled.preset.name.clone(), scheduled);
+            }
+        }
+        if !unavailable.is_empty() {
+            let api = self.api.clone();
+            ctx.spawn(
+                unsubscribe_all(api, unavailable)
+                    .map(|result| {
+                        result
+                            .map_err(|e| log::warn!("Failed to unsubscribe offers. {}", e))
+                            .ok();
+                    })
+                    .into_actor(self),
+            );
+        }
+
+        let available = self
+            .scheduled_offers
+            .values()
+            .filter(|scheduled| is_available(&scheduled.preset, &self.config, now))
+            .map(|scheduled| scheduled.preset.name.clone())
+            .collect::<Vec<_>>();
+        for name in available {
+            if let Some(scheduled) = self.scheduled_offers.remove(&name) {
+                log::info!(
+                    "Availability window of preset [{}] started. Subscribing offer.",
+                    name
+                );
+                let future = subscribe(
+                    ctx.address(),
+                    self.api.clone(),
+                    scheduled.offer,
+                    scheduled.preset,
+                )
+                .map(move |result| {
+                    result
+                        .log_err_msg(&format!("Can't subscribe offer for preset [{}]", name))
+                        .ok();
+                });
+                ctx.spawn(future.into_actor(self));
+            }
+        }
+
+        // Running Agreements are terminated gracefully only, if Provider requested so.
+        if self.config.terminate_before_window_end.is_some() {
+            let expiring = self
+                .scheduled_agreements
+                .iter()
+                .filter(|(_, preset)| !is_available(preset, &self.config, now))
+                .map(|(id, _)| id.clone())
+                .collect::<Vec<_>>();
+            for agreement_id in expiring {
+                self.scheduled_agreements.remove(&agreement_id);
+                log::info!(
+                    "Closing agreement [{}], because availability window of its preset ends.",
+                    agreement_id
+                );
+                self.agreement_terminated_signal
+                    .send_signal(CloseAgreement {
+                        cause: ClosingCause::Schedule,
+                        agreement_id: agreement_id.clone(),
+                    })
+                    .log_err_msg(&format!(
+                        "Failed to propagate closing of agreement [{}]",
+                        agreement_id
+                    ))
+                    .ok();
+            }
+        }
+        Ok(())
+    }
+}
+
 forward_actix_handler!(ProviderMarket, Subscription, on_subscription);
 forward_actix_handler!(ProviderMarket, NewAgreement, on_agreement_approved);
 actix_signal_handler!(ProviderMarket, CloseAgreement, agreement_terminated_signal);
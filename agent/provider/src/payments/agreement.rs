@@ -31,8 +31,11 @@ impl CostInfo {
 //TODO: Remove last_debit_note in future. Payment api
 //      should deduce it based on activity id.
 pub enum ActivityPayment {
-    /// We got activity created event.
-    Running { activity_id: String },
+    /// We got activity created event. Keeps cost from the last debit note.
+    Running {
+        activity_id: String,
+        last_cost: Option<CostInfo>,
+    },
     /// We got activity destroyed event, but cost still isn't computed.
     Destroyed { activity_id: String },
     /// We computed cost and sent last debit note. Activity should
@@ -124,6 +127,7 @@ impl AgreementPayment {
     pub fn add_created_activity(&mut self, activity_id: &str) {
         let activity = ActivityPayment::Running {
             activity_id: activity_id.to_string(),
+            last_cost: None,
         };
         self.activities.insert(activity_id.to_string(), activity);
 
@@ -133,18 +137,32 @@ impl AgreementPayment {
         let _ = self.watch_sender.send(num_activities);
     }
 
-    pub fn activity_destroyed(&mut self, activity_id: &str) -> Result<()> {
+    /// Returns cost from the last debit note sent for this activity.
+    pub fn activity_destroyed(&mut self, activity_id: &str) -> Result<Option<CostInfo>> {
         if let Some(activity) = self.activities.get_mut(activity_id) {
-            if let ActivityPayment::Running { activity_id } = activity {
+            if let ActivityPayment::Running {
+                activity_id,
+                last_cost,
+            } = activity
+            {
+                let last_cost = last_cost.take();
                 *activity = ActivityPayment::Destroyed {
                     activity_id: activity_id.clone(),
                 };
-                return Ok(());
+                return Ok(last_cost);
             }
         }
         Err(anyhow!("Activity [{}] didn't exist before.", activity_id))
     }
 
+    pub fn update_last_cost(&mut self, activity_id: &str, cost_info: CostInfo) {
+        if let Some(ActivityPayment::Running { last_cost, .. }) =
+            self.activities.get_mut(activity_id)
+        {
+            *last_cost = Some(cost_info);
+        }
+    }
+
     pub fn finish_activity(&mut self, activity_id: &str, cost_info: CostInfo) -> Result<()> {
         if cost_info.usage.len() != self.payment_model.expected_usage_len() {
             return Err(anyhow!(
@@ -219,6 +237,7 @@ impl AgreementPayment {
 
 pub async fn compute_cost(
     payment_model: Arc<dyn PaymentModel>,
+    previous: Option<CostInfo>,
     activity_api: Arc<ActivityProviderApi>,
     activity_id: String,
 ) -> Result<CostInfo> {
@@ -252,7 +271,7 @@ pub async fn compute_cost(
         );
     }
 
-    let cost = payment_model.compute_cost(&usage)?;
+    let cost = payment_model.compute_cost(&usage, previous.as_ref(), Utc::now())?;
 
     Ok(CostInfo::new(usage, cost))
 }
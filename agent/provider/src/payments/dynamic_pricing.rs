@@ -0,0 +1,621 @@
+use anyhow::{anyhow, bail, Result};
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use ya_agreement_utils::ComInfo;
+
+use super::agreement::CostInfo;
+use super::model::{PaymentDescription, PaymentModel};
+use super::pricing::{
+    build_com_info, split_prices, to_big_decimal, AccountView, LinearPricingOffer, PricingOffer,
+};
+use crate::market::presets::Preset;
+
+pub const PRICING_MODELS: &[&str] = &["linear", "tiered", "time-of-day", "minimum-charge"];
+
+const DURATION_USAGE: &str = "golem.usage.duration_sec";
+
+/// Parameters of single time-of-day price window.
+const WINDOW_PARAMS: &[&str] = &["start-hour", "end-hour", "multiplier"];
+
+/// Names of parameters, that must be set in preset using given pricing model.
+pub fn pricing_model_params(model: &str) -> Result<&'static [&'static str]> {
+    Ok(match model {
+        "linear" => &[],
+        "tiered" => &["threshold-hours", "discount"],
+        "time-of-day" => &["peak-start-hour", "peak-end-hour", "peak-multiplier"],
+        "minimum-charge" => &["minimum"],
+        other => bail!("Unsupported pricing model: {}", other),
+    })
+}
+
+/// Checks if parameter can be set in preset in addition to `pricing_model_params`.
+/// Time-of-day pricing accepts more peak windows numbered from 2, e.g. `peak-2-start-hour`.
+pub fn is_optional_pricing_param(model: &str, name: &str) -> bool {
+    if model != "time-of-day" {
+        return false;
+    }
+    name.strip_prefix("peak-")
+        .and_then(|name| name.split_once('-'))
+        .map(|(idx, param)| {
+            matches!(idx.parse::<usize>(), Ok(idx) if idx >= 2) && WINDOW_PARAMS.contains(&param)
+        })
+        .unwrap_or(false)
+}
+
+fn window_param(idx: usize, name: &str) -> String {
+    match idx {
+        1 => format!("peak-{}", name),
+        idx => format!("peak-{}-{}", idx, name),
+    }
+}
+
+/// Creates Offer builder for pricing model chosen in preset.
+pub fn pricing_offer(preset: &Preset) -> Result<Box<dyn PricingOffer>> {
+    Ok(match preset.pricing_model.as_str() {
+        "linear" => Box::new(LinearPricingOffer::default()),
+        "tiered" => Box::new(TieredPricingOffer::from_preset(preset)?),
+        "time-of-day" => Box::new(TimeOfDayPricingOffer::from_preset(preset)?),
+        "minimum-charge" => Box::new(MinimumChargePricingOffer::from_preset(preset)?),
+        other => bail!("Unsupported pricing model: {}", other),
+    })
+}
+
+fn preset_param(preset: &Preset, name: &str) -> Result<f64> {
+    preset.pricing_params.get(name).cloned().ok_or_else(|| {
+        anyhow!(
+            "Preset [{}] is missing parameter [{}] of {} pricing model",
+            preset.name,
+            name,
+            preset.pricing_model
+        )
+    })
+}
+
+/// Usage coefficients with initial price, as stored in Agreement.
+struct Coefficients {
+    usage: Vec<f64>,
+    initial: f64,
+}
+
+impl Coefficients {
+    fn from_agreement(commercials: &PaymentDescription) -> Result<Coefficients> {
+        let mut usage = commercials.get_usage_coefficients()?;
+        // Note: last element of coefficients is constant initial cost.
+        let initial = usage
+            .pop()
+            .ok_or_else(|| anyhow!("Empty pricing coefficients"))?;
+        Ok(Coefficients { usage, initial })
+    }
+
+    fn linear(&self, usage: &[f64]) -> f64 {
+        self.initial + self.variable(usage)
+    }
+
+    fn variable(&self, usage: &[f64]) -> f64 {
+        self.usage
+            .iter()
+            .zip(usage.iter())
+            .map(|(coeff, usage_value)| coeff * usage_value)
+            .sum()
+    }
+}
+
+/// Cost computed so far for an activity and usage increase since then.
+struct Increment {
+    base: BigDecimal,
+    previous_usage: Vec<f64>,
+    delta: Vec<f64>,
+}
+
+impl Increment {
+    fn new(coeffs: &Coefficients, usage: &[f64], previous: Option<&CostInfo>) -> Result<Self> {
+        let (base, previous_usage) = match previous {
+            Some(previous) => (previous.cost.clone(), previous.usage.clone()),
+            None => (to_big_decimal(coeffs.initial)?, vec![0.0; usage.len()]),
+        };
+        // Counters shouldn't decrease, but we never want to lower the cost.
+        let delta = usage
+            .iter()
+            .zip(previous_usage.iter())
+            .map(|(current, previous)| (current - previous).max(0.0))
+            .collect();
+        Ok(Increment {
+            base,
+            previous_usage,
+            delta,
+        })
+    }
+
+    fn cost(self, increment: f64) -> Result<BigDecimal> {
+        Ok(self.base + to_big_decimal(increment)?)
+    }
+}
+
+/// Usage counted after `threshold_sec` of activity duration is discounted.
+pub struct TieredPricing {
+    coeffs: Coefficients,
+    duration_idx: usize,
+    threshold_sec: f64,
+    discount: f64,
+}
+
+impl TieredPricing {
+    pub fn new(commercials: &PaymentDescription) -> Result<TieredPricing> {
+        let coeffs = Coefficients::from_agreement(commercials)?;
+        let duration_idx = commercials
+            .get_usage_vector()?
+            .iter()
+            .position(|usage| usage == DURATION_USAGE)
+            .ok_or_else(|| anyhow!("Tiered pricing requires {} usage counter", DURATION_USAGE))?;
+        let threshold_sec = commercials.get_pricing_param("threshold-sec")?;
+        let discount = commercials.get_pricing_param("discount")?;
+
+        log::info!(
+            "Creating TieredPricing payment model. Usage coefficients vector: {:?}, \
+            discount {} after {}s.",
+            coeffs.usage,
+            discount,
+            threshold_sec
+        );
+        Ok(TieredPricing {
+            coeffs,
+            duration_idx,
+            threshold_sec,
+            discount,
+        })
+    }
+
+    /// Fraction of usage increase between `from` and `to` seconds
+    /// of activity duration, that falls after threshold.
+    fn discounted_fraction(&self, from: f64, to: f64) -> f64 {
+        if to <= self.threshold_sec {
+            0.0
+        } else if from >= self.threshold_sec {
+            1.0
+        } else {
+            (to - self.threshold_sec) / (to - from)
+        }
+    }
+}
+
+impl PaymentModel for TieredPricing {
+    fn compute_cost(
+        &self,
+        usage: &[f64],
+        previous: Option<&CostInfo>,
+        _now: DateTime<Utc>,
+    ) -> Result<BigDecimal> {
+        let increment = Increment::new(&self.coeffs, usage, previous)?;
+        // We don't know, how other counters changed in time, so we assume
+        // they grew proportionally to duration.
+        let discounted = self.discounted_fraction(
+            increment.previous_usage[self.duration_idx],
+            usage[self.duration_idx],
+        );
+        let factor = 1.0 - discounted + discounted * self.discount;
+        let cost = self.coeffs.variable(&increment.delta) * factor;
+        increment.cost(cost)
+    }
+
+    fn expected_usage_len(&self) -> usize {
+        self.coeffs.usage.len()
+    }
+}
+
+/// Hours range in UTC with prices multiplied by `multiplier`.
+/// Windows with `start_hour` greater than `end_hour` wrap around midnight.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct PriceWindow {
+    pub start_hour: u32,
+    pub end_hour: u32,
+    pub multiplier: f64,
+}
+
+impl PriceWindow {
+    fn contains(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            self.start_hour <= hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// Usage increase is charged with prices valid at the time of computing cost.
+pub struct TimeOfDayPricing {
+    coeffs: Coefficients,
+    windows: Vec<PriceWindow>,
+}
+
+impl TimeOfDayPricing {
+    pub fn new(commercials: &PaymentDescription) -> Result<TimeOfDayPricing> {
+        let coeffs = Coefficients::from_agreement(commercials)?;
+        let windows: Vec<PriceWindow> = commercials.get_pricing_param("windows")?;
+
+        log::info!(
+            "Creating TimeOfDayPricing payment model. Usage coefficients vector: {:?}, \
+            price windows: {:?}.",
+            coeffs.usage,
+            windows
+        );
+        Ok(TimeOfDayPricing { coeffs, windows })
+    }
+
+    fn multiplier(&self, now: DateTime<Utc>) -> f64 {
+        self.windows
+            .iter()
+            .find(|window| window.contains(now.hour()))
+            .map(|window| window.multiplier)
+            .unwrap_or(1.0)
+    }
+}
+
+impl PaymentModel for TimeOfDayPricing {
+    fn compute_cost(
+        &self,
+        usage: &[f64],
+        previous: Option<&CostInfo>,
+        now: DateTime<Utc>,
+    ) -> Result<BigDecimal> {
+        let increment = Increment::new(&self.coeffs, usage, previous)?;
+        let cost = self.coeffs.variable(&increment.delta) * self.multiplier(now);
+        increment.cost(cost)
+    }
+
+    fn expected_usage_len(&self) -> usize {
+        self.coeffs.usage.len()
+    }
+}
+
+/// Linear pricing, but Requestor pays at least `minimum`.
+pub struct MinimumChargePricing {
+    coeffs: Coefficients,
+    minimum: f64,
+}
+
+impl MinimumChargePricing {
+    pub fn new(commercials: &PaymentDescription) -> Result<MinimumChargePricing> {
+        let coeffs = Coefficients::from_agreement(commercials)?;
+        let minimum = commercials.get_pricing_param("minimum")?;
+
+        log::info!(
+            "Creating MinimumChargePricing payment model. Usage coefficients vector: {:?}, \
+            minimum charge: {}.",
+            coeffs.usage,
+            minimum
+        );
+        Ok(MinimumChargePricing { coeffs, minimum })
+    }
+}
+
+impl PaymentModel for MinimumChargePricing {
+    fn compute_cost(
+        &self,
+        usage: &[f64],
+        _previous: Option<&CostInfo>,
+        _now: DateTime<Utc>,
+    ) -> Result<BigDecimal> {
+        to_big_decimal(self.coeffs.linear(usage).max(self.minimum))
+    }
+
+    fn expected_usage_len(&self) -> usize {
+        self.coeffs.usage.len()
+    }
+}
+
+/// Helper for building tiered pricing Offer.
+pub struct TieredPricingOffer {
+    threshold_sec: f64,
+    discount: f64,
+}
+
+impl TieredPricingOffer {
+    pub fn from_preset(preset: &Preset) -> Result<TieredPricingOffer> {
+        let threshold_hours = preset_param(preset, "threshold-hours")?;
+        let discount = preset_param(preset, "discount")?;
+        if threshold_hours < 0.0 {
+            bail!("Tiered pricing threshold can't be negative");
+        }
+        if !(0.0..=1.0).contains(&discount) {
+            bail!("Tiered pricing discount must be within [0, 1]");
+        }
+        Ok(TieredPricingOffer {
+            threshold_sec: threshold_hours * 3600.0,
+            discount,
+        })
+    }
+}
+
+impl PricingOffer for TieredPricingOffer {
+    fn prices(&self, preset: &Preset) -> Vec<(String, f64)> {
+        // Duration counter is required to find out, when discount starts.
+        let mut prices = LinearPricingOffer::default().prices(preset);
+        if !prices.iter().any(|(usage, _)| usage == DURATION_USAGE) {
+            prices.push((DURATION_USAGE.to_string(), 0.0));
+        }
+        prices
+    }
+
+    fn build(
+        &self,
+        accounts: &[AccountView],
+        initial_price: f64,
+        prices: Vec<(String, f64)>,
+    ) -> Result<ComInfo> {
+        let (usage_vector, coefficients) = split_prices(initial_price, prices);
+        if !usage_vector.iter().any(|usage| usage == DURATION_USAGE) {
+            bail!("Tiered pricing requires {} usage counter", DURATION_USAGE);
+        }
+        let params = json!({
+            "coeffs": coefficients,
+            "threshold-sec": self.threshold_sec,
+            "discount": self.discount,
+        });
+        Ok(build_com_info(accounts, "tiered", params, usage_vector))
+    }
+}
+
+/// Helper for building time-of-day pricing Offer.
+pub struct TimeOfDayPricingOffer {
+    windows: Vec<PriceWindow>,
+}
+
+impl TimeOfDayPricingOffer {
+    pub fn from_preset(preset: &Preset) -> Result<TimeOfDayPricingOffer> {
+        let hour = |name: &str| -> Result<u32> {
+            let hour = preset_param(preset, name)?;
+            if !(0.0..=24.0).contains(&hour) || hour.fract() != 0.0 {
+                bail!("Pricing parameter [{}] must be a full hour", name);
+            }
+            Ok(hour as u32)
+        };
+        let mut windows = Vec::new();
+        for idx in 1.. {
+            let param = |name: &str| window_param(idx, name);
+            if idx > 1 && !preset.pricing_params.contains_key(&param("start-hour")) {
+                break;
+            }
+
+            let window = PriceWindow {
+                start_hour: hour(&param("start-hour"))?,
+                end_hour: hour(&param("end-hour"))?,
+                multiplier: preset_param(preset, &param("multiplier"))?,
+            };
+            if window.multiplier < 0.0 {
+                bail!("Peak price multiplier can't be negative");
+            }
+            windows.push(window);
+        }
+
+        if (0..24).any(|hour| windows.iter().filter(|w| w.contains(hour)).count() > 1) {
+            bail!("Peak windows of time-of-day pricing can't overlap");
+        }
+        Ok(TimeOfDayPricingOffer { windows })
+    }
+}
+
+impl PricingOffer for TimeOfDayPricingOffer {
+    fn prices(&self, preset: &Preset) -> Vec<(String, f64)> {
+        LinearPricingOffer::default().prices(preset)
+    }
+
+    fn build(
+        &self,
+        accounts: &[AccountView],
+        initial_price: f64,
+        prices: Vec<(String, f64)>,
+    ) -> Result<ComInfo> {
+        let (usage_vector, coefficients) = split_prices(initial_price, prices);
+        let params = json!({
+            "coeffs": coefficients,
+            "windows": self.windows,
+        });
+        Ok(build_com_info(
+            accounts,
+            "time-of-day",
+            params,
+            usage_vector,
+        ))
+    }
+}
+
+/// Helper for building minimum charge pricing Offer.
+pub struct MinimumChargePricingOffer {
+    minimum: f64,
+}
+
+impl MinimumChargePricingOffer {
+    pub fn from_preset(preset: &Preset) -> Result<MinimumChargePricingOffer> {
+        let minimum = preset_param(preset, "minimum")?;
+        if minimum < 0.0 {
+            bail!("Minimum charge can't be negative");
+        }
+        Ok(MinimumChargePricingOffer { minimum })
+    }
+}
+
+impl PricingOffer for MinimumChargePricingOffer {
+    fn prices(&self, preset: &Preset) -> Vec<(String, f64)> {
+        LinearPricingOffer::default().prices(preset)
+    }
+
+    fn build(
+        &self,
+        accounts: &[AccountView],
+        initial_price: f64,
+        prices: Vec<(String, f64)>,
+    ) -> Result<ComInfo> {
+        let (usage_vector, coefficients) = split_prices(initial_price, prices);
+        let params = json!({
+            "coeffs": coefficients,
+            "minimum": self.minimum,
+        });
+        Ok(build_com_info(
+            accounts,
+            "minimum-charge",
+            params,
+            usage_vector,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::str::FromStr;
+
+    fn coeffs() -> Coefficients {
+        // Duration and cpu prices.
+        Coefficients {
+            usage: vec![0.1, 1.0],
+            initial: 2.0,
+        }
+    }
+
+    fn cost(value: &str) -> BigDecimal {
+        BigDecimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_tiered_pricing() {
+        let model = TieredPricing {
+            coeffs: coeffs(),
+            duration_idx: 0,
+            threshold_sec: 100.0,
+            discount: 0.5,
+        };
+        let now = Utc::now();
+
+        assert_eq!(
+            model.compute_cost(&[100.0, 10.0], None, now).unwrap(),
+            cost("22")
+        );
+        // Half of duration falls after threshold.
+        assert_eq!(
+            model.compute_cost(&[200.0, 20.0], None, now).unwrap(),
+            cost("32")
+        );
+
+        let previous = CostInfo::new(vec![100.0, 10.0], cost("22"));
+        assert_eq!(
+            model
+                .compute_cost(&[200.0, 20.0], Some(&previous), now)
+                .unwrap(),
+            cost("32")
+        );
+    }
+
+    #[test]
+    fn test_price_window() {
+        let day = PriceWindow {
+            start_hour: 8,
+            end_hour: 20,
+            multiplier: 2.0,
+        };
+        assert!(day.contains(8));
+        assert!(day.contains(19));
+        assert!(!day.contains(20));
+        assert!(!day.contains(3));
+
+        let night = PriceWindow {
+            start_hour: 22,
+            end_hour: 6,
+            multiplier: 0.5,
+        };
+        assert!(night.contains(23));
+        assert!(night.contains(0));
+        assert!(!night.contains(6));
+        assert!(!night.contains(12));
+    }
+
+    #[test]
+    fn test_time_of_day_pricing() {
+        let model = TimeOfDayPricing {
+            coeffs: coeffs(),
+            windows: vec![PriceWindow {
+                start_hour: 8,
+                end_hour: 20,
+                multiplier: 2.0,
+            }],
+        };
+        let night = Utc.ymd(2022, 10, 1).and_hms(3, 0, 0);
+        let day = Utc.ymd(2022, 10, 1).and_hms(12, 0, 0);
+
+        let previous = CostInfo::new(vec![100.0, 10.0], cost("22"));
+        assert_eq!(
+            model
+                .compute_cost(&[100.0, 10.0], Some(&previous), day)
+                .unwrap(),
+            cost("22")
+        );
+        assert_eq!(
+            model
+                .compute_cost(&[200.0, 20.0], Some(&previous), night)
+                .unwrap(),
+            cost("42")
+        );
+        assert_eq!(
+            model
+                .compute_cost(&[200.0, 20.0], Some(&previous), day)
+                .unwrap(),
+            cost("62")
+        );
+    }
+
+    #[test]
+    fn test_time_of_day_offer_windows() {
+        let mut preset = Preset::default();
+        preset.pricing_model = "time-of-day".to_string();
+        preset.pricing_params = vec![
+            ("peak-start-hour", 8.0),
+            ("peak-end-hour", 12.0),
+            ("peak-multiplier", 1.5),
+            ("peak-2-start-hour", 18.0),
+            ("peak-2-end-hour", 22.0),
+            ("peak-2-multiplier", 2.0),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+
+        let offer = TimeOfDayPricingOffer::from_preset(&preset).unwrap();
+        assert_eq!(offer.windows.len(), 2);
+        // Market schema tests use the same form of windows in Offer.
+        assert_eq!(
+            serde_json::to_value(&offer.windows[1]).unwrap(),
+            json!({"start-hour": 18, "end-hour": 22, "multiplier": 2.0})
+        );
+
+        preset
+            .pricing_params
+            .insert("peak-2-start-hour".to_string(), 10.0);
+        assert!(TimeOfDayPricingOffer::from_preset(&preset).is_err());
+
+        assert!(is_optional_pricing_param("time-of-day", "peak-2-end-hour"));
+        assert!(!is_optional_pricing_param("time-of-day", "peak-1-end-hour"));
+        assert!(!is_optional_pricing_param("tiered", "peak-2-end-hour"));
+    }
+
+    #[test]
+    fn test_minimum_charge_pricing() {
+        let model = MinimumChargePricing {
+            coeffs: coeffs(),
+            minimum: 10.0,
+        };
+        let now = Utc::now();
+
+        assert_eq!(
+            model.compute_cost(&[0.0, 0.0], None, now).unwrap(),
+            cost("10")
+        );
+        assert_eq!(
+            model.compute_cost(&[100.0, 10.0], None, now).unwrap(),
+            cost("22")
+        );
+    }
+}
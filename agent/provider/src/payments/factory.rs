@@ -1,13 +1,20 @@
+use super::dynamic_pricing::{MinimumChargePricing, TieredPricing, TimeOfDayPricing};
 use super::model::{PaymentDescription, PaymentModel};
 use super::pricing::LinearPricing;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use std::sync::Arc;
 
 pub struct PaymentModelFactory;
 
 impl PaymentModelFactory {
     pub fn create<'a>(commercials: &'a PaymentDescription<'a>) -> Result<Arc<dyn PaymentModel>> {
-        Ok(Arc::new(LinearPricing::new(commercials)?))
+        Ok(match commercials.get_pricing_model()?.as_str() {
+            "linear" => Arc::new(LinearPricing::new(commercials)?),
+            "tiered" => Arc::new(TieredPricing::new(commercials)?),
+            "time-of-day" => Arc::new(TimeOfDayPricing::new(commercials)?),
+            "minimum-charge" => Arc::new(MinimumChargePricing::new(commercials)?),
+            other => bail!("Unsupported pricing model: {}", other),
+        })
     }
 }
@@ -1,10 +1,16 @@
 mod agreement;
+mod dynamic_pricing;
 mod factory;
 mod model;
 #[allow(clippy::module_inception)]
 mod payments;
 mod pricing;
+mod repricing;
 
+pub use dynamic_pricing::{
+    is_optional_pricing_param, pricing_model_params, pricing_offer, PRICING_MODELS,
+};
 pub use factory::PaymentModelFactory;
 pub use payments::{Payments, PaymentsConfig};
 pub use pricing::{AccountView, LinearPricing, LinearPricingOffer, PricingOffer};
+pub use repricing::{MarketSampler, PriceOverrides, RepricingConfig};
@@ -1,9 +1,13 @@
 use anyhow::Result;
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
 use std::time::Duration;
 
 use ya_agreement_utils::{AgreementView, Error};
 
+use super::agreement::CostInfo;
+
 use crate::market::negotiator::builtin::expiration::DEBIT_NOTE_ACCEPT_TIMEOUT_PROPERTY;
 use crate::market::negotiator::builtin::note_interval::{
     DEBIT_NOTE_INTERVAL_PROPERTY, DEFAULT_DEBIT_NOTE_INTERVAL_SEC,
@@ -13,7 +17,15 @@ use crate::market::negotiator::builtin::payment_timeout::PAYMENT_TIMEOUT_PROPERT
 /// Implementation of payment model which knows, how to compute amount
 /// of money, that requestor should pay for computations.
 pub trait PaymentModel {
-    fn compute_cost(&self, usage: &[f64]) -> Result<BigDecimal>;
+    /// Computes total cost of activity `usage`. `previous` is the last cost computed
+    /// for the same activity. Models with prices changing in time use it to charge
+    /// only the usage increase since then, using prices valid at `now`.
+    fn compute_cost(
+        &self,
+        usage: &[f64],
+        previous: Option<&CostInfo>,
+        now: DateTime<Utc>,
+    ) -> Result<BigDecimal>;
     fn expected_usage_len(&self) -> usize;
 }
 
@@ -27,9 +39,35 @@ impl<'a> PaymentDescription<'a> {
         Ok(PaymentDescription::<'a> { agreement })
     }
 
+    /// Agreements without pricing model property are treated as linear.
+    pub fn get_pricing_model(&self) -> Result<String> {
+        match self
+            .agreement
+            .get_property::<String>("offer.properties.golem.com.pricing.model")
+        {
+            Ok(model) => Ok(model),
+            Err(Error::NoKey(_)) => Ok("linear".to_string()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     pub fn get_usage_coefficients(&self) -> Result<Vec<f64>> {
-        let coeffs_addr = "/offer/properties/golem/com/pricing/model/linear/coeffs";
-        Ok(self.agreement.pointer_typed::<Vec<f64>>(coeffs_addr)?)
+        self.get_pricing_param("coeffs")
+    }
+
+    /// Gets parameter of pricing model used in Agreement.
+    pub fn get_pricing_param<T: for<'de> Deserialize<'de>>(&self, name: &str) -> Result<T> {
+        let model = self.get_pricing_model()?;
+        Ok(self.agreement.get_property::<T>(&format!(
+            "offer.properties.golem.com.pricing.model.{}.{}",
+            model, name
+        ))?)
+    }
+
+    pub fn get_usage_vector(&self) -> Result<Vec<String>> {
+        Ok(self
+            .agreement
+            .pointer_typed::<Vec<String>>("/offer/properties/golem/com/usage/vector")?)
     }
 
     pub fn get_update_interval(&self) -> Result<Duration> {
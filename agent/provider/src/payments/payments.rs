@@ -467,11 +467,13 @@ async fn handle_debit_note_event(
 async fn compute_cost_and_send_debit_note(
     provider_context: Arc<ProviderCtx>,
     payment_model: Arc<dyn PaymentModel>,
+    last_cost: Option<CostInfo>,
     last_payable_debit_node: DateTime<Utc>,
     invoice_info: &DebitNoteInfo,
 ) -> Result<(DebitNote, CostInfo)> {
     let cost_info = compute_cost(
         payment_model.clone(),
+        last_cost,
         provider_context.activity_api.clone(),
         invoice_info.activity_id.clone(),
     )
@@ -558,7 +560,7 @@ impl Handler<ActivityDestroyed> for Payments {
             Err(e) => return ActorResponse::reply(Err(e)),
         };
 
-        agreement.activity_destroyed(&msg.activity_id).unwrap();
+        let last_cost = agreement.activity_destroyed(&msg.activity_id).unwrap();
 
         let payment_model = agreement.payment_model.clone();
         let last_payable_debit_node = match agreement.payment_timeout {
@@ -586,6 +588,7 @@ impl Handler<ActivityDestroyed> for Payments {
                 match compute_cost_and_send_debit_note(
                     provider_context.clone(),
                     payment_model.clone(),
+                    last_cost.clone(),
                     last_payable_debit_node,
                     &debit_note_info,
                 )
@@ -639,7 +642,8 @@ impl Handler<UpdateCost> for Payments {
         };
 
         return match agreement.activities.get(&msg.invoice_info.activity_id) {
-            Some(ActivityPayment::Running { .. }) => {
+            Some(ActivityPayment::Running { last_cost, .. }) => {
+                let last_cost = last_cost.clone();
                 let last_debit_note = agreement.last_send_debit_note;
                 let last_payable_debit_node = agreement.last_payable_debit_note;
                 let accept_timeout = agreement.accept_timeout;
@@ -648,15 +652,16 @@ impl Handler<UpdateCost> for Payments {
                 let context = self.context.clone();
 
                 let debit_note_future = async move {
-                    let (debit_note, _cost) = compute_cost_and_send_debit_note(
+                    let (debit_note, cost) = compute_cost_and_send_debit_note(
                         context.clone(),
                         payment_model.clone(),
+                        last_cost,
                         last_payable_debit_node,
                         &invoice_info,
                     )
                         .await
                         .log_err()?;
-                    Ok(debit_note)
+                    Ok((debit_note, cost))
                 }
                     .into_actor(self)
                     .map(move |result: Result<_, anyhow::Error>, myself, ctx| {
@@ -678,7 +683,7 @@ impl Handler<UpdateCost> for Payments {
                                     }
                                 }
                             },
-                            Ok(debit_note) => {
+                            Ok((debit_note, cost)) => {
                                 // Payment due date is always set _before_ sending the DebitNote.
                                 // The following synchronises the acceptance timeout check.
                                 if let Some(agreement) = myself.agreements
@@ -688,6 +693,7 @@ impl Handler<UpdateCost> for Payments {
                                         if debit_note.payment_due_date.is_some() {
                                             agreement.last_payable_debit_note = debit_note.timestamp
                                         }
+                                        agreement.update_last_cost(&msg.invoice_info.activity_id, cost);
                                     }
                             }
                         }
@@ -1,11 +1,13 @@
 use anyhow::{anyhow, Result};
 use bigdecimal::{BigDecimal, FromPrimitive};
-use serde_json::json;
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
 
 use ya_agreement_utils::ComInfo;
 use ya_client::model::{payment::Account, NodeId};
 use ya_core_model::payment::local::NetworkName;
 
+use super::agreement::CostInfo;
 use super::model::{PaymentDescription, PaymentModel};
 use crate::market::presets::Preset;
 
@@ -42,7 +44,12 @@ pub struct LinearPricing {
 }
 
 impl PaymentModel for LinearPricing {
-    fn compute_cost(&self, usage: &[f64]) -> Result<BigDecimal> {
+    fn compute_cost(
+        &self,
+        usage: &[f64],
+        _previous: Option<&CostInfo>,
+        _now: DateTime<Utc>,
+    ) -> Result<BigDecimal> {
         // Note: last element of usage_coeffs contains constant initial cost
         // of computing task, so we don't multiply it.
         let const_coeff_idx = self.usage_coeffs.len() - 1;
@@ -53,8 +60,7 @@ impl PaymentModel for LinearPricing {
                 .map(|(coeff, usage_value)| coeff * usage_value)
                 .sum::<f64>();
 
-        BigDecimal::from_f64(cost)
-            .ok_or_else(|| anyhow!("Failed to convert to BigDecimal: {}", cost))
+        to_big_decimal(cost)
     }
 
     fn expected_usage_len(&self) -> usize {
@@ -62,6 +68,10 @@ impl PaymentModel for LinearPricing {
     }
 }
 
+pub(super) fn to_big_decimal(cost: f64) -> Result<BigDecimal> {
+    BigDecimal::from_f64(cost).ok_or_else(|| anyhow!("Failed to convert to BigDecimal: {}", cost))
+}
+
 impl LinearPricing {
     pub fn new<'a>(commercials: &'a PaymentDescription<'a>) -> Result<LinearPricing> {
         let usage: Vec<f64> = commercials.get_usage_coefficients()?;
@@ -106,39 +116,61 @@ impl PricingOffer for LinearPricingOffer {
         initial_price: f64,
         prices: Vec<(String, f64)>,
     ) -> Result<ComInfo> {
-        let mut usage_vector = Vec::new();
-        let coefficients = prices
-            .into_iter()
-            .map(|(p, v)| {
-                usage_vector.push(p);
-                v
-            })
-            .chain(std::iter::once(initial_price))
-            .collect::<Vec<_>>();
-
-        let mut params = json!({
-            "scheme": "payu".to_string(),
-            "scheme.payu": json!({}),
-            "pricing": json!({
-                "model": "linear".to_string(),
-                "model.linear": json!({
-                    "coeffs": coefficients
-                })
-            }),
-            "usage": json!({
-                "vector": usage_vector
-            })
-        });
-
-        for account in accounts {
-            params.as_object_mut().unwrap().insert(
-                format!("payment.platform.{}", account.platform),
-                json!({
-                    "address".to_string(): account.address,
-                }),
-            );
-        }
+        let (usage_vector, coefficients) = split_prices(initial_price, prices);
+        Ok(build_com_info(
+            accounts,
+            "linear",
+            json!({ "coeffs": coefficients }),
+            usage_vector,
+        ))
+    }
+}
 
-        Ok(ComInfo { params })
+/// Splits prices into usage vector and coefficients vector with initial
+/// price as the last element.
+pub(super) fn split_prices(
+    initial_price: f64,
+    prices: Vec<(String, f64)>,
+) -> (Vec<String>, Vec<f64>) {
+    let mut usage_vector = Vec::new();
+    let coefficients = prices
+        .into_iter()
+        .map(|(p, v)| {
+            usage_vector.push(p);
+            v
+        })
+        .chain(std::iter::once(initial_price))
+        .collect::<Vec<_>>();
+    (usage_vector, coefficients)
+}
+
+/// Builds commercial part of Offer for pricing `model` described by `model_params`.
+pub(super) fn build_com_info(
+    accounts: &[AccountView],
+    model: &str,
+    model_params: Value,
+    usage_vector: Vec<String>,
+) -> ComInfo {
+    let mut params = json!({
+        "scheme": "payu".to_string(),
+        "scheme.payu": json!({}),
+        "pricing": json!({
+            "model": model.to_string(),
+            format!("model.{}", model): model_params
+        }),
+        "usage": json!({
+            "vector": usage_vector
+        })
+    });
+
+    for account in accounts {
+        params.as_object_mut().unwrap().insert(
+            format!("payment.platform.{}", account.platform),
+            json!({
+                "address".to_string(): account.address,
+            }),
+        );
     }
+
+    ComInfo { params }
 }
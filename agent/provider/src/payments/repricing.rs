@@ -0,0 +1,452 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use structopt::StructOpt;
+
+use ya_agreement_utils::agreement::flatten;
+use ya_client::market::MarketProviderApi;
+use ya_client::model::market::Offer;
+use ya_client::model::NodeId;
+use ya_client::web::{WebClient, WebInterface};
+
+use crate::hardware::Resources;
+use crate::market::Preset;
+
+const PRICING_MODEL: &str = "golem.com.pricing.model";
+const LINEAR_COEFFS: &str = "golem.com.pricing.model.linear.coeffs";
+const USAGE_VECTOR: &str = "golem.com.usage.vector";
+const RUNTIME_NAME: &str = "golem.runtime.name";
+const CPU_THREADS: &str = "golem.inf.cpu.threads";
+const MEM_GIB: &str = "golem.inf.mem.gib";
+
+/// Max number of competing Offers sampled in single repricing round.
+const MAX_SAMPLED_OFFERS: usize = 1000;
+const QUERY_PAGE_SIZE: usize = 100;
+
+/// Configuration of automatic Offer repricing based on competing Offers
+/// visible in local market. Repricing is disabled if interval is not set.
+#[derive(StructOpt, Clone, Debug)]
+pub struct RepricingConfig {
+    /// How often competing Offers are sampled and own Offers repriced
+    #[structopt(long, env, parse(try_from_str = humantime::parse_duration))]
+    pub repricing_interval: Option<Duration>,
+    /// Percentile of competing prices, that our prices should follow
+    #[structopt(long, env, default_value = "50")]
+    pub repricing_percentile: f64,
+    /// Lowest allowed price as a fraction of preset price
+    #[structopt(long, env, default_value = "0.5")]
+    pub repricing_floor: f64,
+    /// Highest allowed price as a multiple of preset price
+    #[structopt(long, env, default_value = "2.0")]
+    pub repricing_ceiling: f64,
+    /// Max relative difference of cpu threads and memory of similar Offers
+    #[structopt(long, env, default_value = "0.5")]
+    pub repricing_resources_tolerance: f64,
+    /// Minimal number of similar Offers required to change prices
+    #[structopt(long, env, default_value = "5")]
+    pub repricing_min_samples: usize,
+    /// Offer is re-subscribed only if any price changes by more than this fraction
+    #[structopt(long, env, default_value = "0.05")]
+    pub repricing_min_change: f64,
+}
+
+impl RepricingConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=100.0).contains(&self.repricing_percentile) {
+            return Err(anyhow!(
+                "Repricing percentile must be within [0, 100], got {}",
+                self.repricing_percentile
+            ));
+        }
+        if self.repricing_floor <= 0.0 || self.repricing_floor > self.repricing_ceiling {
+            return Err(anyhow!(
+                "Invalid repricing bounds: floor {}, ceiling {}",
+                self.repricing_floor,
+                self.repricing_ceiling
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Prices of single linear pricing Offer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PriceSample {
+    pub initial_price: f64,
+    pub usage_coeffs: HashMap<String, f64>,
+}
+
+/// Part of Offer, that decides if it competes with our Offer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OfferResources {
+    pub runtime: String,
+    pub cpu_threads: Option<f64>,
+    pub mem_gib: Option<f64>,
+}
+
+impl PriceSample {
+    /// Reads prices from Offer properties. Returns None for Offers
+    /// not using linear pricing model.
+    pub fn from_properties(properties: &Map<String, Value>) -> Option<PriceSample> {
+        if properties.get(PRICING_MODEL)?.as_str()? != "linear" {
+            return None;
+        }
+        let coeffs = properties
+            .get(LINEAR_COEFFS)?
+            .as_array()?
+            .iter()
+            .map(Value::as_f64)
+            .collect::<Option<Vec<_>>>()?;
+        let usage = properties
+            .get(USAGE_VECTOR)?
+            .as_array()?
+            .iter()
+            .map(|name| name.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()?;
+
+        // Last coefficient is initial price, not multiplied by any usage counter.
+        let (initial_price, coeffs) = coeffs.split_last()?;
+        if coeffs.len() != usage.len() {
+            return None;
+        }
+        Some(PriceSample {
+            initial_price: *initial_price,
+            usage_coeffs: usage.into_iter().zip(coeffs.iter().cloned()).collect(),
+        })
+    }
+}
+
+impl OfferResources {
+    pub fn from_properties(properties: &Map<String, Value>) -> Option<OfferResources> {
+        Some(OfferResources {
+            runtime: properties.get(RUNTIME_NAME)?.as_str()?.to_string(),
+            cpu_threads: properties.get(CPU_THREADS).and_then(Value::as_f64),
+            mem_gib: properties.get(MEM_GIB).and_then(Value::as_f64),
+        })
+    }
+
+    pub fn own(runtime: &str, resources: &Resources) -> OfferResources {
+        OfferResources {
+            runtime: runtime.to_string(),
+            cpu_threads: Some(resources.cpu_threads as f64),
+            mem_gib: Some(resources.mem_gib),
+        }
+    }
+
+    /// Offers with the same runtime are similar, if their resources don't differ
+    /// more than `tolerance`. Missing resources aren't compared.
+    pub fn is_similar(&self, other: &OfferResources, tolerance: f64) -> bool {
+        let close = |own: Option<f64>, other: Option<f64>| match (own, other) {
+            (Some(own), Some(other)) if own > 0.0 => (own - other).abs() / own <= tolerance,
+            _ => true,
+        };
+        self.runtime == other.runtime
+            && close(self.cpu_threads, other.cpu_threads)
+            && close(self.mem_gib, other.mem_gib)
+    }
+}
+
+/// Computes percentile using linear interpolation between closest ranks.
+pub fn percentile(values: &[f64], percentile: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut values = values.to_vec();
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let rank = percentile.max(0.0).min(100.0) / 100.0 * (values.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    Some(values[lower] + (values[upper] - values[lower]) * (rank - lower as f64))
+}
+
+/// Computes new prices for `preset` from competing Offers' prices.
+/// Each price follows configured percentile of competing prices, bounded by
+/// floor and ceiling relative to price set in `preset`. Prices without enough
+/// samples stay unchanged.
+pub fn reprice(preset: &Preset, samples: &[PriceSample], config: &RepricingConfig) -> Preset {
+    let follow = |base: f64, values: Vec<f64>| {
+        if values.len() < config.repricing_min_samples.max(1) {
+            return base;
+        }
+        let price = percentile(&values, config.repricing_percentile).unwrap_or(base);
+        price
+            .max(base * config.repricing_floor)
+            .min(base * config.repricing_ceiling)
+    };
+
+    let mut repriced = preset.clone();
+    repriced.initial_price = follow(
+        preset.initial_price,
+        samples.iter().map(|sample| sample.initial_price).collect(),
+    );
+    for (usage, coeff) in repriced.usage_coeffs.iter_mut() {
+        let values = samples
+            .iter()
+            .filter_map(|sample| sample.usage_coeffs.get(usage).cloned())
+            .collect();
+        *coeff = follow(*coeff, values);
+    }
+    repriced
+}
+
+/// Checks if any price of `new` preset differs from `current` by more than `min_change`.
+pub fn prices_changed(current: &Preset, new: &Preset, min_change: f64) -> bool {
+    let changed = |current: f64, new: f64| {
+        if current == 0.0 {
+            return new != 0.0;
+        }
+        ((new - current) / current).abs() > min_change
+    };
+    changed(current.initial_price, new.initial_price)
+        || current.usage_coeffs.iter().any(|(usage, coeff)| {
+            new.usage_coeffs
+                .get(usage)
+                .map(|new_coeff| changed(*coeff, *new_coeff))
+                .unwrap_or(false)
+        })
+}
+
+/// Prices computed by repricing, that override prices from presets file.
+/// Overrides are dropped, when preset is changed by user.
+#[derive(Clone, Default)]
+pub struct PriceOverrides {
+    presets: Arc<Mutex<HashMap<String, Preset>>>,
+}
+
+impl PriceOverrides {
+    pub fn apply(&self, presets: Vec<Preset>) -> Vec<Preset> {
+        let overrides = self.presets.lock().unwrap();
+        presets
+            .into_iter()
+            .map(|preset| match overrides.get(&preset.name) {
+                Some(repriced) => Preset {
+                    initial_price: repriced.initial_price,
+                    usage_coeffs: repriced.usage_coeffs.clone(),
+                    ..preset
+                },
+                None => preset,
+            })
+            .collect()
+    }
+
+    pub fn set(&self, preset: Preset) {
+        self.presets
+            .lock()
+            .unwrap()
+            .insert(preset.name.clone(), preset);
+    }
+
+    pub fn remove(&self, names: &[String]) {
+        let mut overrides = self.presets.lock().unwrap();
+        for name in names {
+            overrides.remove(name);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OffersPage {
+    offers: Vec<Offer>,
+    next_cursor: Option<String>,
+}
+
+/// Samples Offers known to local market. Uses market Offers query endpoint,
+/// which isn't available in `MarketProviderApi`.
+#[derive(Clone)]
+pub struct MarketSampler {
+    client: WebClient,
+}
+
+impl MarketSampler {
+    pub fn new(app_key: &str) -> MarketSampler {
+        let client = WebClient::builder()
+            .api_url(MarketProviderApi::rest_api_url())
+            .auth_token(app_key)
+            .build();
+        MarketSampler { client }
+    }
+
+    /// Returns prices of linear pricing Offers similar to our Offer.
+    /// Offers published by our node are skipped.
+    pub async fn sample(
+        &self,
+        own: &OfferResources,
+        config: &RepricingConfig,
+    ) -> Result<Vec<PriceSample>> {
+        let own_nodes = self
+            .client
+            .interface::<MarketProviderApi>()?
+            .get_offers()
+            .await?
+            .into_iter()
+            .map(|offer| offer.provider_id)
+            .collect::<HashSet<NodeId>>();
+        let constraints = format!(
+            "(&({}={})({}=linear))",
+            RUNTIME_NAME, own.runtime, PRICING_MODEL
+        );
+
+        let mut samples = Vec::new();
+        let mut cursor = None;
+        let mut sampled = 0;
+        while sampled < MAX_SAMPLED_OFFERS {
+            let page = self.query_offers(&constraints, cursor.as_deref()).await?;
+            sampled += page.offers.len();
+
+            samples.extend(
+                page.offers
+                    .into_iter()
+                    .filter(|offer| !own_nodes.contains(&offer.provider_id))
+                    .filter_map(|offer| {
+                        let properties = flatten(offer.properties);
+                        let resources = OfferResources::from_properties(&properties)?;
+                        if !own.is_similar(&resources, config.repricing_resources_tolerance) {
+                            return None;
+                        }
+                        PriceSample::from_properties(&properties)
+                    }),
+            );
+
+            cursor = match page.next_cursor {
+                Some(cursor) => Some(cursor),
+                None => break,
+            };
+        }
+        Ok(samples)
+    }
+
+    async fn query_offers(&self, constraints: &str, cursor: Option<&str>) -> Result<OffersPage> {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        query.append_pair("constraints", constraints);
+        query.append_pair("limit", &QUERY_PAGE_SIZE.to_string());
+        if let Some(cursor) = cursor {
+            query.append_pair("cursor", cursor);
+        }
+
+        let url = format!("offers/query?{}", query.finish());
+        Ok(self.client.get(&url).send().json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> RepricingConfig {
+        RepricingConfig {
+            repricing_interval: None,
+            repricing_percentile: 50.0,
+            repricing_floor: 0.5,
+            repricing_ceiling: 2.0,
+            repricing_resources_tolerance: 0.5,
+            repricing_min_samples: 2,
+            repricing_min_change: 0.05,
+        }
+    }
+
+    fn preset() -> Preset {
+        Preset {
+            name: "default".to_string(),
+            exeunit_name: "wasmtime".to_string(),
+            pricing_model: "linear".to_string(),
+            initial_price: 1.0,
+            usage_coeffs: vec![
+                ("golem.usage.duration_sec".to_string(), 0.1),
+                ("golem.usage.cpu_sec".to_string(), 0.2),
+            ]
+            .into_iter()
+            .collect(),
+            pricing_params: Default::default(),
+            slot: None,
+            availability: Default::default(),
+        }
+    }
+
+    fn sample(initial_price: f64, duration: f64, cpu: f64) -> PriceSample {
+        PriceSample {
+            initial_price,
+            usage_coeffs: vec![
+                ("golem.usage.duration_sec".to_string(), duration),
+                ("golem.usage.cpu_sec".to_string(), cpu),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[], 50.0), None);
+        assert_eq!(percentile(&[3.0], 90.0), Some(3.0));
+        assert_eq!(percentile(&[4.0, 1.0, 3.0, 2.0], 50.0), Some(2.5));
+        assert_eq!(percentile(&[4.0, 1.0, 3.0, 2.0], 0.0), Some(1.0));
+        assert_eq!(percentile(&[4.0, 1.0, 3.0, 2.0], 100.0), Some(4.0));
+    }
+
+    #[test]
+    fn test_price_sample_from_properties() {
+        let properties = flatten(json!({
+            "golem.com.pricing.model": "linear",
+            "golem.com.pricing.model.linear.coeffs": [0.1, 0.2, 1.0],
+            "golem.com.usage.vector": ["golem.usage.duration_sec", "golem.usage.cpu_sec"],
+        }));
+        assert_eq!(
+            PriceSample::from_properties(&properties),
+            Some(sample(1.0, 0.1, 0.2))
+        );
+
+        let properties = flatten(json!({
+            "golem.com.pricing.model": "linear",
+            "golem.com.pricing.model.linear.coeffs": [0.1, 1.0],
+            "golem.com.usage.vector": ["golem.usage.duration_sec", "golem.usage.cpu_sec"],
+        }));
+        assert_eq!(PriceSample::from_properties(&properties), None);
+    }
+
+    #[test]
+    fn test_similar_resources() {
+        let own = OfferResources {
+            runtime: "vm".to_string(),
+            cpu_threads: Some(4.0),
+            mem_gib: Some(8.0),
+        };
+        let mut other = own.clone();
+        other.cpu_threads = Some(6.0);
+        assert!(own.is_similar(&other, 0.5));
+
+        other.mem_gib = Some(16.0);
+        assert!(!own.is_similar(&other, 0.5));
+
+        other.mem_gib = None;
+        other.runtime = "wasmtime".to_string();
+        assert!(!own.is_similar(&other, 0.5));
+    }
+
+    #[test]
+    fn test_reprice_within_bounds() {
+        let samples = vec![
+            sample(0.8, 0.01, 0.3),
+            sample(1.2, 0.01, 0.5),
+            sample(1.0, 0.01, 0.7),
+        ];
+        let repriced = reprice(&preset(), &samples, &config());
+
+        assert_eq!(repriced.initial_price, 1.0);
+        // Median 0.01 is below floor.
+        assert_eq!(repriced.usage_coeffs["golem.usage.duration_sec"], 0.05);
+        // Median 0.5 is above ceiling.
+        assert_eq!(repriced.usage_coeffs["golem.usage.cpu_sec"], 0.4);
+        assert!(prices_changed(&preset(), &repriced, 0.05));
+    }
+
+    #[test]
+    fn test_reprice_not_enough_samples() {
+        let repriced = reprice(&preset(), &[sample(5.0, 5.0, 5.0)], &config());
+        assert!(!prices_changed(&preset(), &repriced, 0.05));
+    }
+}
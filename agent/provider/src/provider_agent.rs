@@ -4,6 +4,7 @@ use futures::{FutureExt, StreamExt, TryFutureExt};
 use ya_client::net::NetApi;
 use ya_manifest_utils::matching::domain::{DomainPatterns, DomainWhitelistState, DomainsMatcher};
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
@@ -18,16 +19,21 @@ use ya_file_logging::{start_logger, LoggerHandle};
 use ya_manifest_utils::{manifest, Feature, Keystore};
 
 use crate::config::globals::GlobalsState;
+use crate::config::requestors::{RequestorLists, RequestorListsState};
 use crate::dir::clean_provider_dir;
 use crate::events::Event;
 use crate::execution::{
     ExeUnitDesc, GetExeUnit, GetOfferTemplates, Shutdown as ShutdownExecution, TaskRunner,
     UpdateActivity,
 };
-use crate::hardware;
+use crate::hardware::{self, Resources, Slot, SLOT_PROPERTY};
 use crate::market::provider_market::{OfferKind, Shutdown as MarketShutdown, Unsubscribe};
 use crate::market::{CreateOffer, Preset, PresetManager, ProviderMarket};
-use crate::payments::{AccountView, LinearPricingOffer, Payments, PricingOffer};
+use crate::payments::repricing::{prices_changed, reprice, OfferResources};
+use crate::payments::{
+    pricing_offer, AccountView, MarketSampler, Payments, PriceOverrides, PricingOffer,
+    RepricingConfig,
+};
 use crate::startup_config::{
     FileMonitor, FileMonitorConfig, NodeConfig, ProviderConfig, RunConfig,
 };
@@ -65,6 +71,11 @@ impl GlobalsManager {
     fn get_state(&self) -> GlobalsState {
         self.state.lock().unwrap().clone()
     }
+
+    /// State updated, when globals file changes.
+    fn get_shared_state(&self) -> Arc<Mutex<GlobalsState>> {
+        self.state.clone()
+    }
 }
 
 /// Stores current whitelist state.
@@ -118,6 +129,44 @@ impl WhitelistManager {
     }
 }
 
+/// Stores Requestor allowed and denied lists.
+/// Starts and stops lists file monitor.
+struct RequestorListsManager {
+    state: RequestorListsState,
+    monitor: Option<FileMonitor>,
+}
+
+impl RequestorListsManager {
+    fn try_new(requestors_file: &Path) -> anyhow::Result<Self> {
+        let lists = RequestorLists::load_or_create(requestors_file)?;
+        Ok(Self {
+            state: RequestorListsState::new(lists),
+            monitor: None,
+        })
+    }
+
+    fn spawn_monitor(&mut self, requestors_file: &Path) -> anyhow::Result<()> {
+        let state = self.state.clone();
+        let handler = move |p: PathBuf| match RequestorLists::load(&p) {
+            Ok(lists) => state.replace(lists),
+            Err(e) => log::warn!("Error updating Requestor lists from {:?}: {:?}", p, e),
+        };
+        let monitor = FileMonitor::spawn(requestors_file, FileMonitor::on_modified(handler))?;
+        self.monitor = Some(monitor);
+        Ok(())
+    }
+
+    fn get_state(&self) -> RequestorListsState {
+        self.state.clone()
+    }
+
+    fn stop(&mut self) {
+        if let Some(monitor) = &mut self.monitor {
+            monitor.stop();
+        }
+    }
+}
+
 pub struct ProviderAgent {
     globals: GlobalsManager,
     market: Addr<ProviderMarket>,
@@ -131,6 +180,10 @@ pub struct ProviderAgent {
     keystore_monitor: FileMonitor,
     net_api: NetApi,
     domain_whitelist: WhitelistManager,
+    requestor_lists: RequestorListsManager,
+    repricing: RepricingConfig,
+    price_overrides: PriceOverrides,
+    market_sampler: MarketSampler,
 }
 
 impl ProviderAgent {
@@ -160,7 +213,9 @@ impl ProviderAgent {
             log::info!("Freed {} of disk space", human_freed);
         }
 
+        args.repricing.validate()?;
         let api = ProviderApi::try_from(&args.api)?;
+        let market_sampler = MarketSampler::new(&args.api.app_key);
 
         log::info!("Loading payment accounts...");
         let accounts: Vec<AccountView> = api
@@ -199,6 +254,7 @@ impl ProviderAgent {
                 NetworkName::Rinkeby => yansi::Color::Cyan,
                 NetworkName::Mumbai => yansi::Color::Cyan,
                 NetworkName::Goerli => yansi::Color::Cyan,
+                NetworkName::Dev => yansi::Color::Cyan,
                 _ => yansi::Color::Red,
             };
             log::info!("Using payment network: {}", net_color.paint(&n));
@@ -210,17 +266,30 @@ impl ProviderAgent {
         presets.spawn_monitor(&config.presets_file)?;
         let mut hardware = hardware::Manager::try_new(&config)?;
         hardware.spawn_monitor(&config.hardware_file)?;
+        args.market.negotiator_config.composite_config.hardware = Some(hardware.allocator());
         let keystore_monitor = spawn_keystore_monitor(cert_dir, keystore)?;
         let mut domain_whitelist = WhitelistManager::try_new(&config.domain_whitelist_file)?;
         domain_whitelist.spawn_monitor(&config.domain_whitelist_file)?;
         policy_config.domain_patterns = domain_whitelist.get_state();
-
-        let market = ProviderMarket::new(api.market, args.market).start();
+        let mut requestor_lists = RequestorListsManager::try_new(&config.requestors_file)?;
+        requestor_lists.spawn_monitor(&config.requestors_file)?;
+        args.market
+            .negotiator_config
+            .composite_config
+            .requestor_lists = requestor_lists.get_state();
+        args.market.negotiator_config.composite_config.globals = globals.get_shared_state();
+        args.market
+            .negotiator_config
+            .composite_config
+            .requestors_usage_file = config.requestors_usage_file.clone();
+
+        let market = ProviderMarket::try_start(api.market, args.market)?;
         let payments = Payments::new(api.activity.clone(), api.payment, args.payment).start();
         let runner = TaskRunner::new(api.activity, args.runner, registry, data_dir)?.start();
         let task_manager =
             TaskManager::new(market.clone(), runner.clone(), payments, args.tasks)?.start();
         let net_api = api.net;
+        let repricing = args.repricing;
 
         Ok(ProviderAgent {
             globals,
@@ -235,13 +304,18 @@ impl ProviderAgent {
             keystore_monitor,
             net_api,
             domain_whitelist,
+            requestor_lists,
+            repricing,
+            price_overrides: PriceOverrides::default(),
+            market_sampler,
         })
     }
 
     async fn create_offers(
         presets: Vec<Preset>,
         node_info: NodeInfo,
-        inf_node_info: InfNodeInfo,
+        resources: Resources,
+        slots: HashMap<String, Slot>,
         runner: Addr<TaskRunner>,
         market: Addr<ProviderMarket>,
         accounts: Vec<AccountView>,
@@ -255,10 +329,24 @@ impl ProviderAgent {
         let offer_templates = runner.send(GetOfferTemplates(presets.clone())).await??;
 
         for preset in presets {
-            let offer: OfferTemplate = offer_templates
+            let mut offer: OfferTemplate = offer_templates
                 .get(&preset.name)
                 .ok_or_else(|| anyhow!("Offer template not found for preset [{}]", preset.name))?
                 .clone();
+            let inf_node_info = match &preset.slot {
+                Some(name) => {
+                    let slot = slots.get(name).ok_or_else(|| {
+                        anyhow!(
+                            "Resource slot [{}] of preset [{}] not found",
+                            name,
+                            preset.name
+                        )
+                    })?;
+                    offer.set_property(SLOT_PROPERTY, serde_json::json!(name));
+                    InfNodeInfo::from(slot.resources)
+                }
+                None => InfNodeInfo::from(resources),
+            };
             let exeunit_name = preset.exeunit_name.clone();
             let exeunit_desc = runner
                 .send(GetExeUnit { name: exeunit_name })
@@ -273,7 +361,7 @@ impl ProviderAgent {
 
             let offer = Self::build_offer(
                 node_info.clone(),
-                inf_node_info.clone(),
+                inf_node_info,
                 &accounts,
                 preset,
                 offer,
@@ -293,10 +381,7 @@ impl ProviderAgent {
         mut offer: OfferTemplate,
         exeunit_desc: ExeUnitDesc,
     ) -> anyhow::Result<CreateOffer> {
-        let pricing_model: Box<dyn PricingOffer> = match preset.pricing_model.as_str() {
-            "linear" => Box::new(LinearPricingOffer::default()),
-            other => return Err(anyhow!("Unsupported pricing model: {}", other)),
-        };
+        let pricing_model = pricing_offer(&preset)?;
         let (initial_price, prices) = get_prices(pricing_model.as_ref(), &preset, &offer)?;
         offer.set_property("golem.com.usage.vector", get_usage_vector_value(&prices));
         offer.add_constraints(Self::build_constraints(node_info.subnet.clone())?);
@@ -464,6 +549,17 @@ fn get_usage_vector_value(prices: &[(String, f64)]) -> serde_json::Value {
     serde_json::Value::Array(vec)
 }
 
+async fn reprice_periodically(agent: Addr<ProviderAgent>, interval: Duration) {
+    loop {
+        tokio::time::sleep(interval).await;
+        match agent.send(Reprice).await {
+            Ok(Err(error)) => log::warn!("Failed to reprice Offers: {}", error),
+            Err(error) => log::error!("Error sending reprice message: {:?}", error),
+            Ok(Ok(())) => (),
+        }
+    }
+}
+
 async fn process_activity_events(runner: Addr<TaskRunner>) {
     const ZERO: Duration = Duration::from_secs(0);
     const DEFAULT: Duration = Duration::from_secs(4);
@@ -515,6 +611,7 @@ impl Handler<Initialize> for ProviderAgent {
         let market = self.market.clone();
         let agent = ctx.address();
         let preset_state = self.presets.state.clone();
+        let price_overrides = self.price_overrides.clone();
 
         let rx = futures::stream::select_all(vec![
             WatchStream::new(self.hardware.event_receiver()),
@@ -553,6 +650,8 @@ impl Handler<Initialize> for ProviderAgent {
 
                         let mut to_unsub = updated;
                         to_unsub.extend(removed);
+                        // User changes take precedence over prices computed from market.
+                        price_overrides.remove(&to_unsub);
 
                         if !to_unsub.is_empty() {
                             let _ = market
@@ -573,6 +672,14 @@ impl Handler<Initialize> for ProviderAgent {
             .await;
         });
 
+        if let Some(interval) = self.repricing.repricing_interval {
+            log::info!(
+                "Offers will be repriced every {}",
+                humantime::format_duration(interval)
+            );
+            tokio::task::spawn_local(reprice_periodically(ctx.address(), interval));
+        }
+
         let agent = ctx.address();
         let task_manager = self.task_manager.clone();
         async move {
@@ -593,6 +700,7 @@ impl Handler<Shutdown> for ProviderAgent {
         let log_handler = self.log_handler.clone();
         self.keystore_monitor.stop();
         self.domain_whitelist.stop();
+        self.requestor_lists.stop();
 
         async move {
             market.send(MarketShutdown).await??;
@@ -615,7 +723,8 @@ impl Handler<CreateOffers> for ProviderAgent {
             Ok(acc) => acc,
             Err(e) => return Box::pin(async { Err(e) }),
         };
-        let inf_node_info = InfNodeInfo::from(self.hardware.capped());
+        let resources = self.hardware.capped();
+        let slots = self.hardware.slots();
         let preset_names = match msg.0 {
             OfferKind::Any => self.presets.active(),
             OfferKind::WithPresets(names) => names,
@@ -624,13 +733,82 @@ impl Handler<CreateOffers> for ProviderAgent {
                 vec![]
             }
         };
-        let presets = self.presets.list_matching(&preset_names);
+        let presets = self
+            .presets
+            .list_matching(&preset_names)
+            .map(|presets| self.price_overrides.apply(presets));
         let globals = self.globals.get_state();
         let net_api = self.net_api.clone();
 
         async move {
             let node_info = Self::build_node_info(globals, net_api).await?;
-            Self::create_offers(presets?, node_info, inf_node_info, runner, market, accounts).await
+            Self::create_offers(
+                presets?, node_info, resources, slots, runner, market, accounts,
+            )
+            .await
+        }
+        .boxed_local()
+    }
+}
+
+impl Handler<Reprice> for ProviderAgent {
+    type Result = ResponseFuture<Result<(), Error>>;
+
+    fn handle(&mut self, _: Reprice, ctx: &mut Context<Self>) -> Self::Result {
+        let agent = ctx.address();
+        let market = self.market.clone();
+        let sampler = self.market_sampler.clone();
+        let config = self.repricing.clone();
+        let overrides = self.price_overrides.clone();
+        let resources = self.hardware.capped();
+        let slots = self.hardware.slots();
+        let presets = match self.presets.list_matching(&self.presets.active()) {
+            Ok(presets) => presets,
+            Err(e) => return Box::pin(async { Err(e) }),
+        };
+
+        async move {
+            let mut repriced = Vec::new();
+            for preset in presets {
+                let offered = preset
+                    .slot
+                    .as_ref()
+                    .and_then(|name| slots.get(name))
+                    .map(|slot| slot.resources)
+                    .unwrap_or(resources);
+                let own = OfferResources::own(&preset.exeunit_name, &offered);
+                let samples = sampler.sample(&own, &config).await?;
+                let new = reprice(&preset, &samples, &config);
+                let current = overrides.apply(vec![preset.clone()]).remove(0);
+
+                log::debug!(
+                    "Sampled {} competing Offers for preset [{}].",
+                    samples.len(),
+                    preset.name
+                );
+                if prices_changed(&current, &new, config.repricing_min_change) {
+                    log::info!(
+                        "Repricing preset [{}]: initial price {} -> {}, coefficients {:?} -> {:?}",
+                        preset.name,
+                        current.initial_price,
+                        new.initial_price,
+                        current.usage_coeffs,
+                        new.usage_coeffs
+                    );
+                    repriced.push(new.name.clone());
+                    overrides.set(new);
+                }
+            }
+
+            if !repriced.is_empty() {
+                market
+                    .send(Unsubscribe(OfferKind::WithPresets(repriced.clone())))
+                    .await??;
+                agent
+                    .send(CreateOffers(OfferKind::WithPresets(repriced)))
+                    .await??;
+            }
+            Ok(())
         }
         .boxed_local()
     }
@@ -648,6 +826,11 @@ pub struct Shutdown;
 #[rtype(result = "Result<(), Error>")]
 struct CreateOffers(pub OfferKind);
 
+/// Samples competing Offers and re-subscribes Offers with changed prices.
+#[derive(Message)]
+#[rtype(result = "Result<(), Error>")]
+struct Reprice;
+
 /// Tests
 
 #[cfg(test)]
@@ -696,6 +879,39 @@ mod tests {
         assert_eq!(payload_manifest_prop, expected_manifest_suport);
     }
 
+    #[test]
+    fn tiered_pricing_offer_test() {
+        let mut fake = fake_data();
+        fake.preset.pricing_model = "tiered".to_string();
+        fake.preset.pricing_params = std::collections::HashMap::from([
+            ("threshold-hours".to_string(), 2.0),
+            ("discount".to_string(), 0.5),
+        ]);
+        fake.offer_template.properties = serde_json::json!({
+            "golem.com.usage.vector": ["test_coefficient", "golem.usage.duration_sec"]
+        });
+
+        let offer = ProviderAgent::build_offer(
+            fake.node_info,
+            fake.inf_node_info,
+            &fake.accounts,
+            fake.preset,
+            fake.offer_template,
+            fake.exeunit_desc,
+        )
+        .expect("Failed to build offer");
+
+        let offer_definition = offer.offer_definition.into_json();
+        assert_eq!(
+            offer_definition.get("golem.com.pricing.model"),
+            Some(&serde_json::json!("tiered"))
+        );
+        assert_eq!(
+            offer_definition.get("golem.com.pricing.model.tiered.threshold-sec"),
+            Some(&serde_json::json!(7200.0))
+        );
+    }
+
     /// Test utilities
 
     struct FakeData {
@@ -14,6 +14,7 @@ use ya_client::{cli::ApiOpts, model::node_id::NodeId};
 
 use ya_core_model::payment::local::NetworkName;
 use ya_utils_path::data_dir::DataDir;
+use ya_utils_scheduler::Window;
 
 use crate::cli::clean::CleanConfig;
 use crate::cli::config::ConfigConfig;
@@ -21,11 +22,14 @@ use crate::cli::exe_unit::ExeUnitsConfig;
 use crate::cli::keystore::KeystoreConfig;
 pub use crate::cli::preset::PresetsConfig;
 use crate::cli::profile::ProfileConfig;
+use crate::cli::requestors::RequestorsConfig;
+use crate::cli::status::StatusConfig;
 use crate::cli::whitelist::WhitelistConfig;
 pub(crate) use crate::config::globals::GLOBALS_JSON;
+pub(crate) use crate::config::requestors::{REQUESTORS_JSON, REQUESTORS_USAGE_JSON};
 use crate::execution::{ExeUnitsRegistry, TaskRunnerConfig};
 use crate::market::config::MarketConfig;
-use crate::payments::PaymentsConfig;
+use crate::payments::{PaymentsConfig, RepricingConfig};
 use crate::tasks::config::TaskConfig;
 
 lazy_static::lazy_static! {
@@ -82,6 +86,10 @@ pub struct ProviderConfig {
     pub presets_file: PathBuf,
     #[structopt(skip = HARDWARE_JSON)]
     pub hardware_file: PathBuf,
+    #[structopt(skip = REQUESTORS_JSON)]
+    pub requestors_file: PathBuf,
+    #[structopt(skip = REQUESTORS_USAGE_JSON)]
+    pub requestors_usage_file: PathBuf,
     /// Max number of available CPU cores
     #[structopt(
         long,
@@ -161,6 +169,14 @@ pub struct NodeConfig {
     /// with other identifiers than selected. Useful for test purposes.
     #[structopt(long, env = "SUBNET")]
     pub subnet: Option<String>,
+    /// Maximum number of simultaneous Agreements with single Requestor.
+    /// Set to 0 to remove the limit.
+    #[structopt(long, env = "MAX_REQUESTOR_AGREEMENTS")]
+    pub max_requestor_agreements: Option<u32>,
+    /// Maximum number of new Agreements with single Requestor within an hour.
+    /// Set to 0 to remove the limit.
+    #[structopt(long, env = "MAX_REQUESTOR_AGREEMENTS_PER_HOUR")]
+    pub max_requestor_agreements_per_hour: Option<u32>,
 
     #[structopt(flatten)]
     pub account: ReceiverAccount,
@@ -179,6 +195,8 @@ pub struct RunConfig {
     #[structopt(flatten)]
     pub payment: PaymentsConfig,
     #[structopt(flatten)]
+    pub repricing: RepricingConfig,
+    #[structopt(flatten)]
     pub tasks: TaskConfig,
     ///changes log level from info to debug
     #[structopt(long)]
@@ -195,6 +213,16 @@ pub struct PresetNoInteractive {
     pub pricing: Option<String>,
     #[structopt(long, parse(try_from_str = parse_key_val))]
     pub price: Vec<(String, f64)>,
+    /// Parameter of pricing model, e.g. `--pricing-param discount=0.8`
+    #[structopt(long, parse(try_from_str = parse_key_val))]
+    pub pricing_param: Vec<(String, f64)>,
+    /// Resource slot defined in hardware profiles, offered by this preset
+    #[structopt(long)]
+    pub slot: Option<String>,
+    /// Time window, when preset is offered, e.g. `--availability "mon-fri 18:00-08:00"`.
+    /// Can be repeated. Preset without windows is always offered
+    #[structopt(long)]
+    pub availability: Vec<Window>,
 }
 
 #[derive(StructOpt, Clone, Debug)]
@@ -237,6 +265,10 @@ pub enum Commands {
     Keystore(KeystoreConfig),
     /// Manage domain whitelist
     Whitelist(WhitelistConfig),
+    /// Manage allowed and denied Requestors
+    Requestors(RequestorsConfig),
+    /// Show Agreements counters of Requestors
+    Status(StatusConfig),
     /// Clean up disk space
     Clean(CleanConfig),
 }
@@ -45,6 +45,8 @@ pub enum ClosingCause {
     ApprovalFail,
     Termination,
     SingleActivity,
+    /// Availability window of Agreement's preset ends.
+    Schedule,
 }
 
 /// Notifies TaskManager that Requestor close agreement.
@@ -7,7 +7,7 @@ use std::path::PathBuf;
 
 use serde_json::{json, Value};
 use test_case::test_case;
-use ya_agreement_utils::AgreementView;
+use ya_client::model::NodeId;
 use ya_manifest_test_utils::{load_certificates_from_dir, TestResources};
 use ya_manifest_utils::matching::domain::{DomainPatterns, DomainWhitelistState};
 use ya_manifest_utils::{Keystore, Policy, PolicyConfig};
@@ -191,15 +191,9 @@ fn manifest_negotiator_test_encoded_manifest_sign_and_cert(
 
     let demand = create_demand_json(&comp_manifest_b64, signature_b64, signature_alg, cert_b64);
     let demand: Value = serde_json::from_str(&demand).unwrap();
-    let demand = AgreementView {
-        json: demand,
-        agreement_id: "id".to_string(),
-    };
+    let demand = ProposalView::new("id".to_string(), demand, NodeId::default());
     let offer: Value = serde_json::from_str(offer).unwrap();
-    let offer = AgreementView {
-        json: offer,
-        agreement_id: "id".to_string(),
-    };
+    let offer = ProposalView::new("id".to_string(), offer, NodeId::default());
 
     // When
     let negotiation_result = manifest_negotiator.negotiate_step(&demand, offer.clone());
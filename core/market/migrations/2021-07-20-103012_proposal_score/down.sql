@@ -0,0 +1,28 @@
+-- Older SQLite versions don't support dropping columns, so we need to recreate table.
+CREATE TABLE market_proposal_tmp(
+    id VARCHAR(100) NOT NULL PRIMARY KEY,
+    prev_proposal_id VARCHAR(100),
+    issuer VARCHAR(4) NOT NULL,
+    negotiation_id VARCHAR(100) NOT NULL,
+
+    properties TEXT NOT NULL,
+    constraints TEXT NOT NULL,
+
+    state VARCHAR(10) NOT NULL,
+    creation_ts DATETIME NOT NULL,
+    expiration_ts DATETIME NOT NULL,
+
+    FOREIGN KEY(negotiation_id) REFERENCES market_negotiation (id)
+    CHECK (state in ('Initial', 'Draft', 'Rejected', 'Accepted', 'Expired'))
+    CHECK (issuer in ('Us', 'Them'))
+);
+
+INSERT INTO market_proposal_tmp(id, prev_proposal_id, issuer, negotiation_id, properties, constraints, state, creation_ts, expiration_ts)
+SELECT id, prev_proposal_id, issuer, negotiation_id, properties, constraints, state, creation_ts, expiration_ts FROM market_proposal;
+
+DROP TABLE market_proposal;
+ALTER TABLE market_proposal_tmp RENAME TO market_proposal;
+
+create index if not exists market_proposal_prev_proposal_idx on market_proposal (prev_proposal_id);
+create index if not exists market_proposal_expiration_idx on market_proposal (expiration_ts);
+create index if not exists market_proposal_negotiation_idx on market_proposal (expiration_ts, negotiation_id);
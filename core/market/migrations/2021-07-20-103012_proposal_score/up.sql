@@ -0,0 +1,2 @@
+-- Score computed on Requestor side for Proposals received from Providers.
+ALTER TABLE market_proposal ADD COLUMN score DOUBLE;
@@ -0,0 +1 @@
+drop index market_offer_node_idx;
@@ -0,0 +1 @@
+create index if not exists market_offer_node_idx on market_offer (node_id);
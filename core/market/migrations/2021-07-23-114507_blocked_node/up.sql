@@ -0,0 +1,6 @@
+-- Nodes, which Offers are never matched with local Demands.
+CREATE TABLE market_blocked_node (
+    node_id VARCHAR(20) NOT NULL PRIMARY KEY,
+    reason TEXT,
+    blocked_ts DATETIME NOT NULL
+);
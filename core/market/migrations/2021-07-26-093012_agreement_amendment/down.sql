@@ -0,0 +1,2 @@
+DROP INDEX market_agreement_amendment_agreement_idx;
+DROP TABLE market_agreement_amendment;
@@ -0,0 +1,18 @@
+-- Changes of Agreement terms, mutually agreed after Agreement approval.
+-- Amendment id is the same on Requestor and Provider side.
+CREATE TABLE market_agreement_amendment(
+    id VARCHAR(100) NOT NULL,
+    agreement_id VARCHAR(100) NOT NULL,
+    valid_to DATETIME NOT NULL,
+    demand_properties TEXT,
+    offer_properties TEXT,
+    state VARCHAR(10) NOT NULL,
+    creation_ts DATETIME NOT NULL,
+    reason TEXT,
+
+    PRIMARY KEY(id, agreement_id),
+    FOREIGN KEY(agreement_id) REFERENCES market_agreement (id),
+    CHECK (state in ('Pending', 'Approved', 'Rejected'))
+);
+
+CREATE INDEX market_agreement_amendment_agreement_idx ON market_agreement_amendment(agreement_id);
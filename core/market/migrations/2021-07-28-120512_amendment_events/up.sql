@@ -0,0 +1,27 @@
+-- Agreement events are emitted also for amendments. Agreement can have many
+-- amendments, so uniqueness of event type is required only for events changing Agreement state.
+-- Older SQLite versions don't support altering constraints, so we need to recreate table.
+CREATE TABLE market_agreement_event_tmp(
+    id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+    agreement_id VARCHAR(100) NOT NULL,
+    event_type VARCHAR(20) NOT NULL,
+    timestamp DATETIME NOT NULL DEFAULT(STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')),
+    issuer VARCHAR(1) NOT NULL,
+    reason TEXT,
+    signature TEXT,
+    amendment_id VARCHAR(100),
+
+    FOREIGN KEY(agreement_id) REFERENCES market_agreement (id),
+    UNIQUE(agreement_id, event_type, amendment_id)
+    CHECK (event_type in ('Terminated', 'Approved', 'Cancelled', 'Rejected', 'AmendmentProposed', 'AmendmentApproved', 'AmendmentRejected'))
+    CHECK (issuer in ('P', 'R'))
+);
+
+INSERT INTO market_agreement_event_tmp(id, agreement_id, event_type, timestamp, issuer, reason, signature)
+SELECT id, agreement_id, event_type, timestamp, issuer, reason, signature FROM market_agreement_event;
+
+DROP TABLE market_agreement_event;
+ALTER TABLE market_agreement_event_tmp RENAME TO market_agreement_event;
+
+-- NULL values are distinct in UNIQUE constraint, so state events need separate index.
+CREATE UNIQUE INDEX market_agreement_event_state_idx ON market_agreement_event(agreement_id, event_type) WHERE amendment_id IS NULL;
@@ -6,7 +6,10 @@ pub mod resolver;
 
 use resolver::error::MatchError as InternalMatchErorr;
 
-use crate::resolver::properties::PropertyRef;
+use crate::resolver::explain::{explain, ResolveExplanation};
+use crate::resolver::expression::{build_expression, Expression, ResolveResult};
+use crate::resolver::ldap_parser;
+use crate::resolver::properties::{PropertyRef, PropertySet};
 use flatten::{flatten_properties, FlattenError};
 use resolver::error::PrepareError;
 pub use resolver::matching::{match_weak, MatchResult};
@@ -60,6 +63,108 @@ pub fn match_demand_offer(
     }
 }
 
+/// Explanation of matching Demand with Offer.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Explanation {
+    pub result: Match,
+    /// Demand constraints resolved against Offer properties.
+    pub demand: ConstraintsExplanation,
+    /// Offer constraints resolved against Demand properties.
+    pub offer: ConstraintsExplanation,
+}
+
+/// Sub-expressions of constraints (in LDAP filter syntax), which prevented matching,
+/// and names of properties, they refer to, but which were missing on the other side.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ConstraintsExplanation {
+    pub failing: Vec<String>,
+    pub undefined: Vec<String>,
+    pub missing_properties: Vec<String>,
+}
+
+impl<'a> From<ResolveExplanation<'a>> for ConstraintsExplanation {
+    fn from(explanation: ResolveExplanation<'a>) -> Self {
+        ConstraintsExplanation {
+            failing: explanation
+                .failing
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            undefined: explanation
+                .undefined
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            missing_properties: extract_names(&explanation.missing),
+        }
+    }
+}
+
+pub fn explain_demand_offer(
+    demand_properties: &str,
+    demand_constraints: &str,
+    offer_properties: &str,
+    offer_constraints: &str,
+) -> Result<Explanation, MatchError> {
+    let result = match_demand_offer(
+        demand_properties,
+        demand_constraints,
+        offer_properties,
+        offer_constraints,
+    )?;
+
+    let demand = Demand::from(demand_properties, demand_constraints)?;
+    let prep_demand = PreparedDemand::from(&demand)?;
+    let offer = Offer::from(offer_properties, offer_constraints)?;
+    let prep_offer = PreparedOffer::from(&offer)?;
+
+    let demand_explanation =
+        explain(&prep_demand.constraints, &prep_offer.properties).map_err(|e| {
+            InternalMatchErorr::new(&format!("Error explaining Demand constraints: {}", e))
+        })?;
+    let offer_explanation =
+        explain(&prep_offer.constraints, &prep_demand.properties).map_err(|e| {
+            InternalMatchErorr::new(&format!("Error explaining Offer constraints: {}", e))
+        })?;
+
+    Ok(Explanation {
+        result,
+        demand: demand_explanation.into(),
+        offer: offer_explanation.into(),
+    })
+}
+
+/// Constraints parsed once, to be resolved against properties of many Offers or Demands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintsFilter {
+    expression: Expression,
+}
+
+impl ConstraintsFilter {
+    pub fn new(constraints: &str) -> Result<Self, MatchError> {
+        let tags = ldap_parser::parse(constraints)
+            .map_err(|error| PrepareError::new(&format!("Error parsing constraints: {}", error)))?;
+        let expression = build_expression(&tags).map_err(|error| {
+            PrepareError::new(&format!("Error building constraints expression: {}", error))
+        })?;
+        Ok(ConstraintsFilter { expression })
+    }
+
+    /// Checks if constraints resolve to true against properties given in JSON form.
+    /// Undefined result (for example because of missing property) means no match.
+    pub fn matches(&self, properties: &str) -> Result<bool, MatchError> {
+        let properties = flatten_properties(properties)?;
+        let property_set = PropertySet::from_flat_props(&properties);
+        match self.expression.resolve(&property_set) {
+            ResolveResult::True => Ok(true),
+            ResolveResult::False(..) | ResolveResult::Undefined(..) => Ok(false),
+            ResolveResult::Err(e) => {
+                Err(InternalMatchErorr::new(&format!("Error resolving constraints: {}", e)).into())
+            }
+        }
+    }
+}
+
 fn extract_names(props_vec: &[&PropertyRef]) -> Vec<String> {
     props_vec
         .iter()
@@ -1,10 +1,12 @@
 pub mod error;
+pub mod explain;
 pub mod expression;
 pub mod ldap_parser;
 pub mod matching;
 pub mod prepare;
 pub mod prop_parser;
 pub mod properties;
+pub mod units;
 
 pub use self::expression::Expression;
 pub use self::matching::match_weak;
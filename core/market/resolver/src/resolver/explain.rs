@@ -0,0 +1,73 @@
+use super::error::ResolveError;
+use super::expression::{Expression, ResolveResult};
+use super::properties::{PropertyRef, PropertySet};
+
+// Explanation of expression resolution against a PropertySet.
+// Unlike Expression::resolve, which stops at first factor deciding the result,
+// explanation walks the whole expression and collects all sub-expressions,
+// that prevent it from resolving to true.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResolveExplanation<'a> {
+    pub failing: Vec<&'a Expression>, // Sub-expressions resolved to false
+    pub undefined: Vec<&'a Expression>, // Sub-expressions which couldn't be resolved
+    pub missing: Vec<&'a PropertyRef>, // Referred properties absent in PropertySet
+}
+
+impl<'a> ResolveExplanation<'a> {
+    pub fn is_empty(&self) -> bool {
+        self.failing.is_empty() && self.undefined.is_empty()
+    }
+
+    fn add_missing(&mut self, refs: Vec<&'a PropertyRef>) {
+        for prop_ref in refs {
+            if !self.missing.contains(&prop_ref) {
+                self.missing.push(prop_ref);
+            }
+        }
+    }
+}
+
+// Explain the expression resolution with a given PropertySet.
+pub fn explain<'a>(
+    expression: &'a Expression,
+    property_set: &'a PropertySet,
+) -> Result<ResolveExplanation<'a>, ResolveError> {
+    let mut explanation = ResolveExplanation::default();
+    explain_into(expression, property_set, &mut explanation)?;
+    Ok(explanation)
+}
+
+fn explain_into<'a>(
+    expression: &'a Expression,
+    property_set: &'a PropertySet,
+    explanation: &mut ResolveExplanation<'a>,
+) -> Result<(), ResolveError> {
+    let result = expression.resolve(property_set);
+
+    // Descend into non-empty AND/OR expressions to find out which factors are responsible.
+    // All other expressions (including NOT) are reported as a whole.
+    match (&result, expression) {
+        (ResolveResult::True, _) => return Ok(()),
+        (ResolveResult::Err(err), _) => return Err(err.clone()),
+        (_, Expression::And(exprs)) | (_, Expression::Or(exprs)) if !exprs.is_empty() => {
+            for expr in exprs {
+                explain_into(expr, property_set, explanation)?;
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    match result {
+        ResolveResult::False(refs, _) => {
+            explanation.failing.push(expression);
+            explanation.add_missing(refs);
+        }
+        ResolveResult::Undefined(refs, _) => {
+            explanation.undefined.push(expression);
+            explanation.add_missing(refs);
+        }
+        ResolveResult::True | ResolveResult::Err(_) => unreachable!(),
+    }
+    Ok(())
+}
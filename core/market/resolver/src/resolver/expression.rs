@@ -1,6 +1,8 @@
+use std::fmt;
 use std::str;
 
 use asnom::structures::{ExplicitTag, OctetString, Tag};
+use regex::Regex;
 
 use super::error::{ExpressionError, ResolveError};
 use super::ldap_parser;
@@ -23,6 +25,8 @@ pub enum Expression {
     GreaterEqual(PropertyRef, String), // property ref, value
     Less(PropertyRef, String),         // property ref, value
     LessEqual(PropertyRef, String),    // property ref, value
+    Regex(PropertyRef, String),        // property ref, regular expression
+    Substring(PropertyRef, String),    // property ref, value
     Present(PropertyRef),              // property ref
     Or(Vec<Expression>),               // operands
     And(Vec<Expression>),              // operands
@@ -71,6 +75,8 @@ impl Expression {
             | Expression::GreaterEqual(prop, _)
             | Expression::Less(prop, _)
             | Expression::LessEqual(prop, _)
+            | Expression::Regex(prop, _)
+            | Expression::Substring(prop, _)
             | Expression::Present(prop) => vec![prop],
             Expression::And(exprs) | Expression::Or(exprs) => {
                 exprs.iter().flat_map(|expr| expr.property_refs()).collect()
@@ -133,6 +139,18 @@ impl Expression {
                 property_set,
                 |prop_value: &PropertyValue, val: &str| -> bool { prop_value.greater_equal(val) },
             ),
+            Expression::Regex(attr, val) => self.resolve_with_function(
+                attr,
+                val,
+                property_set,
+                |prop_value: &PropertyValue, val: &str| -> bool { prop_value.matches_regex(val) },
+            ),
+            Expression::Substring(attr, val) => self.resolve_with_function(
+                attr,
+                val,
+                property_set,
+                |prop_value: &PropertyValue, val: &str| -> bool { prop_value.contains(val) },
+            ),
             // other binary operators here if needed...
             Expression::And(inner_expressions) => self.resolve_and(inner_expressions, property_set),
             Expression::Or(inner_expressions) => self.resolve_or(inner_expressions, property_set),
@@ -375,6 +393,43 @@ impl Expression {
     }
 }
 
+// Formats expression back in LDAP filter syntax.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expression::Equals(prop, val) => write!(f, "({}={})", prop, val),
+            Expression::Greater(prop, val) => write!(f, "({}>{})", prop, val),
+            Expression::GreaterEqual(prop, val) => write!(f, "({}>={})", prop, val),
+            Expression::Less(prop, val) => write!(f, "({}<{})", prop, val),
+            Expression::LessEqual(prop, val) => write!(f, "({}<={})", prop, val),
+            Expression::Regex(prop, val) => {
+                write!(f, "({}:{}:={})", prop, ldap_parser::RULE_REGEX, val)
+            }
+            Expression::Substring(prop, val) => {
+                write!(f, "({}:{}:={})", prop, ldap_parser::RULE_SUBSTRING, val)
+            }
+            Expression::Present(prop) => write!(f, "({}=*)", prop),
+            Expression::Or(exprs) => {
+                f.write_str("(|")?;
+                for expr in exprs {
+                    write!(f, "{}", expr)?;
+                }
+                f.write_str(")")
+            }
+            Expression::And(exprs) => {
+                f.write_str("(&")?;
+                for expr in exprs {
+                    write!(f, "{}", expr)?;
+                }
+                f.write_str(")")
+            }
+            Expression::Not(expr) => write!(f, "(!{})", expr),
+            Expression::Empty(true) => f.write_str("()"),
+            Expression::Empty(false) => f.write_str("(|)"),
+        }
+    }
+}
+
 // #region Expression building
 
 pub fn build_expression(root: &Tag) -> Result<Expression, ExpressionError> {
@@ -387,6 +442,7 @@ pub fn build_expression(root: &Tag) -> Result<Expression, ExpressionError> {
             | ldap_parser::TAG_LESS_EQUAL
             | ldap_parser::TAG_GREATER
             | ldap_parser::TAG_GREATER_EQUAL => build_simple_expression(seq.id, &seq.inner),
+            ldap_parser::TAG_EXTENSIBLE => build_extensible_expression(&seq.inner),
             _ => Err(ExpressionError::new(&format!(
                 "Unknown sequence type {}",
                 seq.id
@@ -500,6 +556,44 @@ fn build_simple_expression(
     }
 }
 
+fn build_extensible_expression(sequence: &[Tag]) -> Result<Expression, ExpressionError> {
+    if sequence.len() < 3 {
+        return Err(ExpressionError::new(&format!(
+            "Expected 3 tags, got {} tags",
+            sequence.len()
+        )));
+    }
+
+    let attr = extract_str_from_octet_string(&sequence[0])?;
+    let rule = extract_str_from_octet_string(&sequence[1])?;
+    let value = extract_str_from_octet_string(&sequence[2])?;
+
+    let prop_ref = match parse_prop_ref(attr) {
+        Ok(prop_ref) => prop_ref,
+        Err(prop_err) => {
+            return Err(ExpressionError::new(&format!(
+                "Error parsing property reference {}: {}",
+                attr, prop_err
+            )))
+        }
+    };
+
+    match rule {
+        ldap_parser::RULE_REGEX => match Regex::new(value) {
+            Ok(_) => Ok(Expression::Regex(prop_ref, String::from(value))),
+            Err(err) => Err(ExpressionError::new(&format!(
+                "Invalid regular expression {}: {}",
+                value, err
+            ))),
+        },
+        ldap_parser::RULE_SUBSTRING => Ok(Expression::Substring(prop_ref, String::from(value))),
+        _ => Err(ExpressionError::new(&format!(
+            "Unknown matching rule {}",
+            rule
+        ))),
+    }
+}
+
 fn extract_str_from_octet_string(tag: &Tag) -> Result<&str, ExpressionError> {
     match tag {
         Tag::OctetString(oct) => match str::from_utf8(&oct.inner) {
@@ -1,6 +1,6 @@
 use std::default::Default;
 
-use nom::IResult;
+use nom::{IResult, Needed};
 
 use asnom::common::TagClass;
 use asnom::structures::{ExplicitTag, Null, OctetString, Sequence, Tag};
@@ -17,6 +17,12 @@ pub const TAG_GREATER: u64 = 8;
 pub const TAG_GREATER_EQUAL: u64 = 9;
 pub const TAG_LESS: u64 = 10;
 pub const TAG_LESS_EQUAL: u64 = 11;
+pub const TAG_EXTENSIBLE: u64 = 12;
+
+// Extensible match rules
+
+pub const RULE_REGEX: &str = "regex";
+pub const RULE_SUBSTRING: &str = "substr";
 
 // Parse function
 
@@ -83,7 +89,7 @@ named!(
     })
 );
 
-named!(match_f<Tag>, alt!(present | simple));
+named!(match_f<Tag>, alt!(extensible | present | simple));
 
 named!(
     present<Tag>,
@@ -121,6 +127,56 @@ named!(
     )
 );
 
+// Extensible match, eg. (golem.runtime.name:regex:=^vm(-nvidia)?$)
+// Encoded as sequence of three octet strings: attribute, matching rule, value.
+named!(
+    extensible<Tag>,
+    do_parse!(
+        attr: take_till!(is_ext_delimiter)
+            >> char!(':')
+            >> rule: take_till!(is_ext_delimiter)
+            >> tag!(":=")
+            >> value: extensible_value
+            >> (Tag::Sequence(Sequence {
+                class: TagClass::Context,
+                id: TAG_EXTENSIBLE,
+                inner: vec![
+                    Tag::OctetString(OctetString {
+                        inner: attr.to_vec(),
+                        ..Default::default()
+                    }),
+                    Tag::OctetString(OctetString {
+                        inner: rule.to_vec(),
+                        ..Default::default()
+                    }),
+                    Tag::OctetString(OctetString {
+                        inner: value.to_vec(),
+                        ..Default::default()
+                    })
+                ]
+            }))
+    )
+);
+
+// Extensible match value may contain balanced brackets (eg. regex groups),
+// so unlike simple values it does not end at the first closing bracket.
+// A bracket preceded by backslash is not counted.
+fn extensible_value(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    let mut depth = 0usize;
+    let mut escaped = false;
+    for (idx, chr) in input.iter().enumerate() {
+        match *chr {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'(' => depth += 1,
+            b')' if depth == 0 => return IResult::Done(&input[idx..], &input[..idx]),
+            b')' => depth -= 1,
+            _ => {}
+        }
+    }
+    IResult::Incomplete(Needed::Unknown)
+}
+
 //named!(filtertype <u64>, call!(equal));
 
 named!(
@@ -144,3 +200,7 @@ named!(
 pub fn is_delimiter(chr: u8) -> bool {
     chr == b'=' || chr == b'<' || chr == b'>' || chr == b'~'
 }
+
+pub fn is_ext_delimiter(chr: u8) -> bool {
+    chr == b':' || is_delimiter(chr)
+}
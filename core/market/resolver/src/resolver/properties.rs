@@ -4,11 +4,14 @@ use bigdecimal::BigDecimal;
 use chrono::{DateTime, Utc};
 use regex::Regex;
 use semver::Version;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
 
 use super::error::ParseError;
 use super::prop_parser;
 use super::prop_parser::Literal;
+use super::units::Quantity;
 
 #[allow(non_camel_case_types)]
 type d128 = BigDecimal;
@@ -31,7 +34,10 @@ impl<'a> PropertyValue<'a> {
     // TODO Implement equals() for remaining types
     pub fn equals(&self, other: &str) -> bool {
         match self {
-            PropertyValue::Str(value) => PropertyValue::str_equal_with_wildcard(other, *value), // enhanced string comparison
+            PropertyValue::Str(value) => {
+                PropertyValue::str_equal_with_wildcard(other, *value)
+                    || PropertyValue::str_equal_as_quantity(other, *value)
+            } // enhanced string comparison
             PropertyValue::Number(value) => match other.parse::<f64>() {
                 Ok(parsed_value) => parsed_value == *value,
                 _ => false,
@@ -62,7 +68,10 @@ impl<'a> PropertyValue<'a> {
     // TODO Implement less() for remaining types
     pub fn less(&self, other: &str) -> bool {
         match self {
-            PropertyValue::Str(value) => *value < other, // trivial string comparison
+            PropertyValue::Str(value) => matches!(
+                PropertyValue::str_compare(value, other),
+                Some(Ordering::Less)
+            ), // typed string comparison
             PropertyValue::Number(value) => match other.parse::<f64>() {
                 Ok(parsed_value) => *value < parsed_value,
                 _ => false,
@@ -79,15 +88,18 @@ impl<'a> PropertyValue<'a> {
                 Ok(parsed_value) => *value < parsed_value,
                 _ => false,
             }, // ignore parsing error, assume false
-            PropertyValue::List(_) => false,             // operator meaningless for List
-            PropertyValue::Boolean(_) => false,          // operator meaningless for bool
+            PropertyValue::List(_) => false, // operator meaningless for List
+            PropertyValue::Boolean(_) => false, // operator meaningless for bool
         }
     }
 
     // TODO Implement less_equal() for remaining types
     pub fn less_equal(&self, other: &str) -> bool {
         match self {
-            PropertyValue::Str(value) => *value <= other, // trivial string comparison
+            PropertyValue::Str(value) => matches!(
+                PropertyValue::str_compare(value, other),
+                Some(Ordering::Less) | Some(Ordering::Equal)
+            ), // typed string comparison
             PropertyValue::Number(value) => match other.parse::<f64>() {
                 Ok(parsed_value) => *value <= parsed_value,
                 _ => false,
@@ -104,15 +116,18 @@ impl<'a> PropertyValue<'a> {
                 Ok(parsed_value) => *value <= parsed_value,
                 _ => false,
             }, // ignore parsing error, assume false
-            PropertyValue::List(_) => false,              // operator meaningless for List
-            PropertyValue::Boolean(_) => false,           // operator meaningless for bool
+            PropertyValue::List(_) => false, // operator meaningless for List
+            PropertyValue::Boolean(_) => false, // operator meaningless for bool
         }
     }
 
     // TODO Implement greater() for remaining types
     pub fn greater(&self, other: &str) -> bool {
         match self {
-            PropertyValue::Str(value) => *value > other, // trivial string comparison
+            PropertyValue::Str(value) => matches!(
+                PropertyValue::str_compare(value, other),
+                Some(Ordering::Greater)
+            ), // typed string comparison
             PropertyValue::Number(value) => match other.parse::<f64>() {
                 Ok(parsed_value) => *value > parsed_value,
                 _ => false,
@@ -129,15 +144,18 @@ impl<'a> PropertyValue<'a> {
                 Ok(parsed_value) => *value > parsed_value,
                 _ => false,
             }, // ignore parsing error, assume false
-            PropertyValue::List(_) => false,             // operator meaningless for List
-            PropertyValue::Boolean(_) => false,          // operator meaningless for bool
+            PropertyValue::List(_) => false, // operator meaningless for List
+            PropertyValue::Boolean(_) => false, // operator meaningless for bool
         }
     }
 
     // TODO Implement greater_equal() for remaining types
     pub fn greater_equal(&self, other: &str) -> bool {
         match self {
-            PropertyValue::Str(value) => *value >= other, // trivial string comparison
+            PropertyValue::Str(value) => matches!(
+                PropertyValue::str_compare(value, other),
+                Some(Ordering::Greater) | Some(Ordering::Equal)
+            ), // typed string comparison
             PropertyValue::Number(value) => match other.parse::<f64>() {
                 Ok(parsed_value) => *value >= parsed_value,
                 _ => false,
@@ -154,8 +172,31 @@ impl<'a> PropertyValue<'a> {
                 Ok(parsed_value) => *value >= parsed_value,
                 _ => false,
             }, // ignore parsing error, assume false
-            PropertyValue::List(_) => false,              // operator meaningless for List
-            PropertyValue::Boolean(_) => false,           // operator meaningless for bool
+            PropertyValue::List(_) => false, // operator meaningless for List
+            PropertyValue::Boolean(_) => false, // operator meaningless for bool
+        }
+    }
+
+    // Regular expression matching, meaningful for strings only.
+    // For List - true if any of the items matches (similar to "IN" semantics of equals).
+    pub fn matches_regex(&self, pattern: &str) -> bool {
+        match self {
+            PropertyValue::Str(value) => match Regex::new(pattern) {
+                Ok(regex) => regex.is_match(value),
+                Err(_error) => false,
+            }, // ignore regex error, assume false
+            PropertyValue::List(items) => items.iter().any(|item| item.matches_regex(pattern)),
+            _ => false, // operator meaningless for other types
+        }
+    }
+
+    // Substring matching, meaningful for strings only.
+    // For List - true if any of the items contains the substring.
+    pub fn contains(&self, other: &str) -> bool {
+        match self {
+            PropertyValue::Str(value) => value.contains(other),
+            PropertyValue::List(items) => items.iter().any(|item| item.contains(other)),
+            _ => false, // operator meaningless for other types
         }
     }
 
@@ -174,6 +215,28 @@ impl<'a> PropertyValue<'a> {
         }
     }
 
+    // Equality of strings representing quantities with units, eg. 8GiB = 8192MiB.
+    fn str_equal_as_quantity(str1: &str, str2: &str) -> bool {
+        match (str1.parse::<Quantity>(), str2.parse::<Quantity>()) {
+            (Ok(q1), Ok(q2)) => q1 == q2,
+            _ => false,
+        }
+    }
+
+    // Ordering of string values, which takes their meaning into account.
+    // If both strings are versions, semver ordering is used. If both are quantities with units,
+    // their normalized values are compared (quantities of different kinds are not comparable).
+    // Otherwise strings are compared lexicographically.
+    fn str_compare(value: &str, other: &str) -> Option<Ordering> {
+        if let (Ok(v1), Ok(v2)) = (Version::parse(value), Version::parse(other)) {
+            return Some(v1.cmp(&v2));
+        }
+        if let (Ok(q1), Ok(q2)) = (value.parse::<Quantity>(), other.parse::<Quantity>()) {
+            return q1.partial_cmp(&q2);
+        }
+        Some(value.cmp(other))
+    }
+
     fn parse_date(dt_str: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
         PropertyValue::parse_date_from_rfc3339(dt_str)
     }
@@ -439,6 +502,29 @@ pub enum PropertyRefType {
     DateTime,
 }
 
+// Formats property reference back in filter expression syntax, eg. name[aspect]$v
+impl fmt::Display for PropertyRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PropertyRef::Value(name, impl_type) => write!(f, "{}{}", name, impl_type),
+            PropertyRef::Aspect(name, aspect, impl_type) => {
+                write!(f, "{}[{}]{}", name, aspect, impl_type)
+            }
+        }
+    }
+}
+
+impl fmt::Display for PropertyRefType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PropertyRefType::Any => Ok(()),
+            PropertyRefType::Decimal => f.write_str("$d"),
+            PropertyRefType::Version => f.write_str("$v"),
+            PropertyRefType::DateTime => f.write_str("$t"),
+        }
+    }
+}
+
 pub fn parse_prop_ref(flat_prop: &str) -> Result<PropertyRef, ParseError> {
     // TODO parse the flat_prop using prop_parser and repack to PropertyRef
     match prop_parser::parse_prop_ref_with_aspect(flat_prop) {
@@ -0,0 +1,89 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use super::error::ParseError;
+
+// Physical dimension of quantity. Only quantities of the same dimension can be compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Bytes,
+    Frequency,
+    Duration,
+}
+
+// Numeric value with unit, eg. 8GiB, 2.5GHz, 1h.
+// Value is normalized to base unit of dimension (byte, hertz, second).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub dimension: Dimension,
+}
+
+const KILO: f64 = 1e3;
+const KIBI: f64 = 1024.0;
+
+// Units are case sensitive to avoid ambiguity (eg. mHz vs MHz).
+const UNITS: &[(&str, Dimension, f64)] = &[
+    ("B", Dimension::Bytes, 1.0),
+    ("kB", Dimension::Bytes, KILO),
+    ("KB", Dimension::Bytes, KILO),
+    ("MB", Dimension::Bytes, KILO * KILO),
+    ("GB", Dimension::Bytes, KILO * KILO * KILO),
+    ("TB", Dimension::Bytes, KILO * KILO * KILO * KILO),
+    ("PB", Dimension::Bytes, KILO * KILO * KILO * KILO * KILO),
+    ("KiB", Dimension::Bytes, KIBI),
+    ("MiB", Dimension::Bytes, KIBI * KIBI),
+    ("GiB", Dimension::Bytes, KIBI * KIBI * KIBI),
+    ("TiB", Dimension::Bytes, KIBI * KIBI * KIBI * KIBI),
+    ("PiB", Dimension::Bytes, KIBI * KIBI * KIBI * KIBI * KIBI),
+    ("Hz", Dimension::Frequency, 1.0),
+    ("kHz", Dimension::Frequency, KILO),
+    ("MHz", Dimension::Frequency, KILO * KILO),
+    ("GHz", Dimension::Frequency, KILO * KILO * KILO),
+    ("THz", Dimension::Frequency, KILO * KILO * KILO * KILO),
+    ("ns", Dimension::Duration, 1e-9),
+    ("us", Dimension::Duration, 1e-6),
+    ("ms", Dimension::Duration, 1e-3),
+    ("s", Dimension::Duration, 1.0),
+    ("m", Dimension::Duration, 60.0),
+    ("min", Dimension::Duration, 60.0),
+    ("h", Dimension::Duration, 3600.0),
+    ("d", Dimension::Duration, 86400.0),
+];
+
+impl FromStr for Quantity {
+    type Err = ParseError;
+
+    // Parse quantity in the form of <number><unit>, with optional whitespace between.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let unit_start = input
+            .find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E')
+            .ok_or_else(|| ParseError::new(&format!("No unit in quantity: '{}'", input)))?;
+        let (number, unit) = input.split_at(unit_start);
+
+        let value = number
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| ParseError::new(&format!("Invalid quantity value: '{}'", input)))?;
+        let (_, dimension, multiplier) = UNITS
+            .iter()
+            .find(|(name, _, _)| *name == unit)
+            .ok_or_else(|| ParseError::new(&format!("Unknown unit in quantity: '{}'", input)))?;
+
+        Ok(Quantity {
+            value: value * multiplier,
+            dimension: *dimension,
+        })
+    }
+}
+
+impl PartialOrd for Quantity {
+    // Quantities of different dimensions are not comparable.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.dimension != other.dimension {
+            return None;
+        }
+        self.value.partial_cmp(&other.value)
+    }
+}
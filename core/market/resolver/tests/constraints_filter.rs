@@ -0,0 +1,38 @@
+use ya_market_resolver::{ConstraintsFilter, MatchError};
+
+#[test]
+fn filter_matches_properties() {
+    let filter =
+        ConstraintsFilter::new("(&(golem.runtime.name=vm)(golem.inf.mem.gib>=8))").unwrap();
+
+    assert!(filter
+        .matches(r#"{"golem": {"runtime.name": "vm", "inf.mem.gib": 16}}"#)
+        .unwrap());
+    assert!(!filter
+        .matches(r#"{"golem": {"runtime.name": "vm", "inf.mem.gib": 4}}"#)
+        .unwrap());
+}
+
+#[test]
+fn filter_undefined_is_not_matching() {
+    let filter = ConstraintsFilter::new("(golem.inf.mem.gib>=8)").unwrap();
+
+    assert!(!filter
+        .matches(r#"{"golem": {"runtime.name": "vm"}}"#)
+        .unwrap());
+}
+
+#[test]
+fn filter_empty_matches_all() {
+    let filter = ConstraintsFilter::new("()").unwrap();
+
+    assert!(filter.matches(r#"{}"#).unwrap());
+}
+
+#[test]
+fn filter_invalid_constraints() {
+    match ConstraintsFilter::new("(golem.inf.mem.gib>=8") {
+        Err(MatchError::PrepareError(_)) => (),
+        other => panic!("Expected PrepareError, got: {:?}", other),
+    }
+}
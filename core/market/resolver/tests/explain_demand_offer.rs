@@ -0,0 +1,76 @@
+use ya_market_resolver::{explain_demand_offer, ConstraintsExplanation, Match};
+
+mod sample;
+
+use sample::{
+    POC_DEMAND_CONSTRAINTS, POC_DEMAND_PROPERTIES_JSON, POC_OFFER_CONSTRAINTS,
+    POC_OFFER_PROPERTIES_JSON,
+};
+
+#[test]
+fn explain_matching_should_be_empty() {
+    let explanation = explain_demand_offer(
+        POC_DEMAND_PROPERTIES_JSON,
+        POC_DEMAND_CONSTRAINTS,
+        POC_OFFER_PROPERTIES_JSON,
+        POC_OFFER_CONSTRAINTS,
+    )
+    .unwrap();
+
+    assert_eq!(explanation.result, Match::Yes);
+    assert_eq!(explanation.demand, ConstraintsExplanation::default());
+    assert_eq!(explanation.offer, ConstraintsExplanation::default());
+}
+
+#[test]
+fn explain_lists_all_failing_factors() {
+    let explanation = explain_demand_offer(
+        r#"{"foo": "bar"}"#,
+        "(&(qux=baz)(mem>=8)(cores>=4)(runtime:regex:=^vm$))",
+        r#"{"qux": "baz", "mem": 4, "cores": 2, "runtime": "wasmtime"}"#,
+        "(foo=bar)",
+    )
+    .unwrap();
+
+    assert_eq!(
+        explanation.demand,
+        ConstraintsExplanation {
+            failing: vec![
+                "(mem>=8)".to_string(),
+                "(cores>=4)".to_string(),
+                "(runtime:regex:=^vm$)".to_string(),
+            ],
+            undefined: vec![],
+            missing_properties: vec![],
+        }
+    );
+    assert_eq!(explanation.offer, ConstraintsExplanation::default());
+}
+
+#[test]
+fn explain_reports_missing_properties_on_both_sides() {
+    let explanation = explain_demand_offer(
+        r#"{"foo1": "bar"}"#,
+        "(|(qux=baz)(quux=*))",
+        r#"{"qux": "baz1"}"#,
+        "(&(foo=bar)(!(banned=true)))",
+    )
+    .unwrap();
+
+    assert_eq!(
+        explanation.demand,
+        ConstraintsExplanation {
+            failing: vec!["(qux=baz)".to_string(), "(quux=*)".to_string()],
+            undefined: vec![],
+            missing_properties: vec!["quux".to_string()],
+        }
+    );
+    assert_eq!(
+        explanation.offer,
+        ConstraintsExplanation {
+            failing: vec![],
+            undefined: vec!["(foo=bar)".to_string(), "(!(banned=true))".to_string()],
+            missing_properties: vec!["foo".to_string(), "banned".to_string()],
+        }
+    );
+}
@@ -84,3 +84,51 @@ fn build_expression_and() {
 
     assert_eq!(build_expression(&parse(f).unwrap()), Ok(expression));
 }
+
+#[test]
+fn build_expression_regex() {
+    let f = "(golem.runtime.name:regex:=^vm(-nvidia)?$)";
+
+    let expression = Expression::Regex(
+        PropertyRef::Value(String::from("golem.runtime.name"), PropertyRefType::Any),
+        String::from("^vm(-nvidia)?$"),
+    );
+
+    assert_eq!(build_expression(&parse(f).unwrap()), Ok(expression));
+}
+
+#[test]
+fn build_expression_substring() {
+    let f = "(golem.runtime.capabilities:substr:=vpn)";
+
+    let expression = Expression::Substring(
+        PropertyRef::Value(
+            String::from("golem.runtime.capabilities"),
+            PropertyRefType::Any,
+        ),
+        String::from("vpn"),
+    );
+
+    assert_eq!(build_expression(&parse(f).unwrap()), Ok(expression));
+}
+
+#[test]
+fn build_expression_regex_invalid() {
+    let f = "(golem.runtime.name:regex:=vm[)";
+
+    assert!(build_expression(&parse(f).unwrap()).is_err());
+}
+
+#[test]
+fn build_expression_unknown_rule() {
+    let f = "(golem.runtime.name:soundex:=vm)";
+
+    assert!(build_expression(&parse(f).unwrap()).is_err());
+}
+
+#[test]
+fn expression_display_is_ldap_filter() {
+    let f = "(&(a=b)(|(c>=1)(d$v<1.0.0))(!(e[f]=*))(g:regex:=^h(i)?$)(j:substr:=k))";
+
+    assert_eq!(build_expression(&parse(f).unwrap()).unwrap().to_string(), f);
+}
@@ -294,11 +294,15 @@ fn resolve_greater_equal_version_with_implied_type() {
 
     run_resolve_test("(cn$v>=1.5.0)", &vec!["cn=\"1.10.0\""], ResolveResult::True);
 
+    // test positive - strings, which look like versions, are compared as versions anyway
+
+    run_resolve_test("(cn>=1.5.0)", &vec!["cn=\"1.10.0\""], ResolveResult::True);
+
     // test negative
 
     run_resolve_test(
         "(cn>=1.5.0)",
-        &vec!["cn=\"1.10.0\""],
+        &vec!["cn=\"1.4.10\""],
         ResolveResult::False(vec![], Expression::Empty(false)),
     );
 
@@ -684,3 +688,132 @@ fn resolve_complex_and_undefined_reduced() {
         ResolveResult::False(vec![], Expression::Empty(false)),
     );
 }
+
+#[test]
+fn resolve_regex() {
+    let f = "(golem.runtime.name:regex:=^vm(-nvidia)?$)";
+
+    // test positive
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.runtime.name="vm-nvidia""#],
+        ResolveResult::True,
+    );
+    run_resolve_test(f, &vec![r#"golem.runtime.name="vm""#], ResolveResult::True);
+
+    // test negative
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.runtime.name="wasmtime""#],
+        ResolveResult::False(vec![], Expression::Empty(false)),
+    );
+
+    // test undefined
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.runtime.version="0.2.10""#],
+        ResolveResult::Undefined(
+            vec![&PropertyRef::Value(
+                String::from("golem.runtime.name"),
+                PropertyRefType::Any,
+            )],
+            Expression::Regex(
+                PropertyRef::Value(String::from("golem.runtime.name"), PropertyRefType::Any),
+                String::from("^vm(-nvidia)?$"),
+            ),
+        ),
+    );
+}
+
+#[test]
+fn resolve_regex_list() {
+    let f = "(golem.runtime.capabilities:regex:=^(vpn|manifest-support)$)";
+
+    // test positive
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.runtime.capabilities=["inet","vpn"]"#],
+        ResolveResult::True,
+    );
+
+    // test negative
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.runtime.capabilities=["inet","vpn-legacy"]"#],
+        ResolveResult::False(vec![], Expression::Empty(false)),
+    );
+}
+
+#[test]
+fn resolve_substring() {
+    let f = "(golem.inf.cpu.model:substr:=EPYC)";
+
+    // test positive
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.inf.cpu.model="AMD EPYC 7502P""#],
+        ResolveResult::True,
+    );
+
+    // test negative
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.inf.cpu.model="Intel Xeon""#],
+        ResolveResult::False(vec![], Expression::Empty(false)),
+    );
+}
+
+#[test]
+fn resolve_greater_equal_version_string() {
+    let f = "(golem.runtime.version>=0.2.10)";
+
+    // test positive
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.runtime.version="0.2.10""#],
+        ResolveResult::True,
+    );
+    run_resolve_test(
+        f,
+        &vec![r#"golem.runtime.version="0.10.0""#],
+        ResolveResult::True,
+    );
+
+    // test negative (lexicographically "0.2.9" > "0.2.10")
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.runtime.version="0.2.9""#],
+        ResolveResult::False(vec![], Expression::Empty(false)),
+    );
+}
+
+#[test]
+fn resolve_less_quantity_string() {
+    let f = "(golem.inf.mem<8GiB)";
+
+    // test positive
+
+    run_resolve_test(f, &vec![r#"golem.inf.mem="512MiB""#], ResolveResult::True);
+
+    // test negative
+
+    run_resolve_test(
+        f,
+        &vec![r#"golem.inf.mem="16GiB""#],
+        ResolveResult::False(vec![], Expression::Empty(false)),
+    );
+    run_resolve_test(
+        f,
+        &vec![r#"golem.inf.mem="2.5GHz""#],
+        ResolveResult::False(vec![], Expression::Empty(false)),
+    );
+}
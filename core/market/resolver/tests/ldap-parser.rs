@@ -302,3 +302,108 @@ fn and_whitespace() {
 
     assert_eq!(parse(f), Ok(tag));
 }
+
+#[test]
+fn extensible_regex() {
+    let f = "(cn:regex:=^Babs( Jensen)?$)";
+
+    let tag = Tag::Sequence(Sequence {
+        class: TagClass::Context,
+        id: TAG_EXTENSIBLE,
+        inner: vec![
+            Tag::OctetString(OctetString {
+                inner: b"cn".to_vec(),
+                ..Default::default()
+            }),
+            Tag::OctetString(OctetString {
+                inner: b"regex".to_vec(),
+                ..Default::default()
+            }),
+            Tag::OctetString(OctetString {
+                inner: b"^Babs( Jensen)?$".to_vec(),
+                ..Default::default()
+            }),
+        ],
+    });
+
+    assert_eq!(parse(f), Ok(tag));
+}
+
+#[test]
+fn extensible_escaped_bracket() {
+    let f = r"(cn:regex:=\))";
+
+    let tag = Tag::Sequence(Sequence {
+        class: TagClass::Context,
+        id: TAG_EXTENSIBLE,
+        inner: vec![
+            Tag::OctetString(OctetString {
+                inner: b"cn".to_vec(),
+                ..Default::default()
+            }),
+            Tag::OctetString(OctetString {
+                inner: b"regex".to_vec(),
+                ..Default::default()
+            }),
+            Tag::OctetString(OctetString {
+                inner: br"\)".to_vec(),
+                ..Default::default()
+            }),
+        ],
+    });
+
+    assert_eq!(parse(f), Ok(tag));
+}
+
+#[test]
+fn extensible_unbalanced_bracket() {
+    let f = "(cn:regex:=Babs(Jensen)";
+
+    assert!(parse(f).is_err());
+}
+
+#[test]
+fn extensible_in_and() {
+    let f = "(&(cn:substr:=Babs)(a=b))";
+
+    let tag = Tag::Sequence(Sequence {
+        class: TagClass::Context,
+        id: TAG_AND,
+        inner: vec![
+            Tag::Sequence(Sequence {
+                class: TagClass::Context,
+                id: TAG_EXTENSIBLE,
+                inner: vec![
+                    Tag::OctetString(OctetString {
+                        inner: b"cn".to_vec(),
+                        ..Default::default()
+                    }),
+                    Tag::OctetString(OctetString {
+                        inner: b"substr".to_vec(),
+                        ..Default::default()
+                    }),
+                    Tag::OctetString(OctetString {
+                        inner: b"Babs".to_vec(),
+                        ..Default::default()
+                    }),
+                ],
+            }),
+            Tag::Sequence(Sequence {
+                class: TagClass::Context,
+                id: TAG_EQUAL,
+                inner: vec![
+                    Tag::OctetString(OctetString {
+                        inner: b"a".to_vec(),
+                        ..Default::default()
+                    }),
+                    Tag::OctetString(OctetString {
+                        inner: b"b".to_vec(),
+                        ..Default::default()
+                    }),
+                ],
+            }),
+        ],
+    });
+
+    assert_eq!(parse(f), Ok(tag));
+}
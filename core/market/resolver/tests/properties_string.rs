@@ -31,3 +31,42 @@ fn equals_for_strings_wildcard_false() {
 }
 
 // #endregion
+
+// #region Extensible match
+
+#[test]
+fn matches_regex_for_strings_true() {
+    let prop_value = PropertyValue::Str("vm-nvidia");
+
+    assert!(prop_value.matches_regex("^vm(-nvidia)?$"));
+}
+
+#[test]
+fn matches_regex_for_strings_false() {
+    let prop_value = PropertyValue::Str("wasmtime");
+
+    assert!(!prop_value.matches_regex("^vm(-nvidia)?$"));
+}
+
+#[test]
+fn matches_regex_for_numbers_false() {
+    let prop_value = PropertyValue::Number(1.0);
+
+    assert!(!prop_value.matches_regex(".*"));
+}
+
+#[test]
+fn contains_for_strings_true() {
+    let prop_value = PropertyValue::Str("AMD EPYC 7502P");
+
+    assert!(prop_value.contains("EPYC"));
+}
+
+#[test]
+fn contains_for_strings_false() {
+    let prop_value = PropertyValue::Str("AMD EPYC 7502P");
+
+    assert!(!prop_value.contains("Xeon"));
+}
+
+// #endregion
@@ -0,0 +1,106 @@
+use ya_market_resolver::resolver::properties::*;
+use ya_market_resolver::resolver::units::*;
+
+// #region Quantity parsing
+
+#[test]
+fn parse_quantity_bytes() {
+    assert_eq!(
+        "8GiB".parse::<Quantity>().unwrap(),
+        Quantity {
+            value: 8.0 * 1024.0 * 1024.0 * 1024.0,
+            dimension: Dimension::Bytes
+        }
+    );
+    assert_eq!(
+        "1.5 kB".parse::<Quantity>().unwrap(),
+        Quantity {
+            value: 1500.0,
+            dimension: Dimension::Bytes
+        }
+    );
+}
+
+#[test]
+fn parse_quantity_frequency_and_duration() {
+    assert_eq!(
+        "2.5GHz".parse::<Quantity>().unwrap(),
+        Quantity {
+            value: 2.5e9,
+            dimension: Dimension::Frequency
+        }
+    );
+    assert_eq!(
+        "1h".parse::<Quantity>().unwrap(),
+        Quantity {
+            value: 3600.0,
+            dimension: Dimension::Duration
+        }
+    );
+}
+
+#[test]
+fn parse_quantity_invalid() {
+    assert!("8".parse::<Quantity>().is_err());
+    assert!("GiB".parse::<Quantity>().is_err());
+    assert!("8 parsecs".parse::<Quantity>().is_err());
+    assert!("0.2.10".parse::<Quantity>().is_err());
+}
+
+// #endregion
+
+// #region String values with units
+
+#[test]
+fn equals_for_quantity_strings_true() {
+    let prop_value = PropertyValue::Str("8GiB");
+
+    assert!(prop_value.equals("8192MiB"));
+}
+
+#[test]
+fn less_for_quantity_strings_true() {
+    let prop_value = PropertyValue::Str("900MHz");
+
+    // lexicographically "900MHz" > "2.5GHz"
+    assert!(prop_value.less("2.5GHz"));
+}
+
+#[test]
+fn greater_equal_for_quantity_strings_true() {
+    let prop_value = PropertyValue::Str("90min");
+
+    assert!(prop_value.greater_equal("1h"));
+    assert!(prop_value.greater_equal("5400s"));
+}
+
+#[test]
+fn compare_quantity_strings_of_different_dimensions_false() {
+    let prop_value = PropertyValue::Str("8GiB");
+
+    assert!(!prop_value.less("1h"));
+    assert!(!prop_value.greater("1h"));
+    assert!(!prop_value.equals("1h"));
+}
+
+// #endregion
+
+// #region String values with versions
+
+#[test]
+fn less_for_version_strings_true() {
+    let prop_value = PropertyValue::Str("0.2.9");
+
+    assert!(prop_value.less("0.2.10"));
+    assert!(!prop_value.greater_equal("0.2.10"));
+}
+
+#[test]
+fn compare_plain_strings_lexicographically() {
+    let prop_value = PropertyValue::Str("abc");
+
+    assert!(prop_value.less("abd"));
+    assert!(prop_value.greater("ab"));
+}
+
+// #endregion
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use env_logger::{Builder, Env, Target};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+use ya_market::testing::simulation::{self, Scenario};
+
+/// Runs scenario on in-process markets connected by mocked GSB
+/// and prints report with matching latencies and Proposal counts.
+#[derive(StructOpt)]
+struct Args {
+    /// Scenario file in json format
+    scenario: PathBuf,
+    /// Write report to file instead of stdout
+    #[structopt(long)]
+    output: Option<PathBuf>,
+}
+
+#[actix_rt::main]
+async fn main() -> Result<()> {
+    let mut builder = Builder::from_env(Env::new().default_filter_or("info"));
+    builder.target(Target::Stderr);
+    builder.init();
+
+    let args = Args::from_args();
+    let scenario = Scenario::load(&args.scenario)?;
+    let report = simulation::run(&scenario).await?;
+
+    let report = serde_json::to_string_pretty(&report)?;
+    match args.output {
+        Some(path) => std::fs::write(path, report)?,
+        None => println!("{}", report),
+    }
+    Ok(())
+}
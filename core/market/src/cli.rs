@@ -1,20 +1,52 @@
 use chrono::{DateTime, Utc};
+use std::path::PathBuf;
 use structopt::StructOpt;
 use ya_client::model::market::{agreement::State, Role};
-use ya_core_model::market::{GetAgreement, ListAgreements};
+use ya_client::model::NodeId;
+use ya_core_model::market::{
+    AgreementStatistics, BlockNode, ExplainMatch, ExportAgreements, GetAgreement, ListAgreements,
+    ListBlockedNodes, UnblockNode,
+};
 use ya_service_api::{CliCtx, CommandOutput, ResponseTable};
 use ya_service_bus::{typed as bus, RpcEndpoint};
 
+use crate::market::export::{self, ExportFormat};
+
 /// Market management
 #[derive(StructOpt, Debug)]
 pub enum Command {
     Agreements(AgreementsCommand),
+    /// Manage nodes, which Offers are never matched with local Demands
+    Blocklist(BlocklistCommand),
+    /// Explain why Demand does (not) match Offer
+    Explain {
+        #[structopt(long, help = "Demand subscription id")]
+        demand_id: String,
+        #[structopt(long, help = "Offer id")]
+        offer_id: String,
+    },
 }
 
 impl Command {
     pub async fn run_command(self, ctx: &CliCtx) -> anyhow::Result<CommandOutput> {
         match self {
             Command::Agreements(agreements_cmd) => agreements_cmd.run_command(ctx).await,
+            Command::Blocklist(blocklist_cmd) => blocklist_cmd.run_command(ctx).await,
+            Command::Explain {
+                demand_id,
+                offer_id,
+            } => {
+                let request = ExplainMatch {
+                    demand_id,
+                    offer_id,
+                };
+
+                let explanation = bus::service(ya_core_model::market::local::BUS_ID)
+                    .send(request)
+                    .await??;
+
+                CommandOutput::object(explanation)
+            }
         }
     }
 }
@@ -37,10 +69,30 @@ pub enum AgreementsCommand {
         #[structopt(long, help = "Your role in the agreement (Provider | Requestor)")]
         role: Role,
     },
+    /// Export agreements with their terms, termination reasons and summary statistics
+    Export {
+        #[structopt(long, help = "Only export agreements with this node")]
+        peer: Option<NodeId>,
+        #[structopt(long, help = "Only export agreements with this state")]
+        state: Option<State>,
+        #[structopt(
+            long,
+            help = "Only export agreements created before this date, rfc3339"
+        )]
+        before: Option<DateTime<Utc>>,
+        #[structopt(long, help = "Only export agreements created after this date, rfc3339")]
+        after: Option<DateTime<Utc>>,
+        #[structopt(long, help = "Only export agreements with this app session id")]
+        app_session_id: Option<String>,
+        #[structopt(long, default_value = "json", help = "Output format (json | csv)")]
+        format: ExportFormat,
+        #[structopt(long, help = "Write export to file and print only statistics")]
+        output: Option<PathBuf>,
+    },
 }
 
 impl AgreementsCommand {
-    pub async fn run_command(self, _ctx: &CliCtx) -> anyhow::Result<CommandOutput> {
+    pub async fn run_command(self, ctx: &CliCtx) -> anyhow::Result<CommandOutput> {
         match self {
             AgreementsCommand::List {
                 state,
@@ -95,6 +147,133 @@ impl AgreementsCommand {
 
                 CommandOutput::object(agreement)
             }
+            AgreementsCommand::Export {
+                peer,
+                state,
+                before,
+                after,
+                app_session_id,
+                format,
+                output,
+            } => {
+                let request = ExportAgreements {
+                    peer_id: peer,
+                    state,
+                    before_date: before,
+                    after_date: after,
+                    app_session_id,
+                };
+
+                let export = bus::service(ya_core_model::market::local::BUS_ID)
+                    .send(request)
+                    .await??;
+
+                let path = match (output, format) {
+                    (Some(path), _) => path,
+                    (None, ExportFormat::Json) => return CommandOutput::object(export),
+                    (None, ExportFormat::Csv) => {
+                        print!("{}", export::to_csv(&export.agreements));
+                        return Ok(CommandOutput::NoOutput);
+                    }
+                };
+
+                let content = match format {
+                    ExportFormat::Json => serde_json::to_string_pretty(&export)?,
+                    ExportFormat::Csv => export::to_csv(&export.agreements),
+                };
+                std::fs::write(&path, content)?;
+
+                if ctx.json_output {
+                    return CommandOutput::object(export.statistics);
+                }
+                Ok(statistics_table(&export.statistics)?.with_header(format!(
+                    "\nExported {} agreements to {}:\n",
+                    export.statistics.count,
+                    path.display()
+                )))
+            }
+        }
+    }
+}
+
+fn statistics_table(statistics: &AgreementStatistics) -> anyhow::Result<ResponseTable> {
+    let average_duration = statistics
+        .average_duration_secs
+        .map(|secs| format!("{:.1}s", secs))
+        .unwrap_or_else(|| "N/A".to_owned());
+
+    let mut values = vec![serde_json::to_value([
+        "count".to_owned(),
+        statistics.count.to_string(),
+    ])?];
+    for (state, count) in &statistics.states {
+        values.push(serde_json::to_value([
+            format!("state: {}", state),
+            count.to_string(),
+        ])?);
+    }
+    values.push(serde_json::to_value([
+        "average duration".to_owned(),
+        average_duration,
+    ])?);
+    for (reason, count) in &statistics.termination_reasons {
+        values.push(serde_json::to_value([
+            format!("terminated: {}", reason),
+            count.to_string(),
+        ])?);
+    }
+
+    Ok(ResponseTable {
+        columns: vec!["statistic".to_owned(), "value".to_owned()],
+        values,
+    })
+}
+
+#[derive(StructOpt, Debug)]
+pub enum BlocklistCommand {
+    List,
+    Add {
+        #[structopt(help = "Node id to block")]
+        node_id: NodeId,
+        #[structopt(long, help = "Why node was blocked")]
+        reason: Option<String>,
+    },
+    Remove {
+        #[structopt(help = "Node id to unblock")]
+        node_id: NodeId,
+    },
+}
+
+impl BlocklistCommand {
+    pub async fn run_command(self, _ctx: &CliCtx) -> anyhow::Result<CommandOutput> {
+        let service = bus::service(ya_core_model::market::local::BUS_ID);
+        match self {
+            BlocklistCommand::List => {
+                let nodes = service.send(ListBlockedNodes {}).await??;
+
+                let mut nodes_json = Vec::new();
+                for node in nodes {
+                    nodes_json.push(serde_json::to_value([
+                        node.node_id.to_string(),
+                        node.blocked_date.to_rfc3339(),
+                        node.reason.unwrap_or_default(),
+                    ])?);
+                }
+
+                Ok(ResponseTable {
+                    columns: vec!["node".to_owned(), "blocked".to_owned(), "reason".to_owned()],
+                    values: nodes_json,
+                }
+                .with_header("\nBlocked nodes:\n".to_owned()))
+            }
+            BlocklistCommand::Add { node_id, reason } => {
+                let node = service.send(BlockNode { node_id, reason }).await??;
+                CommandOutput::object(node)
+            }
+            BlocklistCommand::Remove { node_id } => {
+                service.send(UnblockNode { node_id }).await??;
+                Ok(CommandOutput::NoOutput)
+            }
         }
     }
 }
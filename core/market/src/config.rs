@@ -1,6 +1,8 @@
 use std::time::Duration;
 use structopt::StructOpt;
 
+use crate::matcher::schema::{SchemaValidation, SchemaVersion};
+
 #[derive(StructOpt, Clone)]
 pub struct Config {
     #[structopt(flatten)]
@@ -11,11 +13,23 @@ pub struct Config {
     pub events: EventsConfig,
     #[structopt(flatten)]
     pub db: DbConfig,
+    #[structopt(flatten)]
+    pub index: IndexConfig,
+    #[structopt(flatten)]
+    pub query: QueryConfig,
+    #[structopt(flatten)]
+    pub scoring: ScoringConfig,
+    #[structopt(flatten)]
+    pub schema: SchemaConfig,
+    #[structopt(flatten)]
+    pub agreements: AgreementConfig,
 }
 
 #[derive(StructOpt, Clone)]
 pub struct DiscoveryConfig {
     // don't set this value higher than SQLITE_MAX_VARIABLE_NUMBER, which defaults to 999 for SQLite versions prior to 3.32.0 (2020-05-22)
+    /// Max number of Offers broadcasted and served to other nodes at once.
+    /// 0 disables Offers broadcasting and retrieval.
     #[structopt(env, default_value = "200")]
     pub max_bcasted_offers: u32,
     #[structopt(env, default_value = "200")]
@@ -28,6 +42,20 @@ pub struct DiscoveryConfig {
     pub offer_broadcast_delay: Duration,
     #[structopt(env, parse(try_from_str = humantime::parse_duration), default_value = "5sec")]
     pub unsub_broadcast_delay: Duration,
+    /// Max number of Offer broadcasts accepted from single node in `rate_limit_window`.
+    /// 0 disables limit.
+    #[structopt(env, default_value = "120")]
+    pub max_incoming_bcasts_per_node: u32,
+    /// Max number of Offer retrieval requests served to single node in `rate_limit_window`.
+    /// 0 disables limit.
+    #[structopt(env, default_value = "120")]
+    pub max_offer_retrievals_per_node: u32,
+    #[structopt(env, parse(try_from_str = humantime::parse_duration), default_value = "1min")]
+    pub rate_limit_window: Duration,
+    /// Max number of active Offers from single Provider node stored in database.
+    /// Offers subscribed locally are not limited. 0 disables limit.
+    #[structopt(env, default_value = "1000")]
+    pub max_offers_per_node: u32,
 }
 
 #[derive(StructOpt, Clone)]
@@ -42,6 +70,17 @@ pub struct EventsConfig {
     pub max_events_default: i32,
     #[structopt(env = "MARKET_MAX_EVENTS_MAX", default_value = "100")]
     pub max_events_max: i32,
+    /// Interval of keep-alive messages sent on idle event streams.
+    #[structopt(env = "MARKET_EVENTS_STREAM_KEEPALIVE", parse(try_from_str = humantime::parse_duration), default_value = "15s")]
+    pub stream_keepalive_interval: Duration,
+}
+
+#[derive(StructOpt, Clone)]
+pub struct QueryConfig {
+    #[structopt(env = "MARKET_QUERY_OFFERS_LIMIT_DEFAULT", default_value = "50")]
+    pub offers_limit_default: i32,
+    #[structopt(env = "MARKET_QUERY_OFFERS_LIMIT_MAX", default_value = "500")]
+    pub offers_limit_max: i32,
 }
 
 #[derive(StructOpt, Clone)]
@@ -57,6 +96,65 @@ pub struct DbConfig {
     pub event_store_days: i32,
 }
 
+#[derive(StructOpt, Clone)]
+pub struct IndexConfig {
+    /// Comma separated list of Offer properties indexed by value.
+    /// Demands constraining them with `=` will be resolved only against matching Offers.
+    #[structopt(
+        env = "MARKET_INDEXED_PROPERTIES",
+        default_value = "golem.runtime.name,golem.inf.cpu.architecture"
+    )]
+    pub indexed_properties: String,
+    /// Comma separated list of numeric Offer properties indexed by range.
+    /// Demands constraining them with `=`, `<`, `<=`, `>`, `>=` will be resolved
+    /// only against matching Offers.
+    #[structopt(
+        env = "MARKET_INDEXED_NUMERIC_PROPERTIES",
+        default_value = "golem.inf.mem.gib,golem.inf.storage.gib,golem.inf.cpu.cores,golem.inf.cpu.threads"
+    )]
+    pub indexed_numeric_properties: String,
+}
+
+impl IndexConfig {
+    /// Index, that doesn't narrow down Offers, so Resolver checks all of them.
+    pub fn disabled() -> IndexConfig {
+        IndexConfig {
+            indexed_properties: String::new(),
+            indexed_numeric_properties: String::new(),
+        }
+    }
+}
+
+#[derive(StructOpt, Clone)]
+pub struct ScoringConfig {
+    /// Arithmetic expression over Offer properties used to score Proposals on Requestor side,
+    /// for example `golem.inf.mem.gib / golem.com.pricing.model.linear.coeffs[1]`.
+    /// Proposals aren't scored, if expression isn't set.
+    #[structopt(env = "MARKET_PROPOSAL_SCORE_EXPRESSION")]
+    pub proposal_score_expression: Option<String>,
+}
+
+#[derive(StructOpt, Clone)]
+pub struct SchemaConfig {
+    /// Validation of Offer and Demand properties against schema of well-known `golem.*`
+    /// properties: `disabled`, `warn` (violations are returned to caller as warnings)
+    /// or `strict` (subscriptions with violations are rejected).
+    #[structopt(env = "MARKET_PROPERTY_SCHEMA_VALIDATION", default_value = "warn")]
+    pub validation: SchemaValidation,
+    #[structopt(env = "MARKET_PROPERTY_SCHEMA_VERSION", default_value = "1")]
+    pub version: SchemaVersion,
+}
+
+#[derive(StructOpt, Clone)]
+pub struct AgreementConfig {
+    /// Max number of Proposals promoted to Agreements in single bulk request.
+    #[structopt(env = "MARKET_MAX_BULK_AGREEMENTS", default_value = "100")]
+    pub max_bulk_agreements: usize,
+    /// Number of Agreements from single bulk request created and confirmed concurrently.
+    #[structopt(env = "MARKET_BULK_AGREEMENTS_CONCURRENCY", default_value = "10")]
+    pub bulk_agreements_concurrency: usize,
+}
+
 impl Config {
     pub fn from_env() -> Result<Config, structopt::clap::Error> {
         // Empty command line arguments, because we want to use ENV fallback
@@ -72,6 +170,7 @@ fn parse_chrono_duration(s: &str) -> Result<chrono::Duration, anyhow::Error> {
 #[cfg(test)]
 mod test {
     use super::Config;
+    use crate::matcher::schema::{SchemaValidation, SchemaVersion};
 
     #[test]
     fn test_default_structopt_subscription_ttl() {
@@ -79,11 +178,35 @@ mod test {
         assert_eq!(60, c.subscription.default_ttl.num_minutes());
     }
 
+    #[test]
+    fn test_default_structopt_discovery_limits() {
+        let c = Config::from_env().unwrap();
+        assert_eq!(120, c.discovery.max_incoming_bcasts_per_node);
+        assert_eq!(120, c.discovery.max_offer_retrievals_per_node);
+        assert_eq!(60, c.discovery.rate_limit_window.as_secs());
+        assert_eq!(1000, c.discovery.max_offers_per_node);
+    }
+
     #[test]
     fn test_default_structopt_events() {
         let c = Config::from_env().unwrap();
         assert_eq!(20, c.events.max_events_default);
         assert_eq!(100, c.events.max_events_max);
+        assert_eq!(15, c.events.stream_keepalive_interval.as_secs());
+    }
+
+    #[test]
+    fn test_default_structopt_agreements() {
+        let c = Config::from_env().unwrap();
+        assert_eq!(100, c.agreements.max_bulk_agreements);
+        assert_eq!(10, c.agreements.bulk_agreements_concurrency);
+    }
+
+    #[test]
+    fn test_default_structopt_query_config() {
+        let c = Config::from_env().unwrap();
+        assert_eq!(50, c.query.offers_limit_default);
+        assert_eq!(500, c.query.offers_limit_max);
     }
 
     #[test]
@@ -93,4 +216,30 @@ mod test {
         assert_eq!(90, c.db.agreement_store_days);
         assert_eq!(1, c.db.event_store_days);
     }
+
+    #[test]
+    fn test_default_structopt_index_config() {
+        let c = Config::from_env().unwrap();
+        assert_eq!(
+            "golem.runtime.name,golem.inf.cpu.architecture",
+            c.index.indexed_properties
+        );
+        assert_eq!(
+            "golem.inf.mem.gib,golem.inf.storage.gib,golem.inf.cpu.cores,golem.inf.cpu.threads",
+            c.index.indexed_numeric_properties
+        );
+    }
+
+    #[test]
+    fn test_default_structopt_scoring_config() {
+        let c = Config::from_env().unwrap();
+        assert_eq!(None, c.scoring.proposal_score_expression);
+    }
+
+    #[test]
+    fn test_default_structopt_schema_config() {
+        let c = Config::from_env().unwrap();
+        assert_eq!(SchemaValidation::Warn, c.schema.validation);
+        assert_eq!(SchemaVersion::V1, c.schema.version);
+    }
 }
@@ -1,5 +1,7 @@
 mod agreement;
+mod agreement_amendment;
 mod agreement_events;
+mod blocked_node;
 pub mod cleaner;
 mod demand;
 mod negotiation_events;
@@ -15,7 +17,9 @@ mod offer;
 mod proposal;
 
 pub use agreement::{AgreementDao, AgreementDaoError, SaveAgreementError};
+pub use agreement_amendment::{AgreementAmendmentDao, AmendmentDaoError};
 pub use agreement_events::AgreementEventsDao;
+pub use blocked_node::BlockedNodeDao;
 pub use demand::{DemandDao, DemandState};
 pub use negotiation_events::{NegotiationEventsDao, TakeEventsError};
 pub use offer::{OfferDao, OfferState};
@@ -1,5 +1,7 @@
 use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
+use diesel::sqlite::Sqlite;
+use std::collections::HashMap;
 
 use ya_client::model::market::Reason;
 use ya_client::model::NodeId;
@@ -10,11 +12,14 @@ use crate::db::dao::agreement_events::create_event;
 use crate::db::dao::proposal::{has_counter_proposal, update_proposal_state};
 use crate::db::dao::sql_functions::datetime;
 use crate::db::model::{
-    check_transition, Agreement, AgreementId, AgreementState, AppSessionId, Owner, ProposalId,
-    ProposalIdParseError, ProposalState,
+    check_transition, Agreement, AgreementEvent, AgreementId, AgreementState, AppSessionId, Owner,
+    ProposalId, ProposalIdParseError, ProposalState,
 };
+use crate::db::schema::market_agreement as market_agreement_table;
 use crate::db::schema::market_agreement::dsl as agreement;
 use crate::db::schema::market_agreement::dsl::market_agreement;
+use crate::db::schema::market_agreement_amendment::dsl as amendment;
+use crate::db::schema::market_agreement_amendment::dsl::market_agreement_amendment;
 use crate::db::schema::market_agreement_event::dsl as event;
 use crate::db::schema::market_agreement_event::dsl::market_agreement_event;
 use crate::db::{AsMixedDao, DbError, DbResult};
@@ -70,35 +75,62 @@ impl<'c> AgreementDao<'c> {
         app_session_id: Option<String>,
     ) -> Result<Vec<Agreement>, AgreementDaoError> {
         do_with_transaction(self.pool, move |conn| {
-            let mut query = market_agreement.into_boxed();
-
-            if let Some(node_id) = node_id {
-                query = query.filter(
-                    agreement::provider_id
-                        .eq(node_id)
-                        .or(agreement::requestor_id.eq(node_id)),
-                );
-            };
-
-            if let Some(app_session_id) = app_session_id {
-                query = query.filter(agreement::session_id.eq(app_session_id))
-            }
+            let query = filter_agreements(node_id, None, state, before, after, app_session_id);
+            let agreements = query.get_results::<Agreement>(conn)?;
 
-            if let Some(state) = state {
-                query = query.filter(agreement::state.eq(state));
-            }
+            Ok(agreements)
+        })
+        .await
+    }
 
-            if let Some(before) = before {
-                query = query.filter(agreement::creation_ts.lt(before.naive_utc()));
-            }
+    /// Lists Agreements together with their events ordered by timestamp.
+    /// Unlike `list`, it can be limited to Agreements with given peer.
+    pub async fn list_with_events(
+        &self,
+        node_id: Option<NodeId>,
+        peer_id: Option<NodeId>,
+        state: Option<AgreementState>,
+        before: Option<DateTime<Utc>>,
+        after: Option<DateTime<Utc>>,
+        app_session_id: Option<String>,
+    ) -> Result<Vec<(Agreement, Vec<AgreementEvent>)>, AgreementDaoError> {
+        readonly_transaction(self.pool, move |conn| {
+            let filter = || {
+                filter_agreements(
+                    node_id,
+                    peer_id,
+                    state,
+                    before,
+                    after,
+                    app_session_id.clone(),
+                )
+            };
 
-            if let Some(after) = after {
-                query = query.filter(agreement::creation_ts.gt(after.naive_utc()));
+            let agreements = filter()
+                .order_by(agreement::creation_ts.asc())
+                .get_results::<Agreement>(conn)?;
+            let events = market_agreement_event
+                .filter(event::agreement_id.eq_any(filter().select(agreement::id)))
+                .order_by(event::timestamp.asc())
+                .load::<AgreementEvent>(conn)?;
+
+            let mut events_by_agreement = HashMap::<AgreementId, Vec<AgreementEvent>>::new();
+            for event in events {
+                events_by_agreement
+                    .entry(event.agreement_id.clone())
+                    .or_default()
+                    .push(event);
             }
 
-            let agreements = query.get_results::<Agreement>(conn)?;
-
-            Ok(agreements)
+            Ok(agreements
+                .into_iter()
+                .map(|agreement| {
+                    let events = events_by_agreement
+                        .remove(&agreement.id)
+                        .unwrap_or_default();
+                    (agreement, events)
+                })
+                .collect())
         })
         .await
     }
@@ -398,7 +430,12 @@ impl<'c> AgreementDao<'c> {
                 event::agreement_id.eq_any(agreements_to_clean.clone().select(agreement::id)),
             );
 
+            let related_amendments = market_agreement_amendment.filter(
+                amendment::agreement_id.eq_any(agreements_to_clean.clone().select(agreement::id)),
+            );
+
             let num_events = diesel::delete(related_events).execute(conn)?;
+            diesel::delete(related_amendments).execute(conn)?;
             let num_agreements = diesel::delete(agreements_to_clean).execute(conn)?;
             Result::<(usize, usize), DbError>::Ok((num_agreements, num_events))
         })
@@ -413,6 +450,52 @@ impl<'c> AgreementDao<'c> {
     }
 }
 
+fn filter_agreements(
+    node_id: Option<NodeId>,
+    peer_id: Option<NodeId>,
+    state: Option<AgreementState>,
+    before: Option<DateTime<Utc>>,
+    after: Option<DateTime<Utc>>,
+    app_session_id: Option<String>,
+) -> market_agreement_table::BoxedQuery<'static, Sqlite> {
+    let mut query = market_agreement.into_boxed();
+
+    if let Some(node_id) = node_id {
+        query = query.filter(
+            agreement::provider_id
+                .eq(node_id)
+                .or(agreement::requestor_id.eq(node_id)),
+        );
+    };
+
+    // Provider and Requestor are always different nodes, so together with `node_id`
+    // this filter leaves only Agreements between these two nodes.
+    if let Some(peer_id) = peer_id {
+        query = query.filter(
+            agreement::provider_id
+                .eq(peer_id)
+                .or(agreement::requestor_id.eq(peer_id)),
+        );
+    };
+
+    if let Some(app_session_id) = app_session_id {
+        query = query.filter(agreement::session_id.eq(app_session_id))
+    }
+
+    if let Some(state) = state {
+        query = query.filter(agreement::state.eq(state));
+    }
+
+    if let Some(before) = before {
+        query = query.filter(agreement::creation_ts.lt(before.naive_utc()));
+    }
+
+    if let Some(after) = after {
+        query = query.filter(agreement::creation_ts.gt(after.naive_utc()));
+    }
+    query
+}
+
 fn find_agreement_for_proposal(
     conn: &ConnType,
     proposal_id: &ProposalId,
@@ -0,0 +1,249 @@
+use diesel::prelude::*;
+
+use ya_client::model::market::Reason;
+use ya_persistence::executor::{do_with_transaction, readonly_transaction, ConnType, PoolType};
+
+use crate::db::dao::agreement_events::{create_amendment_event, remove_amendment_event};
+use crate::db::model::{
+    Agreement, AgreementAmendment, AgreementEventType, AgreementId, AgreementState, AmendmentState,
+    DbReason,
+};
+use crate::db::schema::market_agreement::dsl as agreement;
+use crate::db::schema::market_agreement::dsl::market_agreement;
+use crate::db::schema::market_agreement_amendment::dsl as amendment;
+use crate::db::schema::market_agreement_amendment::dsl::market_agreement_amendment;
+use crate::db::{AsMixedDao, DbError, DbResult};
+
+#[derive(thiserror::Error, Debug)]
+pub enum AmendmentDaoError {
+    #[error("Amendment [{0}] not found.")]
+    NotFound(String),
+    #[error("Amendment [{0}] is already {1}.")]
+    NotPending(String, AmendmentState),
+    #[error("Agreement [{0}] in state {1} can't be amended.")]
+    InvalidAgreementState(AgreementId, AgreementState),
+    #[error("Failed to apply amendment [{0}] to Agreement properties. Error: {1}")]
+    Properties(String, String),
+    #[error("Failed to update amendment. Error: {0}")]
+    DbError(DbError),
+}
+
+/// Amendments are stored together with Agreements on disk.
+pub struct AgreementAmendmentDao<'c> {
+    pool: &'c PoolType,
+}
+
+impl<'a> AsMixedDao<'a> for AgreementAmendmentDao<'a> {
+    fn as_dao(disk_pool: &'a PoolType, _ram_pool: &'a PoolType) -> Self {
+        Self { pool: disk_pool }
+    }
+}
+
+impl<'c> AgreementAmendmentDao<'c> {
+    /// Stores amendment together with AmendmentProposed event.
+    pub async fn insert(&self, new_amendment: AgreementAmendment) -> DbResult<()> {
+        do_with_transaction(self.pool, move |conn| {
+            diesel::insert_into(market_agreement_amendment)
+                .values(&new_amendment)
+                .execute(conn)?;
+            create_amendment_event(conn, &new_amendment, AgreementEventType::AmendmentProposed)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Removes amendment, that couldn't be delivered to other side.
+    pub async fn remove(&self, agreement_id: &AgreementId, amendment_id: &str) -> DbResult<()> {
+        let agreement_id = agreement_id.clone();
+        let amendment_id = amendment_id.to_string();
+        do_with_transaction(self.pool, move |conn| {
+            if let Some(amendment) = find_amendment(conn, &agreement_id, &amendment_id)? {
+                remove_amendment_event(conn, &amendment, AgreementEventType::AmendmentProposed)?;
+            }
+            diesel::delete(
+                market_agreement_amendment
+                    .filter(amendment::agreement_id.eq(&agreement_id))
+                    .filter(amendment::id.eq(&amendment_id)),
+            )
+            .execute(conn)?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn select(
+        &self,
+        agreement_id: &AgreementId,
+        amendment_id: &str,
+    ) -> DbResult<Option<AgreementAmendment>> {
+        let agreement_id = agreement_id.clone();
+        let amendment_id = amendment_id.to_string();
+        readonly_transaction(self.pool, move |conn| {
+            find_amendment(conn, &agreement_id, &amendment_id).map_err(DbError::from)
+        })
+        .await
+    }
+
+    pub async fn select_pending(
+        &self,
+        agreement_id: &AgreementId,
+    ) -> DbResult<Option<AgreementAmendment>> {
+        let agreement_id = agreement_id.clone();
+        readonly_transaction(self.pool, move |conn| {
+            Ok(market_agreement_amendment
+                .filter(amendment::agreement_id.eq(&agreement_id))
+                .filter(amendment::state.eq(AmendmentState::Pending))
+                .first(conn)
+                .optional()?)
+        })
+        .await
+    }
+
+    /// Lists all amendments of Agreement starting from the oldest.
+    pub async fn list(&self, agreement_id: &AgreementId) -> DbResult<Vec<AgreementAmendment>> {
+        let agreement_id = agreement_id.clone();
+        readonly_transaction(self.pool, move |conn| {
+            Ok(market_agreement_amendment
+                .filter(amendment::agreement_id.eq(&agreement_id))
+                .order_by(amendment::creation_ts.asc())
+                .load(conn)?)
+        })
+        .await
+    }
+
+    /// Marks amendment as approved and updates Agreement terms in single transaction.
+    /// AmendmentApproved event is added in the same transaction.
+    pub async fn approve(
+        &self,
+        agreement_id: &AgreementId,
+        amendment_id: &str,
+    ) -> Result<Agreement, AmendmentDaoError> {
+        let agreement_id = agreement_id.clone();
+        let amendment_id = amendment_id.to_string();
+        do_with_transaction(self.pool, move |conn| {
+            let amendment = find_pending(conn, &agreement_id, &amendment_id)?;
+            let mut agreement: Agreement = market_agreement
+                .filter(agreement::id.eq(&agreement_id))
+                .first(conn)?;
+
+            if agreement.state != AgreementState::Approved {
+                return Err(AmendmentDaoError::InvalidAgreementState(
+                    agreement.id,
+                    agreement.state,
+                ));
+            }
+
+            amendment
+                .apply_to(&mut agreement)
+                .map_err(|e| AmendmentDaoError::Properties(amendment.id.clone(), e.to_string()))?;
+
+            diesel::update(market_agreement.find(&agreement.id))
+                .set((
+                    agreement::valid_to.eq(&agreement.valid_to),
+                    agreement::demand_properties.eq(&agreement.demand_properties),
+                    agreement::offer_properties.eq(&agreement.offer_properties),
+                ))
+                .execute(conn)?;
+            update_state(conn, &amendment, AmendmentState::Approved, None)?;
+            create_amendment_event(conn, &amendment, AgreementEventType::AmendmentApproved)?;
+            Ok(agreement)
+        })
+        .await
+    }
+
+    /// Restores Agreement terms from before approval of amendment.
+    /// Amendment becomes pending again.
+    pub async fn revert_approval(
+        &self,
+        previous: &Agreement,
+        amendment_id: &str,
+    ) -> Result<(), AmendmentDaoError> {
+        let previous = previous.clone();
+        let amendment_id = amendment_id.to_string();
+        do_with_transaction(self.pool, move |conn| {
+            let amendment = find_amendment(conn, &previous.id, &amendment_id)?
+                .ok_or_else(|| AmendmentDaoError::NotFound(amendment_id.clone()))?;
+
+            diesel::update(market_agreement.find(&previous.id))
+                .set((
+                    agreement::valid_to.eq(&previous.valid_to),
+                    agreement::demand_properties.eq(&previous.demand_properties),
+                    agreement::offer_properties.eq(&previous.offer_properties),
+                ))
+                .execute(conn)?;
+            update_state(conn, &amendment, AmendmentState::Pending, None)?;
+            remove_amendment_event(conn, &amendment, AgreementEventType::AmendmentApproved)?;
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn reject(
+        &self,
+        agreement_id: &AgreementId,
+        amendment_id: &str,
+        reason: Option<Reason>,
+    ) -> Result<AgreementAmendment, AmendmentDaoError> {
+        let agreement_id = agreement_id.clone();
+        let amendment_id = amendment_id.to_string();
+        do_with_transaction(self.pool, move |conn| {
+            let mut amendment = find_pending(conn, &agreement_id, &amendment_id)?;
+            let reason = reason.map(DbReason);
+            update_state(conn, &amendment, AmendmentState::Rejected, reason.clone())?;
+
+            amendment.state = AmendmentState::Rejected;
+            amendment.reason = reason;
+            create_amendment_event(conn, &amendment, AgreementEventType::AmendmentRejected)?;
+            Ok(amendment)
+        })
+        .await
+    }
+}
+
+fn find_amendment(
+    conn: &ConnType,
+    agreement_id: &AgreementId,
+    amendment_id: &str,
+) -> Result<Option<AgreementAmendment>, diesel::result::Error> {
+    market_agreement_amendment
+        .filter(amendment::agreement_id.eq(agreement_id))
+        .filter(amendment::id.eq(amendment_id))
+        .first(conn)
+        .optional()
+}
+
+fn find_pending(
+    conn: &ConnType,
+    agreement_id: &AgreementId,
+    amendment_id: &str,
+) -> Result<AgreementAmendment, AmendmentDaoError> {
+    let amendment = find_amendment(conn, agreement_id, amendment_id)?
+        .ok_or_else(|| AmendmentDaoError::NotFound(amendment_id.to_string()))?;
+
+    if amendment.state != AmendmentState::Pending {
+        return Err(AmendmentDaoError::NotPending(amendment.id, amendment.state));
+    }
+    Ok(amendment)
+}
+
+fn update_state(
+    conn: &ConnType,
+    amendment: &AgreementAmendment,
+    state: AmendmentState,
+    reason: Option<DbReason>,
+) -> Result<(), AmendmentDaoError> {
+    diesel::update(
+        market_agreement_amendment
+            .filter(amendment::agreement_id.eq(&amendment.agreement_id))
+            .filter(amendment::id.eq(&amendment.id)),
+    )
+    .set((amendment::state.eq(state), amendment::reason.eq(reason)))
+    .execute(conn)?;
+    Ok(())
+}
+
+impl<ErrorType: Into<DbError>> From<ErrorType> for AmendmentDaoError {
+    fn from(err: ErrorType) -> Self {
+        AmendmentDaoError::DbError(err.into())
+    }
+}
@@ -8,7 +8,10 @@ use ya_persistence::executor::{readonly_transaction, ConnType};
 use ya_persistence::types::AdaptTimestamp;
 
 use crate::db::dao::AgreementDaoError;
-use crate::db::model::{Agreement, AgreementEvent, AgreementId, NewAgreementEvent};
+use crate::db::model::{
+    Agreement, AgreementAmendment, AgreementEvent, AgreementEventType, AgreementId,
+    NewAgreementEvent,
+};
 use crate::db::model::{AppSessionId, Owner};
 use crate::db::schema::market_agreement::dsl as agreement;
 use crate::db::schema::market_agreement::dsl::market_agreement;
@@ -27,12 +30,37 @@ impl<'a> AsMixedDao<'a> for AgreementEventsDao<'a> {
 }
 
 impl<'c> AgreementEventsDao<'c> {
+    /// Selects events changing Agreement state.
     pub async fn select(
         &self,
         node_id: &NodeId,
         session_id: &AppSessionId,
         max_events: i32,
         after_timestamp: NaiveDateTime,
+    ) -> DbResult<Vec<AgreementEvent>> {
+        self.select_events(node_id, session_id, max_events, after_timestamp, false)
+            .await
+    }
+
+    /// Selects events of amendments proposed and resolved after Agreement approval.
+    pub async fn select_amendment_events(
+        &self,
+        node_id: &NodeId,
+        session_id: &AppSessionId,
+        max_events: i32,
+        after_timestamp: NaiveDateTime,
+    ) -> DbResult<Vec<AgreementEvent>> {
+        self.select_events(node_id, session_id, max_events, after_timestamp, true)
+            .await
+    }
+
+    async fn select_events(
+        &self,
+        node_id: &NodeId,
+        session_id: &AppSessionId,
+        max_events: i32,
+        after_timestamp: NaiveDateTime,
+        amendments: bool,
     ) -> DbResult<Vec<AgreementEvent>> {
         let session_id = session_id.clone();
         let node_id = *node_id;
@@ -55,9 +83,16 @@ impl<'c> AgreementEventsDao<'c> {
                     select_corresponding_agreement.filter(agreement::session_id.eq(session_id));
             };
 
-            Ok(market_agreement_event
+            let mut query = market_agreement_event
                 .filter(event::agreement_id.eq_any(select_corresponding_agreement))
                 .filter(event::timestamp.gt(after_timestamp.adapt()))
+                .into_boxed();
+            query = match amendments {
+                true => query.filter(event::amendment_id.is_not_null()),
+                false => query.filter(event::amendment_id.is_null()),
+            };
+
+            Ok(query
                 .order_by(event::timestamp.asc())
                 .limit(max_events as i64)
                 .load::<AgreementEvent>(conn)?)
@@ -65,6 +100,7 @@ impl<'c> AgreementEventsDao<'c> {
         .await
     }
 
+    /// Selects events changing Agreement state.
     pub async fn select_for_agreement(
         &self,
         agreement_id: &AgreementId,
@@ -73,6 +109,7 @@ impl<'c> AgreementEventsDao<'c> {
         readonly_transaction(self.pool, move |conn| {
             Ok(market_agreement_event
                 .filter(event::agreement_id.eq(agreement_id))
+                .filter(event::amendment_id.is_null())
                 .order_by(event::timestamp.asc())
                 .load::<AgreementEvent>(conn)?)
         })
@@ -109,3 +146,29 @@ pub(crate) fn create_event(
 
     Ok(())
 }
+
+pub(crate) fn create_amendment_event(
+    conn: &ConnType,
+    amendment: &AgreementAmendment,
+    event_type: AgreementEventType,
+) -> DbResult<()> {
+    diesel::insert_into(market_agreement_event)
+        .values(&NewAgreementEvent::amendment(amendment, event_type))
+        .execute(conn)?;
+    Ok(())
+}
+
+pub(crate) fn remove_amendment_event(
+    conn: &ConnType,
+    amendment: &AgreementAmendment,
+    event_type: AgreementEventType,
+) -> DbResult<()> {
+    diesel::delete(
+        market_agreement_event
+            .filter(event::agreement_id.eq(&amendment.agreement_id))
+            .filter(event::amendment_id.eq(&amendment.id))
+            .filter(event::event_type.eq(event_type)),
+    )
+    .execute(conn)?;
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use std::collections::HashSet;
+
+use ya_client::model::NodeId;
+use ya_persistence::executor::{do_with_transaction, readonly_transaction, PoolType};
+
+use crate::db::model::BlockedNode;
+use crate::db::schema::market_blocked_node::dsl as blocked;
+use crate::db::schema::market_blocked_node::dsl::market_blocked_node;
+use crate::db::{AsMixedDao, DbResult};
+
+/// Blocklist is kept on disk, so it survives restarts.
+pub struct BlockedNodeDao<'c> {
+    pool: &'c PoolType,
+}
+
+impl<'a> AsMixedDao<'a> for BlockedNodeDao<'a> {
+    fn as_dao(disk_pool: &'a PoolType, _ram_pool: &'a PoolType) -> Self {
+        Self { pool: disk_pool }
+    }
+}
+
+impl<'c> BlockedNodeDao<'c> {
+    /// Adds node to blocklist or updates reason, if it was already blocked.
+    pub async fn block(&self, node: BlockedNode) -> DbResult<BlockedNode> {
+        do_with_transaction(self.pool, move |conn| {
+            diesel::replace_into(market_blocked_node)
+                .values(&node)
+                .execute(conn)?;
+            Ok(node)
+        })
+        .await
+    }
+
+    /// Returns false, if node wasn't blocked.
+    pub async fn unblock(&self, node_id: NodeId) -> DbResult<bool> {
+        do_with_transaction(self.pool, move |conn| {
+            let num_deleted =
+                diesel::delete(market_blocked_node.filter(blocked::node_id.eq(node_id)))
+                    .execute(conn)?;
+            Ok(num_deleted > 0)
+        })
+        .await
+    }
+
+    pub async fn list(&self) -> DbResult<Vec<BlockedNode>> {
+        readonly_transaction(self.pool, move |conn| {
+            Ok(market_blocked_node
+                .order_by(blocked::blocked_ts.asc())
+                .load::<BlockedNode>(conn)?)
+        })
+        .await
+    }
+
+    pub async fn get_ids(&self) -> DbResult<HashSet<NodeId>> {
+        readonly_transaction(self.pool, move |conn| {
+            Ok(market_blocked_node
+                .select(blocked::node_id)
+                .load::<NodeId>(conn)?
+                .into_iter()
+                .collect())
+        })
+        .await
+    }
+
+    pub async fn is_blocked(&self, node_id: NodeId) -> DbResult<bool> {
+        readonly_transaction(self.pool, move |conn| {
+            let count: i64 = market_blocked_node
+                .filter(blocked::node_id.eq(node_id))
+                .count()
+                .get_result(conn)?;
+            Ok(count > 0)
+        })
+        .await
+    }
+}
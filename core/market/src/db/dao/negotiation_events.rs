@@ -5,7 +5,7 @@ use thiserror::Error;
 
 use ya_client::model::market::Reason;
 use ya_persistence::executor::ConnType;
-use ya_persistence::executor::{do_with_transaction, PoolType};
+use ya_persistence::executor::{do_with_transaction, readonly_transaction, PoolType};
 
 use crate::config::DbConfig;
 use crate::db::dao::demand::{demand_status, DemandState};
@@ -78,15 +78,16 @@ impl<'c> NegotiationEventsDao<'c> {
         subscription_id: &SubscriptionId,
         max_events: i32,
         owner: Owner,
+        order_by_score: bool,
     ) -> Result<Vec<MarketEvent>, TakeEventsError> {
         let subscription_id = subscription_id.clone();
         do_with_transaction(self.pool, move |conn| {
             // Check subscription wasn't unsubscribed or expired.
             validate_subscription(conn, &subscription_id, owner)?;
 
-            // Only ProposalEvents should be in random order.
-            //  AgreementEvent and rejections events should be sorted with higher
-            //  priority.
+            // Only ProposalEvents should be in random order (or ordered by Proposal score
+            //  if requested). AgreementEvent and rejections events should be sorted
+            //  with higher priority.
             let basic_query =
                 dsl::market_negotiation_event.filter(dsl::subscription_id.eq(&subscription_id));
             let mut events = basic_query
@@ -99,12 +100,22 @@ impl<'c> NegotiationEventsDao<'c> {
                 .load::<MarketEvent>(conn)?;
             if (events.len() as i32) < max_events {
                 let limit_left: i32 = max_events - (events.len() as i32);
+                let order = if order_by_score {
+                    // Proposals without score (NULL) go last.
+                    concat!(
+                        "(SELECT score FROM market_proposal",
+                        " WHERE market_proposal.id = market_negotiation_event.artifact_id) DESC,",
+                        " market_negotiation_event.timestamp ASC"
+                    )
+                } else {
+                    "RANDOM()"
+                };
                 let proposal_events = basic_query
                     .filter(dsl::event_type.eq_any(vec![
                         EventType::ProviderNewProposal,
                         EventType::RequestorNewProposal,
                     ]))
-                    .order_by(sql::<sql_types::Bool>("RANDOM()"))
+                    .order_by(sql::<sql_types::Bool>(order))
                     .limit(limit_left as i64)
                     .load::<MarketEvent>(conn)?;
 
@@ -123,6 +134,19 @@ impl<'c> NegotiationEventsDao<'c> {
         .await
     }
 
+    /// Checks, if events can be taken for subscription.
+    pub async fn check_subscription(
+        &self,
+        subscription_id: &SubscriptionId,
+        owner: Owner,
+    ) -> Result<(), TakeEventsError> {
+        let subscription_id = subscription_id.clone();
+        readonly_transaction(self.pool, move |conn| {
+            validate_subscription(conn, &subscription_id, owner)
+        })
+        .await
+    }
+
     pub async fn remove_events(&self, subscription_id: &SubscriptionId) -> DbResult<()> {
         let subscription_id = subscription_id.clone();
         do_with_transaction(self.pool, move |conn| {
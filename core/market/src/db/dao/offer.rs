@@ -1,12 +1,12 @@
 use chrono::NaiveDateTime;
 use diesel::expression::dsl::now as sql_now;
-use diesel::{ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
+use diesel::{BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl};
 
 use ya_client::model::NodeId;
 use ya_persistence::executor::{do_with_transaction, readonly_transaction, ConnType, PoolType};
 
 use crate::db::model::SubscriptionId;
-use crate::db::model::{Offer, OfferUnsubscribed};
+use crate::db::model::{Offer, OfferCursor, OfferSortKey, OfferUnsubscribed};
 use crate::db::schema::market_offer::dsl as offer;
 use crate::db::schema::market_offer::dsl::market_offer;
 use crate::db::schema::market_offer_unsubscribed::dsl as unsubscribed;
@@ -92,6 +92,56 @@ impl<'c> OfferDao<'c> {
         .await
     }
 
+    /// Returns at most `limit` active Offers ordered by `sort_by` key and by id for equal keys.
+    /// If `cursor` is given, only Offers placed after it in this order are returned.
+    pub async fn get_offers_sorted(
+        &self,
+        sort_by: OfferSortKey,
+        cursor: Option<OfferCursor>,
+        limit: i64,
+        expiry_validation_ts: NaiveDateTime,
+    ) -> DbResult<Vec<Offer>> {
+        readonly_transaction(self.pool, move |conn| {
+            let mut query = market_offer
+                .filter(offer::expiration_ts.ge(expiry_validation_ts))
+                .filter(
+                    offer::id.ne_all(
+                        market_offer_unsubscribed
+                            .select(unsubscribed::id)
+                            .filter(unsubscribed::expiration_ts.ge(expiry_validation_ts)),
+                    ),
+                )
+                .into_boxed();
+
+            query = match sort_by {
+                OfferSortKey::CreationTs => {
+                    query.order_by((offer::creation_ts.asc(), offer::id.asc()))
+                }
+                OfferSortKey::ExpirationTs => {
+                    query.order_by((offer::expiration_ts.asc(), offer::id.asc()))
+                }
+            };
+
+            if let Some(OfferCursor { ts, id }) = cursor {
+                query = match sort_by {
+                    OfferSortKey::CreationTs => query.filter(
+                        offer::creation_ts
+                            .gt(ts)
+                            .or(offer::creation_ts.eq(ts).and(offer::id.gt(id))),
+                    ),
+                    OfferSortKey::ExpirationTs => query.filter(
+                        offer::expiration_ts
+                            .gt(ts)
+                            .or(offer::expiration_ts.eq(ts).and(offer::id.gt(id))),
+                    ),
+                };
+            }
+
+            Ok(query.limit(limit).load(conn)?)
+        })
+        .await
+    }
+
     /// Returns Offer ids for given `node_ids` or all.
     pub async fn get_offer_ids(
         &self,
@@ -169,7 +219,7 @@ impl<'c> OfferDao<'c> {
     /// or `(true, Active(offer))` after successful insert.
     pub async fn put(
         &self,
-        mut offer: Offer,
+        offer: Offer,
         expiry_validation_ts: NaiveDateTime,
     ) -> DbResult<(bool, OfferState)> {
         if offer.expiration_ts < expiry_validation_ts {
@@ -177,28 +227,30 @@ impl<'c> OfferDao<'c> {
         }
 
         do_with_transaction(self.pool, move |conn| {
-            let id = offer.id.clone();
-
-            if is_unsubscribed(conn, &id)? {
-                return Ok((false, OfferState::Unsubscribed(Some(offer))));
-            }
-
-            if let Some(offer) = query_offer(conn, &id)? {
-                return Ok((false, active_or_expired(offer, &expiry_validation_ts)));
-            };
+            put_offer(conn, offer, &expiry_validation_ts)
+        })
+        .await
+    }
 
-            // We need more precise timestamps, than auto-generated by db.
-            // We must set them under transaction to avoid giving so timestamps
-            // will be assigned in order of insertions to database.
-            offer.insertion_ts = Some(chrono::Utc::now().naive_utc());
+    /// Works like `put`, but doesn't insert Offer, if its node has already
+    /// `quota` active Offers. Returns `None` in this case.
+    /// Offers are counted in the same transaction as insertion is made,
+    /// so concurrent insertions can't exceed quota.
+    pub async fn put_within_quota(
+        &self,
+        offer: Offer,
+        expiry_validation_ts: NaiveDateTime,
+        quota: u32,
+    ) -> DbResult<Option<(bool, OfferState)>> {
+        if offer.expiration_ts < expiry_validation_ts {
+            return Ok(Some((false, OfferState::Expired(Some(offer)))));
+        }
 
-            diesel::insert_into(market_offer)
-                .values(offer)
-                .execute(conn)?;
-            // SQLite do does not support returning from insert,
-            // so we need to query again to get insertion_ts
-            let offer = query_offer(conn, &id)?.unwrap();
-            Ok((true, OfferState::Active(offer)))
+        do_with_transaction(self.pool, move |conn| {
+            if count_active(conn, offer.node_id, expiry_validation_ts)? >= quota as i64 {
+                return Ok(None);
+            }
+            put_offer(conn, offer, &expiry_validation_ts).map(Some)
         })
         .await
     }
@@ -289,6 +341,55 @@ pub(super) fn query_state(
     })
 }
 
+fn put_offer(
+    conn: &ConnType,
+    mut offer: Offer,
+    expiry_validation_ts: &NaiveDateTime,
+) -> DbResult<(bool, OfferState)> {
+    let id = offer.id.clone();
+
+    if is_unsubscribed(conn, &id)? {
+        return Ok((false, OfferState::Unsubscribed(Some(offer))));
+    }
+
+    if let Some(offer) = query_offer(conn, &id)? {
+        return Ok((false, active_or_expired(offer, expiry_validation_ts)));
+    };
+
+    // We need more precise timestamps, than auto-generated by db.
+    // We must set them under transaction to avoid giving so timestamps
+    // will be assigned in order of insertions to database.
+    offer.insertion_ts = Some(chrono::Utc::now().naive_utc());
+
+    diesel::insert_into(market_offer)
+        .values(offer)
+        .execute(conn)?;
+    // SQLite do does not support returning from insert,
+    // so we need to query again to get insertion_ts
+    let offer = query_offer(conn, &id)?.unwrap();
+    Ok((true, OfferState::Active(offer)))
+}
+
+/// Returns number of active Offers subscribed by `node_id`.
+fn count_active(
+    conn: &ConnType,
+    node_id: NodeId,
+    expiry_validation_ts: NaiveDateTime,
+) -> DbResult<i64> {
+    Ok(market_offer
+        .filter(offer::node_id.eq(node_id))
+        .filter(offer::expiration_ts.ge(expiry_validation_ts))
+        .filter(
+            offer::id.ne_all(
+                market_offer_unsubscribed
+                    .select(unsubscribed::id)
+                    .filter(unsubscribed::expiration_ts.ge(expiry_validation_ts)),
+            ),
+        )
+        .count()
+        .get_result(conn)?)
+}
+
 fn active_or_expired(offer: Offer, expiry_validation_ts: &NaiveDateTime) -> OfferState {
     match &offer.expiration_ts > expiry_validation_ts {
         true => OfferState::Active(offer),
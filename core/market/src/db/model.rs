@@ -1,5 +1,7 @@
 mod agreement;
+mod agreement_amendment;
 mod agreement_events;
+mod blocked_node;
 mod demand;
 mod negotiation_events;
 mod offer;
@@ -8,10 +10,16 @@ mod proposal_id;
 mod subscription_id;
 
 pub use agreement::{check_transition, Agreement, AgreementId, AgreementState, AppSessionId};
-pub use agreement_events::{AgreementEvent, AgreementEventType, NewAgreementEvent};
+pub use agreement_amendment::{AgreementAmendment, AmendmentState, AmendmentValidationError};
+pub use agreement_events::{
+    AgreementEvent, AgreementEventType, DbReason, EventTypeError, NewAgreementEvent,
+};
+pub use blocked_node::BlockedNode;
 pub use demand::Demand;
-pub use negotiation_events::{EventError, EventType, MarketEvent};
-pub use offer::{Offer, OfferUnsubscribed};
+pub use negotiation_events::{EventError, EventType, MarketEvent, ScoredRequestorEvent};
+pub use offer::{
+    Offer, OfferCursor, OfferCursorParseError, OfferSortKey, OfferUnsubscribed, OffersPage,
+};
 pub use proposal::{DbProposal, Issuer, Negotiation, Proposal, ProposalState};
 
 pub use proposal_id::{Owner, ProposalId, ProposalIdParseError, ProposalIdValidationError};
@@ -0,0 +1,136 @@
+use chrono::{NaiveDateTime, Utc};
+use diesel::sql_types::Text;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use ya_client::model::market::Reason;
+use ya_diesel_utils::DbTextField;
+
+use crate::db::model::{generate_random_id, Agreement, AgreementId, DbReason};
+use crate::db::schema::market_agreement_amendment;
+
+/// Properties under this prefix define payment terms, which
+/// can't be changed after Agreement was approved.
+const PROTECTED_PROPERTIES_PREFIX: &str = "golem.com.";
+/// Exception from protected properties. Price can be amended, since
+/// Provider has to approve amendment, before it is applied.
+const AMENDABLE_PROPERTIES_PREFIX: &str = "golem.com.pricing.";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AmendmentValidationError {
+    #[error("validTo [{0}] should be in the future.")]
+    ValidTo(NaiveDateTime),
+    #[error("Property [{0}] can't be changed by amendment.")]
+    ProtectedProperty(String),
+    #[error("Properties should be JSON object. {0}")]
+    Properties(String),
+}
+
+#[derive(
+    strum_macros::EnumString,
+    DbTextField,
+    derive_more::Display,
+    AsExpression,
+    FromSqlRow,
+    PartialEq,
+    Eq,
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+)]
+#[sql_type = "Text"]
+pub enum AmendmentState {
+    /// Proposed by Requestor and waiting for Provider decision.
+    Pending,
+    /// Approved by Provider and applied to Agreement on both sides.
+    Approved,
+    /// Rejected by Provider. Agreement terms stay unchanged.
+    Rejected,
+}
+
+/// Change of Agreement terms proposed after Agreement was approved.
+/// Properties contain only values, that should be added or replaced
+/// in Agreement and are stored in flattened form, the same as Agreement properties.
+#[derive(Clone, Debug, Insertable, Queryable)]
+#[table_name = "market_agreement_amendment"]
+pub struct AgreementAmendment {
+    pub id: String,
+    pub agreement_id: AgreementId,
+    pub valid_to: NaiveDateTime,
+    pub demand_properties: Option<String>,
+    pub offer_properties: Option<String>,
+    pub state: AmendmentState,
+    pub creation_ts: NaiveDateTime,
+    pub reason: Option<DbReason>,
+}
+
+impl AgreementAmendment {
+    pub fn new(
+        agreement_id: AgreementId,
+        valid_to: NaiveDateTime,
+        demand_properties: Option<Value>,
+        offer_properties: Option<Value>,
+    ) -> Result<AgreementAmendment, serde_json::Error> {
+        Ok(AgreementAmendment {
+            id: generate_random_id(),
+            agreement_id,
+            valid_to,
+            demand_properties: demand_properties.map(flatten).transpose()?,
+            offer_properties: offer_properties.map(flatten).transpose()?,
+            state: AmendmentState::Pending,
+            creation_ts: Utc::now().naive_utc(),
+            reason: None,
+        })
+    }
+
+    /// Checks terms proposed by Requestor, before they are stored.
+    pub fn validate(&self, now: NaiveDateTime) -> Result<(), AmendmentValidationError> {
+        if self.valid_to <= now {
+            return Err(AmendmentValidationError::ValidTo(self.valid_to));
+        }
+
+        let properties = self
+            .demand_properties
+            .iter()
+            .chain(self.offer_properties.iter());
+        for properties in properties {
+            let properties = serde_json::from_str::<Map<String, Value>>(properties)
+                .map_err(|e| AmendmentValidationError::Properties(e.to_string()))?;
+            if let Some(name) = properties.keys().find(|name| {
+                name.starts_with(PROTECTED_PROPERTIES_PREFIX)
+                    && !name.starts_with(AMENDABLE_PROPERTIES_PREFIX)
+            }) {
+                return Err(AmendmentValidationError::ProtectedProperty(name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates Agreement terms with values from amendment.
+    pub fn apply_to(&self, agreement: &mut Agreement) -> Result<(), serde_json::Error> {
+        agreement.valid_to = self.valid_to;
+        if let Some(properties) = &self.demand_properties {
+            agreement.demand_properties = merge(&agreement.demand_properties, properties)?;
+        }
+        if let Some(properties) = &self.offer_properties {
+            agreement.offer_properties = merge(&agreement.offer_properties, properties)?;
+        }
+        Ok(())
+    }
+
+    pub fn reason(&self) -> Option<Reason> {
+        self.reason.clone().map(|reason| reason.0)
+    }
+}
+
+fn flatten(properties: Value) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&ya_agreement_utils::agreement::flatten(properties))
+}
+
+fn merge(properties: &str, update: &str) -> Result<String, serde_json::Error> {
+    let mut properties = serde_json::from_str::<Map<String, Value>>(properties)?;
+    properties.extend(serde_json::from_str::<Map<String, Value>>(update)?);
+    serde_json::to_string(&properties)
+}
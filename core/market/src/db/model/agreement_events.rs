@@ -3,7 +3,7 @@ use diesel::sql_types::Text;
 use std::fmt;
 use std::fmt::Debug;
 
-use crate::db::model::{Agreement, AgreementId, AgreementState, Owner};
+use crate::db::model::{Agreement, AgreementAmendment, AgreementId, AgreementState, Owner};
 use crate::db::schema::market_agreement_event;
 
 use std::str::FromStr;
@@ -32,6 +32,11 @@ pub enum AgreementEventType {
     Rejected,
     Cancelled,
     Terminated,
+    /// Amendment events don't change Agreement state and are
+    /// returned separately from events above.
+    AmendmentProposed,
+    AmendmentApproved,
+    AmendmentRejected,
 }
 
 #[derive(DbTextField, Debug, Clone, AsExpression, FromSqlRow)]
@@ -47,6 +52,7 @@ pub struct AgreementEvent {
     pub issuer: Owner,
     pub reason: Option<DbReason>,
     pub signature: Option<String>,
+    pub amendment_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Insertable)]
@@ -57,12 +63,17 @@ pub struct NewAgreementEvent {
     pub timestamp: TimestampAdapter,
     pub issuer: Owner,
     pub reason: Option<DbReason>,
+    pub amendment_id: Option<String>,
 }
 
 #[derive(thiserror::Error, Debug, Clone)]
 #[error("Error creating Event from the Agreement: {0}")]
 pub struct EventFromAgreementError(pub String);
 
+#[derive(thiserror::Error, Debug, Clone)]
+#[error("Agreement event [{0}] has unexpected type {1}.")]
+pub struct EventTypeError(pub i32, pub AgreementEventType);
+
 impl NewAgreementEvent {
     pub(crate) fn new(
         agreement: &Agreement,
@@ -95,17 +106,39 @@ impl NewAgreementEvent {
             timestamp: Utc::now().adapt(),
             issuer: terminator,
             reason: reason.map(DbReason),
+            amendment_id: None,
         })
     }
+
+    /// Amendments are always proposed by Requestor and resolved by Provider.
+    pub(crate) fn amendment(
+        amendment: &AgreementAmendment,
+        event_type: AgreementEventType,
+    ) -> Self {
+        let issuer = match event_type {
+            AgreementEventType::AmendmentProposed => Owner::Requestor,
+            _ => Owner::Provider,
+        };
+        Self {
+            agreement_id: amendment.agreement_id.clone(),
+            event_type,
+            timestamp: Utc::now().adapt(),
+            issuer,
+            reason: amendment.reason.clone(),
+            amendment_id: Some(amendment.id.clone()),
+        }
+    }
 }
 
 impl AgreementEvent {
-    pub fn into_client(self) -> ClientEvent {
+    /// Converts Agreement state change event. Amendment events aren't part
+    /// of client model and are converted by `rest_api::AmendmentEvent`.
+    pub fn into_client(self) -> Result<ClientEvent, EventTypeError> {
         let agreement_id = self.agreement_id.into_client();
         let event_date = DateTime::<Utc>::from_utc(self.timestamp, Utc);
         let reason = self.reason.map(|reason| reason.0);
 
-        match self.event_type {
+        Ok(match self.event_type {
             AgreementEventType::Approved => ClientEvent {
                 agreement_id,
                 event_date,
@@ -137,7 +170,12 @@ impl AgreementEvent {
                     }),
                 }
             },
-        }
+            AgreementEventType::AmendmentProposed
+            | AgreementEventType::AmendmentApproved
+            | AgreementEventType::AmendmentRejected => {
+                return Err(EventTypeError(self.id, self.event_type))
+            }
+        })
     }
 }
 
@@ -0,0 +1,33 @@
+use chrono::{NaiveDateTime, TimeZone, Utc};
+
+use ya_client::model::NodeId;
+use ya_core_model::market::BlockedNode as ClientBlockedNode;
+
+use crate::db::schema::market_blocked_node;
+
+/// Node, which Offers shouldn't be matched with local Demands.
+#[derive(Clone, Debug, Insertable, Queryable)]
+#[table_name = "market_blocked_node"]
+pub struct BlockedNode {
+    pub node_id: NodeId,
+    pub reason: Option<String>,
+    pub blocked_ts: NaiveDateTime,
+}
+
+impl BlockedNode {
+    pub fn new(node_id: NodeId, reason: Option<String>) -> BlockedNode {
+        BlockedNode {
+            node_id,
+            reason,
+            blocked_ts: Utc::now().naive_utc(),
+        }
+    }
+
+    pub fn into_client(self) -> ClientBlockedNode {
+        ClientBlockedNode {
+            node_id: self.node_id,
+            reason: self.reason,
+            blocked_date: Utc.from_utc_datetime(&self.blocked_ts),
+        }
+    }
+}
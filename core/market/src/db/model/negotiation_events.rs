@@ -1,5 +1,6 @@
 use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::sql_types::Text;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use ya_client::model::market::event::{ProviderEvent, RequestorEvent};
@@ -26,6 +27,16 @@ pub enum EventError {
     InternalError(#[from] ErrorMessage),
 }
 
+/// RequestorEvent together with score of Proposal, that it refers to.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoredRequestorEvent {
+    #[serde(flatten)]
+    pub event: RequestorEvent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+}
+
 #[derive(
     DbTextField,
     strum_macros::EnumString,
@@ -115,19 +126,35 @@ impl MarketEvent {
         self,
         db: &DbMixedExecutor,
     ) -> Result<RequestorEvent, EventError> {
+        Ok(self.into_scored_requestor_event(db).await?.event)
+    }
+
+    pub async fn into_scored_requestor_event(
+        self,
+        db: &DbMixedExecutor,
+    ) -> Result<ScoredRequestorEvent, EventError> {
         let event_date = DateTime::<Utc>::from_utc(self.timestamp, Utc);
         match self.event_type {
-            EventType::RequestorNewProposal => Ok(RequestorEvent::ProposalEvent {
-                event_date,
-                proposal: self.into_client_proposal(db.clone()).await?,
-            }),
-            EventType::RequestorProposalRejected => Ok(RequestorEvent::ProposalRejectedEvent {
-                event_date,
-                proposal_id: self.artifact_id.to_string(),
-                reason: match self.reason {
-                    None => None,
-                    Some(reason) => Some(reason.0),
+            EventType::RequestorNewProposal => {
+                let proposal = self.get_proposal(db.clone()).await?;
+                Ok(ScoredRequestorEvent {
+                    score: proposal.body.score,
+                    event: RequestorEvent::ProposalEvent {
+                        event_date,
+                        proposal: proposal.into_client()?,
+                    },
+                })
+            }
+            EventType::RequestorProposalRejected => Ok(ScoredRequestorEvent {
+                event: RequestorEvent::ProposalRejectedEvent {
+                    event_date,
+                    proposal_id: self.artifact_id.to_string(),
+                    reason: match self.reason {
+                        None => None,
+                        Some(reason) => Some(reason.0),
+                    },
                 },
+                score: None,
             }),
             EventType::RequestorPropertyQuery => unimplemented!(),
             e => Err(ErrorMessage::new(format!(
@@ -138,14 +165,15 @@ impl MarketEvent {
     }
 
     async fn into_client_proposal(self, db: DbMixedExecutor) -> Result<ClientProposal, EventError> {
-        let prop = db
-            .as_dao::<ProposalDao>()
+        Ok(self.get_proposal(db).await?.into_client()?)
+    }
+
+    async fn get_proposal(&self, db: DbMixedExecutor) -> Result<Proposal, EventError> {
+        db.as_dao::<ProposalDao>()
             .get_proposal(&self.artifact_id)
             .await
             .map_err(|e| EventError::GetError(self.artifact_id.clone(), e.to_string()))?
-            .ok_or_else(|| EventError::ProposalNotFound(self.artifact_id.clone()))?;
-
-        Ok(prop.into_client()?)
+            .ok_or_else(|| EventError::ProposalNotFound(self.artifact_id.clone()))
     }
 
     async fn into_client_agreement(
@@ -1,5 +1,7 @@
 use chrono::{NaiveDateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 use ya_client::model::{market::Offer as ClientOffer, ErrorMessage, NodeId};
 use ya_service_api_web::middleware::Identity;
@@ -115,6 +117,86 @@ impl Offer {
     }
 }
 
+/// Offer field, by which Offers query results are sorted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OfferSortKey {
+    CreationTs,
+    ExpirationTs,
+}
+
+impl Default for OfferSortKey {
+    fn default() -> Self {
+        OfferSortKey::CreationTs
+    }
+}
+
+/// Position of the last Offer returned in Offers query page.
+/// Next page starts with first Offer after this position.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OfferCursor {
+    /// Value of sort key field.
+    pub ts: NaiveDateTime,
+    /// Offers with the same sort key value are ordered by id.
+    pub id: SubscriptionId,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Invalid Offers query cursor '{0}'.")]
+pub struct OfferCursorParseError(String);
+
+impl OfferCursor {
+    pub fn new(offer: &Offer, sort_by: OfferSortKey) -> OfferCursor {
+        OfferCursor {
+            ts: match sort_by {
+                OfferSortKey::CreationTs => offer.creation_ts,
+                OfferSortKey::ExpirationTs => offer.expiration_ts,
+            },
+            id: offer.id.clone(),
+        }
+    }
+}
+
+impl fmt::Display for OfferCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.ts.timestamp_nanos(), self.id)
+    }
+}
+
+impl FromStr for OfferCursor {
+    type Err = OfferCursorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || OfferCursorParseError(s.to_string());
+        let mut parts = s.splitn(2, '_');
+        let nanos = parts
+            .next()
+            .and_then(|nanos| nanos.parse::<i64>().ok())
+            .ok_or_else(error)?;
+        let id = parts
+            .next()
+            .and_then(|id| SubscriptionId::from_str(id).ok())
+            .ok_or_else(error)?;
+        let ts = NaiveDateTime::from_timestamp_opt(
+            nanos.div_euclid(1_000_000_000),
+            nanos.rem_euclid(1_000_000_000) as u32,
+        )
+        .ok_or_else(error)?;
+        Ok(OfferCursor { ts, id })
+    }
+}
+
+/// Single page of Offers query results.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OffersPage {
+    pub offers: Vec<ClientOffer>,
+    /// Cursor to pass in next query to get the following page.
+    /// None if there are no more matching Offers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
 /// PartialEq implementation that ignores insertion_ts.
 impl PartialEq for Offer {
     fn eq(&self, other: &Self) -> bool {
@@ -190,5 +272,21 @@ mod tests {
         offer.validate().unwrap();
     }
 
+    #[test]
+    fn test_offer_cursor_roundtrip() {
+        let offer_id = "c76161077d0343ab85ac986eb5f6ea38-85fdde1924371f4a3a412748f61e5b941c500ea69a55a5135b886a2bffcb8e55";
+        let cursor = OfferCursor {
+            ts: NaiveDateTime::new(
+                NaiveDate::from_ymd(2021, 3, 1),
+                NaiveTime::from_hms_nano(12, 30, 15, 123_456_789),
+            ),
+            id: SubscriptionId::from_str(offer_id).unwrap(),
+        };
+
+        assert_eq!(cursor.to_string().parse::<OfferCursor>(), Ok(cursor));
+        assert!("1234".parse::<OfferCursor>().is_err());
+        assert!(format!("abc_{}", offer_id).parse::<OfferCursor>().is_err());
+    }
+
     // TODO: test from_new
 }
@@ -117,6 +117,9 @@ pub struct DbProposal {
     pub state: ProposalState,
     pub creation_ts: NaiveDateTime,
     pub expiration_ts: NaiveDateTime,
+
+    /// Score computed by Requestor for Proposals received from Provider.
+    pub score: Option<f64>,
 }
 
 /// Proposal together with Negotiation object related with it.
@@ -148,6 +151,7 @@ impl Proposal {
             state: ProposalState::Initial,
             creation_ts,
             expiration_ts,
+            score: None,
         };
         Proposal {
             body: proposal,
@@ -183,6 +187,7 @@ impl Proposal {
             state: ProposalState::Initial,
             creation_ts,
             expiration_ts: offer.expiration_ts,
+            score: None,
         };
 
         Proposal {
@@ -203,6 +208,7 @@ impl Proposal {
             state: ProposalState::Draft,
             creation_ts: proposal.creation_ts,
             expiration_ts: proposal.expiration_ts,
+            score: None,
         };
 
         Proposal {
@@ -237,6 +243,7 @@ impl Proposal {
             state: ProposalState::Draft,
             creation_ts,
             expiration_ts: *expiration_ts,
+            score: None,
         };
 
         Ok(Proposal {
@@ -54,6 +54,7 @@ table! {
         issuer -> Text,
         reason -> Nullable<Text>,
         signature -> Nullable<Text>,
+        amendment_id -> Nullable<Text>,
     }
 }
 
@@ -71,6 +72,8 @@ table! {
         state -> Text,
         creation_ts -> Timestamp,
         expiration_ts -> Timestamp,
+
+        score -> Nullable<Double>,
     }
 }
 
@@ -121,10 +124,36 @@ table! {
     }
 }
 
+table! {
+    market_agreement_amendment (id, agreement_id) {
+        id -> Text,
+        agreement_id -> Text,
+        valid_to -> Timestamp,
+        demand_properties -> Nullable<Text>,
+        offer_properties -> Nullable<Text>,
+        state -> Text,
+        creation_ts -> Timestamp,
+        reason -> Nullable<Text>,
+    }
+}
+
+table! {
+    market_blocked_node (node_id) {
+        node_id -> Text,
+        reason -> Nullable<Text>,
+        blocked_ts -> Timestamp,
+    }
+}
+
 allow_tables_to_appear_in_same_query!(market_demand, market_offer, market_offer_unsubscribed);
 allow_tables_to_appear_in_same_query!(market_proposal, market_negotiation);
-allow_tables_to_appear_in_same_query!(market_agreement, market_agreement_event);
+allow_tables_to_appear_in_same_query!(
+    market_agreement,
+    market_agreement_event,
+    market_agreement_amendment
+);
 
+joinable!(market_agreement_amendment -> market_agreement (agreement_id));
 joinable!(market_agreement_event -> market_agreement (agreement_id));
 joinable!(market_negotiation -> market_agreement (agreement_id));
 joinable!(market_offer -> market_offer_unsubscribed (id));
@@ -1,19 +1,23 @@
 use actix_web::web::Data;
 use chrono::{DateTime, Utc};
+use futures::stream::{Stream, StreamExt};
 use lazy_static::lazy_static;
 use metrics::counter;
+use std::convert::TryFrom;
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
 use crate::config::Config;
 use crate::db::dao::AgreementDao;
-use crate::db::model::{AgreementId, AppSessionId, Owner, SubscriptionId};
+use crate::db::model::{
+    AgreementId, AppSessionId, OfferSortKey, OffersPage, Owner, SubscriptionId,
+};
 use crate::identity::{IdentityApi, IdentityGSB};
 use crate::matcher::error::{
-    DemandError, MatcherError, MatcherInitError, QueryDemandsError, QueryOfferError,
-    QueryOffersError,
+    BlocklistError, DemandError, MatcherError, MatcherInitError, QueryDemandsError,
+    QueryOfferError, QueryOffersError,
 };
-use crate::matcher::{store::SubscriptionStore, Matcher};
+use crate::matcher::{schema::SchemaViolation, store::SubscriptionStore, Matcher};
 use crate::negotiation::error::{
     AgreementError, AgreementEventsError, NegotiationError, NegotiationInitError,
 };
@@ -25,7 +29,10 @@ use ya_client::model::market::{
     Agreement, AgreementListEntry, AgreementOperationEvent as ClientAgreementEvent, Demand,
     NewDemand, NewOffer, Offer, Reason, Role,
 };
-use ya_core_model::market::{local, BUS_ID};
+use ya_client::model::NodeId;
+use ya_core_model::market::{
+    local, AgreementsExport, BlockedNode, ExportAgreements, MatchExplanation, BUS_ID,
+};
 use ya_service_api_interfaces::{Provider, Service};
 use ya_service_api_web::middleware::Identity;
 
@@ -33,6 +40,9 @@ use crate::db::DbMixedExecutor;
 use ya_service_api_web::scope::ExtendableScope;
 
 pub mod agreement;
+pub mod blocklist;
+pub mod explain;
+pub mod export;
 
 #[derive(Error, Debug)]
 pub enum MarketError {
@@ -48,6 +58,8 @@ pub enum MarketError {
     DemandError(#[from] DemandError),
     #[error(transparent)]
     Negotiation(#[from] NegotiationError),
+    #[error(transparent)]
+    Blocklist(#[from] BlocklistError),
 }
 
 #[derive(Error, Debug)]
@@ -70,6 +82,7 @@ pub struct MarketService {
     pub matcher: Matcher,
     pub provider_engine: ProviderBroker,
     pub requestor_engine: RequestorBroker,
+    pub config: Arc<Config>,
 }
 
 impl MarketService {
@@ -111,8 +124,9 @@ impl MarketService {
             config.clone(),
         )?;
         let cleaner_db = db.clone();
+        let cleaner_config = config.db.clone();
         tokio::spawn(async move {
-            crate::db::dao::cleaner::clean_forever(cleaner_db, config.db.clone()).await;
+            crate::db::dao::cleaner::clean_forever(cleaner_db, cleaner_config).await;
         });
 
         Ok(MarketService {
@@ -120,6 +134,7 @@ impl MarketService {
             matcher,
             provider_engine,
             requestor_engine,
+            config,
         })
     }
 
@@ -136,6 +151,9 @@ impl MarketService {
             .bind_gsb(public_prefix, local_prefix)
             .await?;
         agreement::bind_gsb(self.db.clone(), public_prefix, local_prefix).await;
+        explain::bind_gsb(self.matcher.clone(), public_prefix, local_prefix).await;
+        blocklist::bind_gsb(self.matcher.store.clone(), public_prefix, local_prefix).await;
+        export::bind_gsb(self.db.clone(), public_prefix, local_prefix).await;
         Ok(())
     }
 
@@ -175,6 +193,20 @@ impl MarketService {
             .await?)
     }
 
+    pub async fn query_offers(
+        &self,
+        constraints: Option<&str>,
+        sort_by: OfferSortKey,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+    ) -> Result<OffersPage, MarketError> {
+        Ok(self
+            .matcher
+            .store
+            .query_offers(constraints, sort_by, cursor, limit)
+            .await?)
+    }
+
     pub async fn get_demands(&self, id: Option<Identity>) -> Result<Vec<Demand>, MarketError> {
         Ok(self
             .matcher
@@ -235,6 +267,54 @@ impl MarketService {
         Ok(())
     }
 
+    pub async fn explain_match(
+        &self,
+        demand_id: &SubscriptionId,
+        offer_id: &SubscriptionId,
+        id: &Identity,
+    ) -> Result<MatchExplanation, MarketError> {
+        Ok(self
+            .matcher
+            .explain_match(demand_id, offer_id, Some(id.identity))
+            .await?)
+    }
+
+    pub async fn block_node(
+        &self,
+        node_id: NodeId,
+        reason: Option<String>,
+    ) -> Result<BlockedNode, MarketError> {
+        Ok(self
+            .matcher
+            .store
+            .block_node(node_id, reason)
+            .await?
+            .into_client())
+    }
+
+    pub async fn unblock_node(&self, node_id: NodeId) -> Result<(), MarketError> {
+        Ok(self.matcher.store.unblock_node(node_id).await?)
+    }
+
+    pub async fn list_blocked_nodes(&self) -> Result<Vec<BlockedNode>, MarketError> {
+        Ok(self
+            .matcher
+            .store
+            .list_blocked_nodes()
+            .await?
+            .into_iter()
+            .map(|node| node.into_client())
+            .collect())
+    }
+
+    pub fn validate_schema(
+        &self,
+        properties: &serde_json::Value,
+        constraints: &str,
+    ) -> Result<Vec<SchemaViolation>, MarketError> {
+        Ok(self.matcher.validate_schema(properties, constraints)?)
+    }
+
     pub async fn list_agreements(
         &self,
         id: &Identity,
@@ -270,6 +350,14 @@ impl MarketService {
         Ok(result)
     }
 
+    pub async fn export_agreements(
+        &self,
+        id: &Identity,
+        filter: ExportAgreements,
+    ) -> Result<AgreementsExport, AgreementError> {
+        export::export_agreements(&self.db, Some(id.identity), filter).await
+    }
+
     pub async fn get_agreement(
         &self,
         agreement_id: &AgreementId,
@@ -304,7 +392,44 @@ impl MarketService {
             .await?
             .into_iter()
             .map(|event| event.into_client())
-            .collect())
+            .collect::<Result<_, _>>()
+            .map_err(|e| AgreementEventsError::Internal(e.to_string()))?)
+    }
+
+    pub async fn query_amendment_events(
+        &self,
+        session_id: &AppSessionId,
+        timeout: f32,
+        max_events: Option<i32>,
+        after_timestamp: DateTime<Utc>,
+        id: &Identity,
+    ) -> Result<Vec<rest_api::AmendmentEvent>, AgreementEventsError> {
+        self.requestor_engine
+            .common
+            .query_amendment_events(session_id, timeout, max_events, after_timestamp, id)
+            .await?
+            .into_iter()
+            .map(rest_api::AmendmentEvent::try_from)
+            .collect::<Result<_, _>>()
+            .map_err(|e| AgreementEventsError::Internal(e.to_string()))
+    }
+
+    pub fn stream_agreement_events(
+        &self,
+        session_id: &AppSessionId,
+        after_timestamp: DateTime<Utc>,
+        id: &Identity,
+    ) -> impl Stream<Item = Result<ClientAgreementEvent, AgreementEventsError>> {
+        self.requestor_engine
+            .common
+            .stream_agreement_events(session_id, after_timestamp, id)
+            .map(|result| {
+                result.and_then(|event| {
+                    event
+                        .into_client()
+                        .map_err(|e| AgreementEventsError::Internal(e.to_string()))
+                })
+            })
     }
 
     pub async fn terminate_agreement(
@@ -0,0 +1,61 @@
+use ya_core_model::market::{
+    BlockNode, BlockedNode, ListBlockedNodes, RpcMessageError, UnblockNode,
+};
+use ya_service_bus::typed::ServiceBinder;
+
+use crate::matcher::error::BlocklistError;
+use crate::matcher::store::SubscriptionStore;
+
+pub async fn bind_gsb(store: SubscriptionStore, _public_prefix: &str, local_prefix: &str) {
+    log::trace!("Binding market blocklist local service to service bus");
+    ServiceBinder::new(local_prefix, &(), store)
+        .bind_with_processor(block_node)
+        .bind_with_processor(unblock_node)
+        .bind_with_processor(list_blocked_nodes);
+    log::debug!("Successfully bound market blocklist local service to service bus");
+}
+
+async fn block_node(
+    _db: (),
+    store: SubscriptionStore,
+    _sender_id: String,
+    msg: BlockNode,
+) -> Result<BlockedNode, RpcMessageError> {
+    store
+        .block_node(msg.node_id, msg.reason)
+        .await
+        .map(|node| node.into_client())
+        .map_err(into_rpc_error)
+}
+
+async fn unblock_node(
+    _db: (),
+    store: SubscriptionStore,
+    _sender_id: String,
+    msg: UnblockNode,
+) -> Result<(), RpcMessageError> {
+    store
+        .unblock_node(msg.node_id)
+        .await
+        .map_err(into_rpc_error)
+}
+
+async fn list_blocked_nodes(
+    _db: (),
+    store: SubscriptionStore,
+    _sender_id: String,
+    _msg: ListBlockedNodes,
+) -> Result<Vec<BlockedNode>, RpcMessageError> {
+    store
+        .list_blocked_nodes()
+        .await
+        .map(|nodes| nodes.into_iter().map(|node| node.into_client()).collect())
+        .map_err(into_rpc_error)
+}
+
+fn into_rpc_error(e: BlocklistError) -> RpcMessageError {
+    match e {
+        BlocklistError::NotBlocked(_) => RpcMessageError::NotFound(e.to_string()),
+        BlocklistError::Db(_) => RpcMessageError::Market(e.to_string()),
+    }
+}
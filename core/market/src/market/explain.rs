@@ -0,0 +1,37 @@
+use std::str::FromStr;
+
+use ya_core_model::market::{ExplainMatch, MatchExplanation, RpcMessageError};
+use ya_service_bus::typed::ServiceBinder;
+
+use crate::db::model::SubscriptionId;
+use crate::matcher::error::{DemandError, MatcherError, QueryOfferError};
+use crate::matcher::Matcher;
+
+pub async fn bind_gsb(matcher: Matcher, _public_prefix: &str, local_prefix: &str) {
+    log::trace!("Binding market explain local service to service bus");
+    ServiceBinder::new(local_prefix, &(), matcher).bind_with_processor(explain_match);
+    log::debug!("Successfully bound market explain local service to service bus");
+}
+
+async fn explain_match(
+    _db: (),
+    matcher: Matcher,
+    _sender_id: String,
+    msg: ExplainMatch,
+) -> Result<MatchExplanation, RpcMessageError> {
+    let demand_id = SubscriptionId::from_str(&msg.demand_id)
+        .map_err(|e| RpcMessageError::BadRequest(e.to_string()))?;
+    let offer_id = SubscriptionId::from_str(&msg.offer_id)
+        .map_err(|e| RpcMessageError::BadRequest(e.to_string()))?;
+
+    matcher
+        .explain_match(&demand_id, &offer_id, None)
+        .await
+        .map_err(|e| match e {
+            MatcherError::Demand(DemandError::NotFound(_))
+            | MatcherError::QueryOffer(QueryOfferError::NotFound(_)) => {
+                RpcMessageError::NotFound(e.to_string())
+            }
+            _ => RpcMessageError::Market(e.to_string()),
+        })
+}
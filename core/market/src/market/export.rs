@@ -0,0 +1,234 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Deserialize;
+
+use ya_client::model::market::{agreement::State as ClientState, Role};
+use ya_client::model::NodeId;
+use ya_core_model::market::{
+    AgreementStatistics, AgreementsExport, ExportAgreements, ExportedAgreement, RpcMessageError,
+};
+use ya_service_bus::typed::ServiceBinder;
+
+use crate::db::dao::AgreementDao;
+use crate::db::model::{Agreement, AgreementEvent, AgreementEventType, Owner};
+use crate::db::DbMixedExecutor;
+use crate::negotiation::error::AgreementError;
+use crate::negotiation::termination_reason_code;
+
+/// Format of exported Agreements.
+#[derive(
+    strum_macros::EnumString, strum_macros::Display, Clone, Copy, Debug, PartialEq, Eq, Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl Default for ExportFormat {
+    fn default() -> Self {
+        ExportFormat::Json
+    }
+}
+
+const CSV_HEADER: &str = "agreement_id,role,peer_id,state,app_session_id,creation_date,\
+approved_date,valid_to,end_date,ended_by,reason_code,reason_message,\
+demand_properties,offer_properties";
+
+pub async fn bind_gsb(db: DbMixedExecutor, _public_prefix: &str, local_prefix: &str) {
+    log::trace!("Binding market export local service to service bus");
+    ServiceBinder::new(local_prefix, &db, ()).bind(export);
+    log::debug!("Successfully bound market export local service to service bus");
+}
+
+async fn export(
+    db: DbMixedExecutor,
+    _sender_id: String,
+    msg: ExportAgreements,
+) -> Result<AgreementsExport, RpcMessageError> {
+    export_agreements(&db, None, msg)
+        .await
+        .map_err(|e| RpcMessageError::Market(e.to_string()))
+}
+
+/// Collects Agreements matching filter together with the final event of each
+/// Agreement. If `node_id` is set, only Agreements of this identity are exported.
+pub async fn export_agreements(
+    db: &DbMixedExecutor,
+    node_id: Option<NodeId>,
+    filter: ExportAgreements,
+) -> Result<AgreementsExport, AgreementError> {
+    let agreements = db
+        .as_dao::<AgreementDao>()
+        .list_with_events(
+            node_id,
+            filter.peer_id,
+            filter.state.map(Into::into),
+            filter.before_date,
+            filter.after_date,
+            filter.app_session_id,
+        )
+        .await
+        .map_err(|e| AgreementError::Internal(e.to_string()))?;
+
+    let mut statistics = Statistics::default();
+    let agreements = agreements
+        .into_iter()
+        .map(|(agreement, events)| {
+            statistics.add(&agreement, &events);
+            export_agreement(agreement, events)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(AgreementsExport {
+        agreements,
+        statistics: statistics.finish(),
+    })
+}
+
+fn export_agreement(
+    agreement: Agreement,
+    events: Vec<AgreementEvent>,
+) -> Result<ExportedAgreement, AgreementError> {
+    let (role, peer_id) = match agreement.id.owner() {
+        Owner::Provider => (Role::Provider, agreement.requestor_id),
+        Owner::Requestor => (Role::Requestor, agreement.provider_id),
+    };
+    let end = events.into_iter().rev().find(|event| is_final(event));
+
+    Ok(ExportedAgreement {
+        agreement: agreement
+            .into_client()
+            .map_err(|e| AgreementError::Internal(e.to_string()))?,
+        role,
+        peer_id,
+        end_date: end.as_ref().map(|event| naive_to_utc(event.timestamp)),
+        ended_by: end.as_ref().map(|event| match event.issuer {
+            Owner::Provider => Role::Provider,
+            Owner::Requestor => Role::Requestor,
+        }),
+        reason: end.and_then(|event| event.reason).map(|reason| reason.0),
+    })
+}
+
+fn is_final(event: &AgreementEvent) -> bool {
+    match event.event_type {
+        AgreementEventType::Approved
+        | AgreementEventType::AmendmentProposed
+        | AgreementEventType::AmendmentApproved
+        | AgreementEventType::AmendmentRejected => false,
+        AgreementEventType::Rejected
+        | AgreementEventType::Cancelled
+        | AgreementEventType::Terminated => true,
+    }
+}
+
+fn naive_to_utc(ts: NaiveDateTime) -> DateTime<Utc> {
+    DateTime::<Utc>::from_utc(ts, Utc)
+}
+
+#[derive(Default)]
+struct Statistics {
+    stats: AgreementStatistics,
+    durations_count: u64,
+    durations_sum: i64,
+}
+
+impl Statistics {
+    fn add(&mut self, agreement: &Agreement, events: &[AgreementEvent]) {
+        self.stats.count += 1;
+        *self
+            .stats
+            .states
+            .entry(format!("{:?}", ClientState::from(agreement.state)))
+            .or_default() += 1;
+
+        let terminated = events
+            .iter()
+            .find(|event| event.event_type == AgreementEventType::Terminated);
+        if let Some(terminated) = terminated {
+            let reason = terminated.reason.clone().map(|reason| reason.0);
+            *self
+                .stats
+                .termination_reasons
+                .entry(termination_reason_code(&reason))
+                .or_default() += 1;
+
+            if let Some(approved_ts) = agreement.approved_ts {
+                self.durations_count += 1;
+                self.durations_sum += (terminated.timestamp - approved_ts).num_milliseconds();
+            }
+        }
+    }
+
+    fn finish(mut self) -> AgreementStatistics {
+        if self.durations_count > 0 {
+            self.stats.average_duration_secs =
+                Some(self.durations_sum as f64 / self.durations_count as f64 / 1000.0);
+        }
+        self.stats
+    }
+}
+
+/// Formats exported Agreements as CSV with header row. Properties are
+/// included as json strings.
+pub fn to_csv(agreements: &[ExportedAgreement]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+
+    for exported in agreements {
+        let agreement = &exported.agreement;
+        let reason = exported.reason.as_ref();
+        let date = |ts: Option<DateTime<Utc>>| ts.map(|ts| ts.to_rfc3339()).unwrap_or_default();
+        let row = [
+            agreement.agreement_id.clone(),
+            exported.role.to_string(),
+            exported.peer_id.to_string(),
+            format!("{:?}", agreement.state),
+            agreement.app_session_id.clone().unwrap_or_default(),
+            agreement.timestamp.to_rfc3339(),
+            date(agreement.approved_date),
+            agreement.valid_to.to_rfc3339(),
+            date(exported.end_date),
+            exported
+                .ended_by
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            reason
+                .map(|_| termination_reason_code(&exported.reason))
+                .unwrap_or_default(),
+            reason
+                .map(|reason| reason.message.clone())
+                .unwrap_or_default(),
+            agreement.demand.properties.to_string(),
+            agreement.offer.properties.to_string(),
+        ];
+
+        let row = row.iter().map(|field| escape(field)).collect::<Vec<_>>();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn escape(field: &str) -> String {
+    if field.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape;
+
+    #[test]
+    fn test_escape_csv_field() {
+        assert_eq!(escape("Success"), "Success");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("{\"x\":1}"), "\"{\"\"x\"\":1}\"");
+        assert_eq!(escape("line\nbreak"), "\"line\nbreak\"");
+    }
+}
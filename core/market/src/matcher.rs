@@ -6,6 +6,8 @@ use std::sync::Arc;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
 use ya_client::model::market::{NewDemand, NewOffer};
+use ya_client::model::NodeId;
+use ya_core_model::market::MatchExplanation;
 use ya_service_api_web::middleware::Identity;
 use ya_utils_actix::deadline_checker::{
     bind_deadline_reaction, DeadlineChecker, StopTracking, TrackDeadline,
@@ -19,13 +21,19 @@ use crate::protocol::discovery::{builder::DiscoveryBuilder, Discovery};
 pub(crate) mod cyclic;
 pub mod error;
 pub(crate) mod handlers;
+pub(crate) mod index;
 pub(crate) mod resolver;
+pub mod schema;
 pub(crate) mod store;
 
 use crate::db::dao::{DemandDao, DemandState};
-use error::{MatcherError, MatcherInitError, QueryOfferError, QueryOffersError};
+use error::{
+    DemandError, MatcherError, MatcherInitError, PropertySchemaError, QueryOfferError,
+    QueryOffersError,
+};
 use futures::FutureExt;
 use resolver::Resolver;
+use schema::{SchemaValidation, SchemaViolation};
 use store::SubscriptionStore;
 
 /// Stores proposal generated from resolver.
@@ -104,6 +112,10 @@ impl Matcher {
     ) -> Result<(), MatcherInitError> {
         self.discovery.bind_gsb(public_prefix, local_prefix).await?;
 
+        // Offers stored before restart must be indexed, otherwise
+        // Resolver would never match them with new Demands.
+        self.store.rebuild_index().await?;
+
         // We can't spawn broadcasts, before gsb is bound.
         // That's why we don't spawn this in Matcher::new.
         tokio::task::spawn_local(cyclic::bcast_offers(self.clone()));
@@ -269,6 +281,47 @@ impl Matcher {
         Ok(())
     }
 
+    /// Explains, why Demand does (not) match Offer. If `owner` is given,
+    /// Demand must belong to this identity.
+    pub async fn explain_match(
+        &self,
+        demand_id: &SubscriptionId,
+        offer_id: &SubscriptionId,
+        owner: Option<NodeId>,
+    ) -> Result<MatchExplanation, MatcherError> {
+        let demand = self.store.get_demand(demand_id).await?;
+        if owner.map(|owner| owner != demand.node_id).unwrap_or(false) {
+            return Err(DemandError::NotFound(demand_id.clone()).into());
+        }
+
+        let offer = self.store.get_offer(offer_id).await?;
+        Ok(resolver::explain(&offer, &demand)?)
+    }
+
+    /// Validates Offer or Demand properties and constraints against property schema.
+    /// Returns violations, that should be reported as warnings, or error
+    /// if strict validation is enabled.
+    pub fn validate_schema(
+        &self,
+        properties: &serde_json::Value,
+        constraints: &str,
+    ) -> Result<Vec<SchemaViolation>, MatcherError> {
+        let config = &self.config.schema;
+        if config.validation == SchemaValidation::Disabled {
+            return Ok(vec![]);
+        }
+
+        let violations = config.version.schema().validate(properties, constraints);
+        if config.validation == SchemaValidation::Strict && !violations.is_empty() {
+            return Err(PropertySchemaError {
+                version: config.version,
+                violations,
+            }
+            .into());
+        }
+        Ok(violations)
+    }
+
     pub async fn get_our_active_offer_ids(&self) -> Result<Vec<SubscriptionId>, QueryOffersError> {
         let our_node_ids = self.identity.list().await?;
         self.store.get_active_offer_ids(Some(our_node_ids)).await
@@ -1,6 +1,10 @@
-use crate::db::model::{SubscriptionId, SubscriptionValidationError};
+use ya_client::model::NodeId;
+use ya_market_resolver::MatchError;
+
+use crate::db::model::{OfferCursorParseError, SubscriptionId, SubscriptionValidationError};
 use crate::db::DbError;
 use crate::identity::IdentityError;
+use crate::matcher::schema::{SchemaVersion, SchemaViolation};
 use crate::protocol::discovery::error::DiscoveryInitError;
 
 #[derive(thiserror::Error, Debug)]
@@ -25,6 +29,12 @@ pub enum QueryOffersError {
     DbError(#[from] DbError),
     #[error("Failed to list Offers based on identity. Error: {0}.")]
     IdentityError(#[from] IdentityError),
+    #[error("Invalid Offers query constraints. Error: {0}.")]
+    InvalidConstraints(#[from] MatchError),
+    #[error(transparent)]
+    InvalidCursor(#[from] OfferCursorParseError),
+    #[error("Invalid limit '{0}', should be between 1 and {1}.")]
+    InvalidLimit(i32, i32),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -55,6 +65,8 @@ pub enum SaveOfferError {
     Unsubscribed(SubscriptionId),
     #[error("Offer [{0}] expired.")]
     Expired(SubscriptionId),
+    #[error("Node [{0}] exceeded quota of {1} active Offers.")]
+    QuotaExceeded(NodeId, u32),
     #[error(transparent)]
     SubscriptionValidation(#[from] SubscriptionValidationError),
     #[error(transparent)]
@@ -107,6 +119,27 @@ pub enum MatcherError {
     SaveOffer(#[from] SaveOfferError),
     #[error(transparent)]
     ModifyOffer(#[from] ModifyOfferError),
+    #[error("Failed to explain matching. Error: {0}.")]
+    Explain(#[from] MatchError),
+    #[error(transparent)]
+    Schema(#[from] PropertySchemaError),
+    #[error(transparent)]
+    Blocklist(#[from] BlocklistError),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Properties don't conform to schema version {version}. {}", join_violations(.violations))]
+pub struct PropertySchemaError {
+    pub version: SchemaVersion,
+    pub violations: Vec<SchemaViolation>,
+}
+
+fn join_violations(violations: &[SchemaViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -115,6 +148,8 @@ pub enum MatcherInitError {
     DiscoveryInitError(#[from] DiscoveryInitError),
     #[error("Failed to initialize expiration tracker. Error: {0}.")]
     ExpirationTrackerError(String),
+    #[error("Failed to initialize Offers index. Error: {0}.")]
+    IndexError(#[from] QueryOffersError),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -125,6 +160,16 @@ pub enum ResolverError {
     QueryOffers(#[from] QueryOffersError),
     #[error(transparent)]
     Demand(#[from] DemandError),
+    #[error(transparent)]
+    Blocklist(#[from] BlocklistError),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BlocklistError {
+    #[error("Failed to access node blocklist. Error: {0}.")]
+    Db(#[from] DbError),
+    #[error("Node [{0}] is not blocked.")]
+    NotBlocked(NodeId),
 }
 
 impl From<ResolverError> for MatcherError {
@@ -133,6 +178,7 @@ impl From<ResolverError> for MatcherError {
             ResolverError::QueryOffer(e) => MatcherError::QueryOffer(e),
             ResolverError::QueryOffers(e) => MatcherError::QueryOffers(e),
             ResolverError::Demand(e) => MatcherError::Demand(e),
+            ResolverError::Blocklist(e) => MatcherError::Blocklist(e),
         }
     }
 }
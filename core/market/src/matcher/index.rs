@@ -0,0 +1,363 @@
+//! In-memory index of Offer properties.
+//!
+//! Used by Resolver to pre-filter Offers, that can possibly match Demand,
+//! before resolving full constraints expression. Index is conservative:
+//! it can return Offers, that won't match, but never skips Offer, that would.
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
+
+use ya_market_resolver::resolver::expression::build_expression;
+use ya_market_resolver::resolver::properties::{PropertyRef, PropertyRefType};
+use ya_market_resolver::resolver::{ldap_parser, Expression};
+
+use crate::config::IndexConfig;
+use crate::db::model::{Offer, SubscriptionId};
+
+pub struct OfferIndex {
+    values: HashMap<String, ValueIndex>,
+    ranges: HashMap<String, RangeIndex>,
+    /// Entries added for each Offer. Necessary to remove Offer from index.
+    entries: HashMap<SubscriptionId, Vec<Entry>>,
+}
+
+/// Indexes string property values.
+#[derive(Default)]
+struct ValueIndex {
+    by_value: HashMap<String, HashSet<SubscriptionId>>,
+    /// Offers with property value, that can't be indexed (not a plain string).
+    /// They are always returned as candidates.
+    other: HashSet<SubscriptionId>,
+}
+
+/// Indexes numeric property values.
+#[derive(Default)]
+struct RangeIndex {
+    by_value: BTreeMap<Number, HashSet<SubscriptionId>>,
+    /// Offers with property value, that is not a number.
+    /// They are always returned as candidates.
+    other: HashSet<SubscriptionId>,
+}
+
+enum Entry {
+    Value(String, Option<String>),
+    Range(String, Option<Number>),
+}
+
+/// JSON numbers are never NaN, so we can order them totally.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Number(f64);
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.partial_cmp(&other.0).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl OfferIndex {
+    pub fn new(config: &IndexConfig) -> Self {
+        OfferIndex {
+            values: split_properties(&config.indexed_properties)
+                .map(|name| (name, ValueIndex::default()))
+                .collect(),
+            ranges: split_properties(&config.indexed_numeric_properties)
+                .map(|name| (name, RangeIndex::default()))
+                .collect(),
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.values.is_empty() || !self.ranges.is_empty()
+    }
+
+    pub fn insert(&mut self, offer: &Offer) {
+        if !self.is_enabled() || self.entries.contains_key(&offer.id) {
+            return;
+        }
+
+        // Offers without parsable properties never match anything, but we still
+        // need to track them, so they won't be skipped in case of doubt.
+        let properties =
+            serde_json::from_str::<Map<String, Value>>(&offer.properties).unwrap_or_default();
+        let mut entries = vec![];
+
+        for (name, index) in self.values.iter_mut() {
+            match properties.get(name) {
+                None => continue,
+                Some(Value::String(value)) if is_plain(value) => {
+                    index
+                        .by_value
+                        .entry(value.clone())
+                        .or_default()
+                        .insert(offer.id.clone());
+                    entries.push(Entry::Value(name.clone(), Some(value.clone())));
+                }
+                Some(_) => {
+                    index.other.insert(offer.id.clone());
+                    entries.push(Entry::Value(name.clone(), None));
+                }
+            }
+        }
+
+        for (name, index) in self.ranges.iter_mut() {
+            match properties.get(name) {
+                None => continue,
+                Some(Value::Number(value)) if value.as_f64().is_some() => {
+                    let value = Number(value.as_f64().unwrap());
+                    index
+                        .by_value
+                        .entry(value)
+                        .or_default()
+                        .insert(offer.id.clone());
+                    entries.push(Entry::Range(name.clone(), Some(value)));
+                }
+                Some(_) => {
+                    index.other.insert(offer.id.clone());
+                    entries.push(Entry::Range(name.clone(), None));
+                }
+            }
+        }
+
+        self.entries.insert(offer.id.clone(), entries);
+    }
+
+    pub fn remove(&mut self, id: &SubscriptionId) {
+        let entries = match self.entries.remove(id) {
+            Some(entries) => entries,
+            None => return,
+        };
+
+        for entry in entries {
+            match entry {
+                Entry::Value(name, value) => {
+                    if let Some(index) = self.values.get_mut(&name) {
+                        match value {
+                            Some(value) => remove_value(&mut index.by_value, &value, id),
+                            None => {
+                                index.other.remove(id);
+                            }
+                        }
+                    }
+                }
+                Entry::Range(name, value) => {
+                    if let Some(index) = self.ranges.get_mut(&name) {
+                        match value {
+                            Some(value) => remove_number(&mut index.by_value, &value, id),
+                            None => {
+                                index.other.remove(id);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns ids of indexed Offers, that can possibly match Demand with given constraints.
+    /// Returns None, if constraints don't restrict any indexed property, so all Offers
+    /// should be checked.
+    pub fn candidates(&self, demand_constraints: &str) -> Option<HashSet<SubscriptionId>> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let expression = ldap_parser::parse(demand_constraints)
+            .ok()
+            .and_then(|tag| build_expression(&tag).ok())?;
+
+        // Only factors of top level conjunction are necessary conditions for Offer to match.
+        let mut result: Option<HashSet<SubscriptionId>> = None;
+        for factor in conjunction_factors(&expression) {
+            if let Some(ids) = self.factor_candidates(factor) {
+                result = Some(match result {
+                    Some(result) => result.intersection(&ids).cloned().collect(),
+                    None => ids,
+                });
+            }
+        }
+        result
+    }
+
+    fn factor_candidates(&self, factor: &Expression) -> Option<HashSet<SubscriptionId>> {
+        let (name, value) = match factor {
+            Expression::Equals(PropertyRef::Value(name, PropertyRefType::Any), value)
+            | Expression::Greater(PropertyRef::Value(name, PropertyRefType::Any), value)
+            | Expression::GreaterEqual(PropertyRef::Value(name, PropertyRefType::Any), value)
+            | Expression::Less(PropertyRef::Value(name, PropertyRefType::Any), value)
+            | Expression::LessEqual(PropertyRef::Value(name, PropertyRefType::Any), value) => {
+                (name, value)
+            }
+            _ => return None,
+        };
+
+        if let (Expression::Equals(..), Some(index)) = (factor, self.values.get(name)) {
+            // Wildcards can't be looked up in index.
+            if value.contains('*') {
+                return None;
+            }
+            let mut ids = index.other.clone();
+            if let Some(matching) = index.by_value.get(value) {
+                ids.extend(matching.iter().cloned());
+            }
+            return Some(ids);
+        }
+
+        let index = self.ranges.get(name)?;
+        // Resolver won't match numeric property with value, that isn't a number.
+        let value = Number(value.parse::<f64>().ok()?);
+        let range = match factor {
+            Expression::Equals(..) => (Bound::Included(value), Bound::Included(value)),
+            Expression::Greater(..) => (Bound::Excluded(value), Bound::Unbounded),
+            Expression::GreaterEqual(..) => (Bound::Included(value), Bound::Unbounded),
+            Expression::Less(..) => (Bound::Unbounded, Bound::Excluded(value)),
+            Expression::LessEqual(..) => (Bound::Unbounded, Bound::Included(value)),
+            _ => return None,
+        };
+
+        let mut ids = index.other.clone();
+        for (_, matching) in index.by_value.range(range) {
+            ids.extend(matching.iter().cloned());
+        }
+        Some(ids)
+    }
+}
+
+fn split_properties(list: &str) -> impl Iterator<Item = String> + '_ {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+fn conjunction_factors(expression: &Expression) -> Vec<&Expression> {
+    match expression {
+        Expression::And(factors) => factors.iter().flat_map(conjunction_factors).collect(),
+        _ => vec![expression],
+    }
+}
+
+/// Resolver compares strings without unescaping them, so we index
+/// only values, that look the same before and after JSON serialization.
+fn is_plain(value: &str) -> bool {
+    !value
+        .chars()
+        .any(|c| c == '\\' || c == '"' || c.is_control())
+}
+
+fn remove_value(
+    by_value: &mut HashMap<String, HashSet<SubscriptionId>>,
+    value: &str,
+    id: &SubscriptionId,
+) {
+    if let Some(ids) = by_value.get_mut(value) {
+        ids.remove(id);
+        if ids.is_empty() {
+            by_value.remove(value);
+        }
+    }
+}
+
+fn remove_number(
+    by_value: &mut BTreeMap<Number, HashSet<SubscriptionId>>,
+    value: &Number,
+    id: &SubscriptionId,
+) {
+    if let Some(ids) = by_value.get_mut(value) {
+        ids.remove(id);
+        if ids.is_empty() {
+            by_value.remove(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    use ya_client::model::market::NewOffer;
+
+    use super::OfferIndex;
+    use crate::config::IndexConfig;
+    use crate::db::model::{Offer, SubscriptionId};
+    use crate::testing::mock_identity::generate_identity;
+
+    fn index() -> OfferIndex {
+        OfferIndex::new(&IndexConfig {
+            indexed_properties: "golem.runtime.name".to_string(),
+            indexed_numeric_properties: "golem.inf.mem.gib".to_string(),
+        })
+    }
+
+    fn offer(properties: serde_json::Value) -> Offer {
+        let creation_ts = Utc::now().naive_utc();
+        let expiration_ts = creation_ts + Duration::hours(1);
+        let offer = NewOffer::new(properties, "()".to_string());
+        Offer::from_new(&offer, &generate_identity(""), creation_ts, expiration_ts).unwrap()
+    }
+
+    fn ids(offers: &[&Offer]) -> HashSet<SubscriptionId> {
+        offers.iter().map(|offer| offer.id.clone()).collect()
+    }
+
+    #[test]
+    fn candidates_by_value_and_range() {
+        let mut index = index();
+        let vm_small = offer(json!({"golem": {"runtime.name": "vm", "inf.mem.gib": 2}}));
+        let vm_big = offer(json!({"golem": {"runtime.name": "vm", "inf.mem.gib": 16}}));
+        let wasm_big = offer(json!({"golem": {"runtime.name": "wasmtime", "inf.mem.gib": 16}}));
+        let vm_list = offer(json!({"golem": {"runtime.name": ["vm", "docker"]}}));
+        for offer in &[&vm_small, &vm_big, &wasm_big, &vm_list] {
+            index.insert(offer);
+        }
+
+        assert_eq!(
+            index.candidates("(golem.runtime.name=vm)"),
+            Some(ids(&[&vm_small, &vm_big, &vm_list]))
+        );
+        assert_eq!(
+            index.candidates("(&(golem.runtime.name=vm)(golem.inf.mem.gib>=8))"),
+            Some(ids(&[&vm_big]))
+        );
+        assert_eq!(
+            index.candidates("(golem.inf.mem.gib<16)"),
+            Some(ids(&[&vm_small]))
+        );
+    }
+
+    #[test]
+    fn candidates_not_restricting_index() {
+        let mut index = index();
+        index.insert(&offer(json!({"golem": {"runtime.name": "vm"}})));
+
+        assert_eq!(index.candidates("()"), None);
+        assert_eq!(index.candidates("(golem.runtime.name=v*)"), None);
+        assert_eq!(index.candidates("(|(golem.runtime.name=vm)(a=b))"), None);
+        assert_eq!(index.candidates("(golem.node.id.name=vm)"), None);
+    }
+
+    #[test]
+    fn remove_from_index() {
+        let mut index = index();
+        let vm = offer(json!({"golem": {"runtime.name": "vm", "inf.mem.gib": 2}}));
+        index.insert(&vm);
+        index.remove(&vm.id);
+
+        assert!(index.entries.is_empty());
+        assert_eq!(
+            index.candidates("(golem.runtime.name=vm)"),
+            Some(HashSet::new())
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
-use ya_market_resolver::{match_demand_offer, Match};
+use ya_core_model::market::{ConstraintsExplanation, MatchExplanation};
+use ya_market_resolver::{explain_demand_offer, match_demand_offer, Match, MatchError};
 
 use super::{error::ResolverError, RawProposal, SubscriptionStore};
 use crate::db::model::{Demand, Offer, SubscriptionId};
@@ -75,6 +76,14 @@ impl Resolver {
         match subscription {
             Subscription::Offer(id) => {
                 let offer = self.store.get_offer(id).await?;
+                if self.store.is_node_blocked(offer.node_id).await? {
+                    log::debug!(
+                        "Skipping {} from blocked node [{}].",
+                        subscription,
+                        offer.node_id
+                    );
+                    return Ok(());
+                }
                 self.store
                     .get_demands_before(offer.insertion_ts.unwrap())
                     .await?
@@ -84,10 +93,12 @@ impl Resolver {
             }
             Subscription::Demand(id) => {
                 let demand = self.store.get_demand(id).await?;
+                let blocked = self.store.blocked_node_ids().await?;
                 self.store
-                    .get_offers_before(demand.insertion_ts.unwrap())
+                    .get_offer_candidates_before(&demand, demand.insertion_ts.unwrap())
                     .await?
                     .into_iter()
+                    .filter(|offer| !blocked.contains(&offer.node_id))
                     .filter(|offer| matches(offer, &demand))
                     .for_each(|offer| self.emit_proposal(offer, demand.clone()));
             }
@@ -133,6 +144,36 @@ fn matches(offer: &Offer, demand: &Demand) -> bool {
     }
 }
 
+/// Explains result of matching for the specific Offer-Demand pair.
+pub(crate) fn explain(offer: &Offer, demand: &Demand) -> Result<MatchExplanation, MatchError> {
+    let explanation = explain_demand_offer(
+        &demand.properties,
+        &demand.constraints,
+        &offer.properties,
+        &offer.constraints,
+    )?;
+    let same_identity = offer.node_id == demand.node_id;
+
+    Ok(MatchExplanation {
+        demand_id: demand.id.to_string(),
+        offer_id: offer.id.to_string(),
+        matching: !same_identity && explanation.result == Match::Yes,
+        same_identity,
+        demand_constraints: into_model_explanation(explanation.demand),
+        offer_constraints: into_model_explanation(explanation.offer),
+    })
+}
+
+fn into_model_explanation(
+    explanation: ya_market_resolver::ConstraintsExplanation,
+) -> ConstraintsExplanation {
+    ConstraintsExplanation {
+        failing: explanation.failing,
+        undefined: explanation.undefined,
+        missing_properties: explanation.missing_properties,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::matcher::resolver::matches;
@@ -0,0 +1,428 @@
+//! Schema of well-known `golem.*` properties.
+//!
+//! Offers and Demands with typos in property names (for example `golem.inf.mem.gb`)
+//! are accepted by market, but silently never match. Schema is used to detect
+//! such mistakes at subscribe time. Properties outside of well-known namespaces
+//! are not validated.
+use serde_json::Value;
+use std::fmt;
+
+use ya_market_resolver::resolver::expression::build_expression;
+use ya_market_resolver::resolver::properties::PropertyRef;
+use ya_market_resolver::resolver::{ldap_parser, Expression};
+
+/// How to treat Offers and Demands not conforming to schema.
+#[derive(strum_macros::EnumString, strum_macros::Display, Clone, Copy, Debug, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum SchemaValidation {
+    Disabled,
+    /// Subscribe, but report violations as warnings.
+    Warn,
+    /// Reject subscription.
+    Strict,
+}
+
+#[derive(strum_macros::EnumString, strum_macros::Display, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaVersion {
+    #[strum(serialize = "1")]
+    V1,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PropertyType {
+    String,
+    Number,
+    Bool,
+    List,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaViolation {
+    UnknownProperty {
+        name: String,
+        suggestion: Option<&'static str>,
+    },
+    InvalidType {
+        name: String,
+        expected: PropertyType,
+    },
+}
+
+pub struct PropertySchema {
+    pub version: SchemaVersion,
+    /// Namespaces, which are fully described by schema.
+    namespaces: &'static [&'static str],
+    /// Property names with types. `*` matches single name segment.
+    properties: &'static [(&'static str, PropertyType)],
+}
+
+const SCHEMA_V1: PropertySchema = PropertySchema {
+    version: SchemaVersion::V1,
+    namespaces: &[
+        "golem.activity.",
+        "golem.com.",
+        "golem.inf.",
+        "golem.node.",
+        "golem.runtime.",
+        "golem.srv.",
+    ],
+    properties: &[
+        ("golem.activity.caps.transfer.protocol", PropertyType::List),
+        ("golem.com.payment.chosen-platform", PropertyType::String),
+        (
+            "golem.com.payment.debit-notes.accept-timeout",
+            PropertyType::Number,
+        ),
+        ("golem.com.payment.platform.*.address", PropertyType::String),
+        ("golem.com.pricing.model", PropertyType::String),
+        ("golem.com.pricing.model.linear.coeffs", PropertyType::List),
+        (
+            "golem.com.pricing.model.minimum-charge.coeffs",
+            PropertyType::List,
+        ),
+        (
+            "golem.com.pricing.model.minimum-charge.minimum",
+            PropertyType::Number,
+        ),
+        ("golem.com.pricing.model.tiered.coeffs", PropertyType::List),
+        (
+            "golem.com.pricing.model.tiered.discount",
+            PropertyType::Number,
+        ),
+        (
+            "golem.com.pricing.model.tiered.threshold-sec",
+            PropertyType::Number,
+        ),
+        (
+            "golem.com.pricing.model.time-of-day.coeffs",
+            PropertyType::List,
+        ),
+        (
+            "golem.com.pricing.model.time-of-day.windows",
+            PropertyType::List,
+        ),
+        ("golem.com.scheme", PropertyType::String),
+        (
+            "golem.com.scheme.payu.debit-note.interval-sec",
+            PropertyType::Number,
+        ),
+        ("golem.com.scheme.payu.interval_sec", PropertyType::Number),
+        (
+            "golem.com.scheme.payu.payment-timeout-sec",
+            PropertyType::Number,
+        ),
+        ("golem.com.usage.vector", PropertyType::List),
+        ("golem.inf.cpu.architecture", PropertyType::String),
+        ("golem.inf.cpu.brand", PropertyType::String),
+        ("golem.inf.cpu.capabilities", PropertyType::List),
+        ("golem.inf.cpu.cores", PropertyType::Number),
+        ("golem.inf.cpu.model", PropertyType::String),
+        ("golem.inf.cpu.threads", PropertyType::Number),
+        ("golem.inf.cpu.vendor", PropertyType::String),
+        ("golem.inf.mem.gib", PropertyType::Number),
+        ("golem.inf.slot", PropertyType::String),
+        ("golem.inf.storage.gib", PropertyType::Number),
+        ("golem.node.debug.subnet", PropertyType::String),
+        ("golem.node.geo.country_code", PropertyType::String),
+        ("golem.node.id.name", PropertyType::String),
+        ("golem.node.net.is-public", PropertyType::Bool),
+        ("golem.runtime.capabilities", PropertyType::List),
+        ("golem.runtime.name", PropertyType::String),
+        ("golem.runtime.version", PropertyType::String),
+        ("golem.srv.caps.multi-activity", PropertyType::Bool),
+        ("golem.srv.caps.payload-manifest", PropertyType::Bool),
+        ("golem.srv.comp.expiration", PropertyType::Number),
+        ("golem.srv.comp.payload", PropertyType::String),
+        ("golem.srv.comp.payload.cert", PropertyType::String),
+        ("golem.srv.comp.payload.sig", PropertyType::String),
+        ("golem.srv.comp.payload.sig.algorithm", PropertyType::String),
+        ("golem.srv.comp.task_package", PropertyType::String),
+        ("golem.srv.comp.vm.package_format", PropertyType::String),
+    ],
+};
+
+/// Maximal edit distance between unknown property and suggested property name.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+impl SchemaVersion {
+    pub fn schema(&self) -> &'static PropertySchema {
+        match self {
+            SchemaVersion::V1 => &SCHEMA_V1,
+        }
+    }
+}
+
+impl PropertySchema {
+    /// Validates properties (flattened or not) and names of properties
+    /// referenced in constraints. Constraints, that can't be parsed are ignored here,
+    /// because they are validated elsewhere.
+    pub fn validate(&self, properties: &Value, constraints: &str) -> Vec<SchemaViolation> {
+        let mut violations = vec![];
+
+        let properties = ya_agreement_utils::agreement::flatten(properties.clone());
+        for (name, value) in properties.iter() {
+            if let Some(violation) = self.validate_property(name, Some(value)) {
+                violations.push(violation);
+            }
+        }
+
+        let expression = ldap_parser::parse(constraints)
+            .ok()
+            .and_then(|tag| build_expression(&tag).ok());
+        if let Some(expression) = expression {
+            let mut names = vec![];
+            referenced_properties(&expression, &mut names);
+            for name in names {
+                if let Some(violation) = self.validate_property(name, None) {
+                    if !violations.contains(&violation) {
+                        violations.push(violation);
+                    }
+                }
+            }
+        }
+        violations
+    }
+
+    fn validate_property(&self, name: &str, value: Option<&Value>) -> Option<SchemaViolation> {
+        // Optional properties are marked with `?` suffix.
+        let name = name.trim_end_matches('?');
+        if !self.namespaces.iter().any(|ns| name.starts_with(ns)) {
+            return None;
+        }
+
+        let expected = match self.property_type(name) {
+            Some(expected) => expected,
+            None => {
+                return Some(SchemaViolation::UnknownProperty {
+                    name: name.to_string(),
+                    suggestion: self.suggest(name),
+                })
+            }
+        };
+
+        let valid = match value {
+            None => true,
+            Some(Value::String(_)) => expected == PropertyType::String,
+            Some(Value::Number(_)) => expected == PropertyType::Number,
+            Some(Value::Bool(_)) => expected == PropertyType::Bool,
+            Some(Value::Array(_)) => expected == PropertyType::List,
+            Some(_) => false,
+        };
+        if valid {
+            return None;
+        }
+        Some(SchemaViolation::InvalidType {
+            name: name.to_string(),
+            expected,
+        })
+    }
+
+    fn property_type(&self, name: &str) -> Option<PropertyType> {
+        self.properties
+            .iter()
+            .find(|(pattern, _)| matches_pattern(pattern, name))
+            .map(|(_, property_type)| *property_type)
+    }
+
+    fn suggest(&self, name: &str) -> Option<&'static str> {
+        self.properties
+            .iter()
+            .map(|(pattern, _)| *pattern)
+            .filter(|pattern| !pattern.contains('*'))
+            .map(|pattern| (edit_distance(pattern, name), pattern))
+            .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, pattern)| pattern)
+    }
+}
+
+fn referenced_properties<'a>(expression: &'a Expression, names: &mut Vec<&'a str>) {
+    match expression {
+        Expression::Equals(prop_ref, _)
+        | Expression::Greater(prop_ref, _)
+        | Expression::GreaterEqual(prop_ref, _)
+        | Expression::Less(prop_ref, _)
+        | Expression::LessEqual(prop_ref, _)
+        | Expression::Regex(prop_ref, _)
+        | Expression::Substring(prop_ref, _)
+        | Expression::Present(prop_ref) => match prop_ref {
+            PropertyRef::Value(name, _) | PropertyRef::Aspect(name, _, _) => names.push(name),
+        },
+        Expression::Or(exprs) | Expression::And(exprs) => {
+            for expr in exprs {
+                referenced_properties(expr, names);
+            }
+        }
+        Expression::Not(expr) => referenced_properties(expr, names),
+        Expression::Empty(_) => {}
+    }
+}
+
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut pattern = pattern.split('.');
+    let mut name = name.split('.');
+    loop {
+        match (pattern.next(), name.next()) {
+            (None, None) => return true,
+            (Some("*"), Some(_)) => continue,
+            (Some(expected), Some(segment)) if expected == segment => continue,
+            _ => return false,
+        }
+    }
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut distances = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.bytes().enumerate() {
+        let mut prev_diagonal = distances[0];
+        distances[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev_diagonal + if ca == *cb { 0 } else { 1 };
+            prev_diagonal = distances[j + 1];
+            distances[j + 1] = substitution.min(distances[j] + 1).min(distances[j + 1] + 1);
+        }
+    }
+    distances[b.len()]
+}
+
+impl fmt::Display for PropertyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PropertyType::String => "string",
+            PropertyType::Number => "number",
+            PropertyType::Bool => "bool",
+            PropertyType::List => "list",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaViolation::UnknownProperty { name, suggestion } => {
+                write!(f, "Unknown property '{}'.", name)?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " Did you mean '{}'?", suggestion)?;
+                }
+                Ok(())
+            }
+            SchemaViolation::InvalidType { name, expected } => {
+                write!(f, "Property '{}' should be of type {}.", name, expected)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{PropertyType, SchemaVersion, SchemaViolation};
+
+    #[test]
+    fn valid_properties_and_constraints() {
+        let properties = json!({
+            "golem": {
+                "inf.mem.gib": 8,
+                "runtime.name": "vm",
+                "com.payment.platform.erc20-rinkeby-tglm.address": "0x123",
+                "com.payment.debit-notes.accept-timeout?": 240,
+                "custom.property": "anything",
+            },
+            "my.namespace": 1,
+        });
+        let constraints = "(&(golem.inf.cpu.threads>=4)(golem.srv.comp.expiration>0))";
+
+        let schema = SchemaVersion::V1.schema();
+        assert_eq!(schema.validate(&properties, constraints), vec![]);
+    }
+
+    #[test]
+    fn pricing_models_properties() {
+        let schema = SchemaVersion::V1.schema();
+        let offer = |model: &str, params: serde_json::Value| {
+            let mut properties = json!({
+                "golem.com.pricing.model": model,
+                "golem.com.usage.vector": ["golem.usage.duration_sec"],
+            });
+            properties[format!("golem.com.pricing.model.{}", model)] = params;
+            properties
+        };
+
+        let tiered = offer(
+            "tiered",
+            json!({"coeffs": [0.1, 0.0], "threshold-sec": 3600, "discount": 0.2}),
+        );
+        // Windows in the form produced by provider agent `PriceWindow` serialization.
+        let time_of_day = offer(
+            "time-of-day",
+            json!({
+                "coeffs": [0.1, 0.0],
+                "windows": [
+                    {"start-hour": 8, "end-hour": 12, "multiplier": 1.5},
+                    {"start-hour": 18, "end-hour": 22, "multiplier": 2.0},
+                ],
+            }),
+        );
+        let minimum_charge = offer(
+            "minimum-charge",
+            json!({"coeffs": [0.1, 0.0], "minimum": 0.5}),
+        );
+        for properties in vec![tiered, time_of_day, minimum_charge] {
+            assert_eq!(schema.validate(&properties, "()"), vec![]);
+        }
+    }
+
+    #[test]
+    fn slot_property() {
+        let schema = SchemaVersion::V1.schema();
+        let properties = json!({"golem.inf.slot": "small", "golem.inf.cpu.threads": 2});
+        assert_eq!(
+            schema.validate(&properties, "(golem.inf.slot=small)"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn unknown_properties_and_invalid_types() {
+        let properties = json!({
+            "golem": {
+                "inf.mem.gb": 8,
+                "inf.storage.gib": "10GiB",
+            },
+        });
+        let constraints = "(&(golem.inf.cpu.thread>=4)(golem.inf.mem.gb>=8)(golem.inf.xyz=1))";
+
+        let violations = SchemaVersion::V1
+            .schema()
+            .validate(&properties, constraints);
+        assert_eq!(
+            violations,
+            vec![
+                SchemaViolation::UnknownProperty {
+                    name: "golem.inf.mem.gb".to_string(),
+                    suggestion: Some("golem.inf.mem.gib"),
+                },
+                SchemaViolation::InvalidType {
+                    name: "golem.inf.storage.gib".to_string(),
+                    expected: PropertyType::Number,
+                },
+                SchemaViolation::UnknownProperty {
+                    name: "golem.inf.cpu.thread".to_string(),
+                    suggestion: Some("golem.inf.cpu.threads"),
+                },
+                SchemaViolation::UnknownProperty {
+                    name: "golem.inf.xyz".to_string(),
+                    suggestion: None,
+                },
+            ]
+        );
+        assert_eq!(
+            violations[0].to_string(),
+            "Unknown property 'golem.inf.mem.gb'. Did you mean 'golem.inf.mem.gib'?"
+        );
+    }
+}
@@ -1,29 +1,59 @@
 use chrono::{NaiveDateTime, Utc};
+use metrics::counter;
 use std::collections::HashSet;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use ya_client::model::market::{Demand as ClientDemand, NewDemand, NewOffer, Offer as ClientOffer};
 use ya_client::model::NodeId;
+use ya_market_resolver::ConstraintsFilter;
 use ya_service_api_web::middleware::Identity;
 
 use crate::config::Config;
 use crate::db::dao::*;
-use crate::db::model::{Demand, Offer, SubscriptionId};
+use crate::db::model::{
+    BlockedNode, Demand, Offer, OfferCursor, OfferSortKey, OffersPage, SubscriptionId,
+};
 use crate::db::DbMixedExecutor;
 use crate::matcher::error::{
-    DemandError, ModifyOfferError, QueryDemandsError, QueryOfferError, QueryOffersError,
-    SaveOfferError,
+    BlocklistError, DemandError, ModifyOfferError, QueryDemandsError, QueryOfferError,
+    QueryOffersError, SaveOfferError,
 };
+use crate::matcher::index::OfferIndex;
+use crate::utils::display::EnableDisplay;
+
+/// Max number of ids in single query. Must be lower than SQLITE_MAX_VARIABLE_NUMBER.
+const IDS_QUERY_CHUNK: usize = 500;
 
 #[derive(Clone)]
 pub struct SubscriptionStore {
     pub(crate) db: DbMixedExecutor,
     config: Arc<Config>,
+    index: Arc<RwLock<OfferIndex>>,
 }
 
 impl SubscriptionStore {
     pub fn new(db: DbMixedExecutor, config: Arc<Config>) -> Self {
-        SubscriptionStore { db, config }
+        let index = Arc::new(RwLock::new(OfferIndex::new(&config.index)));
+        SubscriptionStore { db, config, index }
+    }
+
+    /// Index lives only in memory, so it has to be filled with Offers
+    /// already stored in database, before Resolver starts using it.
+    pub async fn rebuild_index(&self) -> Result<(), QueryOffersError> {
+        if !self.index.read().unwrap().is_enabled() {
+            return Ok(());
+        }
+
+        let offers = self
+            .db
+            .as_dao::<OfferDao>()
+            .get_offers(None, None, None, Utc::now().naive_utc())
+            .await?;
+
+        let mut index = self.index.write().unwrap();
+        offers.iter().for_each(|offer| index.insert(offer));
+        log::debug!("Offers index rebuilt with {} active Offers.", offers.len());
+        Ok(())
     }
 
     /// returns newly created offer with insertion_ts
@@ -36,32 +66,46 @@ impl SubscriptionStore {
         // TODO: provider agent should set expiration.
         let expiration_ts = creation_ts + self.config.subscription.default_ttl;
         let offer = Offer::from_new(offer, id, creation_ts, expiration_ts)?;
-        self.insert_offer(offer).await
+        // Offers subscribed locally are not limited.
+        self.insert_offer(offer, 0).await
     }
 
     /// returns saved offer with insertion_ts
     pub async fn save_offer(&self, offer: Offer) -> Result<Offer, SaveOfferError> {
         offer.validate()?;
-        self.insert_offer(offer).await
+        // Single node shouldn't be able to flood our database with Offers.
+        self.insert_offer(offer, self.config.discovery.max_offers_per_node)
+            .await
     }
 
-    async fn insert_offer(&self, mut offer: Offer) -> Result<Offer, SaveOfferError> {
+    /// Inserts Offer, if its node has less than `quota` active Offers. 0 disables limit.
+    async fn insert_offer(&self, mut offer: Offer, quota: u32) -> Result<Offer, SaveOfferError> {
         // Insertions timestamp should always reference our local time
         // of adding it to database, so we must reset it here.
         offer.insertion_ts = None;
         let id = offer.id.clone();
+        let node_id = offer.node_id;
 
-        match self
-            .db
-            .as_dao::<OfferDao>()
-            .put(offer, Utc::now().naive_utc())
-            .await
-        {
-            Ok((true, OfferState::Active(offer))) => Ok(offer),
-            Ok((false, OfferState::Active(_))) => Err(SaveOfferError::Exists(id)),
-            Ok((false, OfferState::Unsubscribed(_))) => Err(SaveOfferError::Unsubscribed(id)),
-            Ok((_, OfferState::Expired(_))) => Err(SaveOfferError::Expired(id)),
-            Ok((inserted, state)) => Err(SaveOfferError::WrongState {
+        let dao = self.db.as_dao::<OfferDao>();
+        let now = Utc::now().naive_utc();
+        let result = match quota {
+            0 => dao.put(offer, now).await.map(Some),
+            quota => dao.put_within_quota(offer, now, quota).await,
+        };
+
+        match result {
+            Ok(None) => {
+                counter!("market.offers.incoming.quota_exceeded", 1);
+                Err(SaveOfferError::QuotaExceeded(node_id, quota))
+            }
+            Ok(Some((true, OfferState::Active(offer)))) => {
+                self.index.write().unwrap().insert(&offer);
+                Ok(offer)
+            }
+            Ok(Some((false, OfferState::Active(_)))) => Err(SaveOfferError::Exists(id)),
+            Ok(Some((false, OfferState::Unsubscribed(_)))) => Err(SaveOfferError::Unsubscribed(id)),
+            Ok(Some((_, OfferState::Expired(_)))) => Err(SaveOfferError::Expired(id)),
+            Ok(Some((inserted, state))) => Err(SaveOfferError::WrongState {
                 state: state.to_string(),
                 inserted,
                 id,
@@ -118,6 +162,79 @@ impl SubscriptionStore {
             .collect())
     }
 
+    /// Returns single page of active Offers with properties matching `constraints`.
+    /// Offers are sorted by `sort_by` key. Page starts after position given by `cursor`.
+    pub async fn query_offers(
+        &self,
+        constraints: Option<&str>,
+        sort_by: OfferSortKey,
+        cursor: Option<&str>,
+        limit: Option<i32>,
+    ) -> Result<OffersPage, QueryOffersError> {
+        let limit = limit.unwrap_or(self.config.query.offers_limit_default);
+        if limit <= 0 || limit > self.config.query.offers_limit_max {
+            return Err(QueryOffersError::InvalidLimit(
+                limit,
+                self.config.query.offers_limit_max,
+            ));
+        }
+        let limit = limit as usize;
+
+        let filter = constraints.map(ConstraintsFilter::new).transpose()?;
+        let cursor = cursor.map(str::parse::<OfferCursor>).transpose()?;
+
+        // Constraints can't be checked by database, so we read Offers in batches
+        // until page is filled. We need one Offer more than limit to check,
+        // if next page exists.
+        let batch_size = limit + 1;
+        let now = Utc::now().naive_utc();
+        let mut offers = vec![];
+        let mut position = cursor;
+        loop {
+            let batch = self
+                .db
+                .as_dao::<OfferDao>()
+                .get_offers_sorted(sort_by, position, batch_size as i64, now)
+                .await?;
+            let exhausted = batch.len() < batch_size;
+            position = batch.last().map(|offer| OfferCursor::new(offer, sort_by));
+
+            offers.extend(batch.into_iter().filter(|offer| match &filter {
+                None => true,
+                Some(filter) => filter.matches(&offer.properties).unwrap_or_else(|e| {
+                    log::warn!("Skipping Offer [{}] in query. Error: {}", offer.id, e);
+                    false
+                }),
+            }));
+            if exhausted || offers.len() > limit {
+                break;
+            }
+        }
+
+        let next_cursor = if offers.len() > limit {
+            offers.truncate(limit);
+            offers
+                .last()
+                .map(|offer| OfferCursor::new(offer, sort_by).to_string())
+        } else {
+            None
+        };
+
+        Ok(OffersPage {
+            offers: offers
+                .into_iter()
+                .filter_map(|o| match o.into_client_offer() {
+                    Err(e) => {
+                        log::error!("Skipping Offer because of: {}", e);
+                        None
+                    }
+                    Ok(o) => Some(o),
+                })
+                .collect(),
+            next_cursor,
+        })
+    }
+
     pub async fn get_offers(
         &self,
         ids: Vec<SubscriptionId>,
@@ -140,6 +257,46 @@ impl SubscriptionStore {
             .map_err(QueryOffersError::from)
     }
 
+    /// Returns Offers inserted before given timestamp, that can possibly match Demand.
+    /// Uses Offers index to narrow down the set of Offers, if Demand constraints allow it.
+    /// Otherwise behaves like `get_offers_before`.
+    pub async fn get_offer_candidates_before(
+        &self,
+        demand: &Demand,
+        inserted_before_ts: NaiveDateTime,
+    ) -> Result<Vec<Offer>, QueryOffersError> {
+        // Lock guard can't be held across await point.
+        let candidates = self.index.read().unwrap().candidates(&demand.constraints);
+        let candidates = match candidates {
+            Some(candidates) => candidates.into_iter().collect::<Vec<_>>(),
+            None => return self.get_offers_before(inserted_before_ts).await,
+        };
+
+        let mut offers = vec![];
+        for ids in candidates.chunks(IDS_QUERY_CHUNK) {
+            offers.extend(self.get_offers(ids.to_vec()).await?);
+        }
+
+        // Offers, that weren't returned, are expired, unsubscribed or removed,
+        // so we don't need to keep them in index anymore.
+        if offers.len() < candidates.len() {
+            let active = offers.iter().map(|offer| &offer.id).collect::<HashSet<_>>();
+            let mut index = self.index.write().unwrap();
+            candidates
+                .iter()
+                .filter(|id| !active.contains(id))
+                .for_each(|id| index.remove(id));
+        }
+
+        offers.retain(|offer| {
+            offer
+                .insertion_ts
+                .map_or(false, |ts| ts <= inserted_before_ts)
+        });
+        offers.sort_by_key(|offer| offer.creation_ts);
+        Ok(offers)
+    }
+
     /// Returns Offers SubscriptionId from vector, that don't exist in our database.
     pub async fn filter_out_known_offer_ids(
         &self,
@@ -222,6 +379,8 @@ impl SubscriptionStore {
             return Ok(());
         }
 
+        self.index.write().unwrap().remove(offer_id);
+
         log::debug!("Removing not owned unsubscribed Offer [{}].", offer_id);
         match self.db.as_dao::<OfferDao>().delete(offer_id).await {
             Ok(true) => Ok(()),
@@ -308,4 +467,48 @@ impl SubscriptionStore {
             false => Err(DemandError::NotFound(demand_id.clone())),
         }
     }
+
+    pub async fn block_node(
+        &self,
+        node_id: NodeId,
+        reason: Option<String>,
+    ) -> Result<BlockedNode, BlocklistError> {
+        let node = self
+            .db
+            .as_dao::<BlockedNodeDao>()
+            .block(BlockedNode::new(node_id, reason))
+            .await?;
+        log::info!(
+            "Node [{}] blocked. Reason: {}",
+            node_id,
+            node.reason.display()
+        );
+        Ok(node)
+    }
+
+    pub async fn unblock_node(&self, node_id: NodeId) -> Result<(), BlocklistError> {
+        match self.db.as_dao::<BlockedNodeDao>().unblock(node_id).await? {
+            true => {
+                log::info!("Node [{}] unblocked.", node_id);
+                Ok(())
+            }
+            false => Err(BlocklistError::NotBlocked(node_id)),
+        }
+    }
+
+    pub async fn list_blocked_nodes(&self) -> Result<Vec<BlockedNode>, BlocklistError> {
+        Ok(self.db.as_dao::<BlockedNodeDao>().list().await?)
+    }
+
+    pub async fn blocked_node_ids(&self) -> Result<HashSet<NodeId>, BlocklistError> {
+        Ok(self.db.as_dao::<BlockedNodeDao>().get_ids().await?)
+    }
+
+    pub async fn is_node_blocked(&self, node_id: NodeId) -> Result<bool, BlocklistError> {
+        Ok(self
+            .db
+            .as_dao::<BlockedNodeDao>()
+            .is_blocked(node_id)
+            .await?)
+    }
 }
@@ -3,7 +3,9 @@ pub mod error;
 mod notifier;
 mod provider;
 mod requestor;
+pub mod scoring;
 
+pub(crate) use common::termination_reason_code;
 pub use notifier::EventNotifier;
 pub use provider::{ApprovalResult, ProviderBroker};
-pub use requestor::{ApprovalStatus, RequestorBroker};
+pub use requestor::{ApprovalStatus, BulkAgreementOutcome, RequestorBroker};
@@ -1,5 +1,8 @@
 use chrono::{DateTime, Utc};
+use futures::stream::{self, LocalBoxStream, Stream, StreamExt};
 use metrics::counter;
+use std::collections::VecDeque;
+use std::fmt::Debug;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -13,12 +16,12 @@ use crate::config::Config;
 use crate::db::model::check_transition;
 use crate::db::{
     dao::{
-        AgreementDao, AgreementEventsDao, NegotiationEventsDao, ProposalDao, SaveProposalError,
-        TakeEventsError,
+        AgreementAmendmentDao, AgreementDao, AgreementEventsDao, AmendmentDaoError,
+        NegotiationEventsDao, ProposalDao, SaveProposalError, TakeEventsError,
     },
     model::{
-        Agreement, AgreementEvent, AgreementId, AgreementState, AppSessionId, MarketEvent, Owner,
-        Proposal, ProposalId, ProposalState, SubscriptionId,
+        Agreement, AgreementAmendment, AgreementEvent, AgreementId, AgreementState, AppSessionId,
+        MarketEvent, Owner, Proposal, ProposalId, ProposalState, SubscriptionId,
     },
     DbMixedExecutor,
 };
@@ -31,10 +34,11 @@ use crate::negotiation::error::RegenerateProposalError;
 use crate::negotiation::error::{NegotiationError, ProposalValidationError};
 use crate::negotiation::{
     error::{
-        AgreementError, AgreementEventsError, GetProposalError, MatchValidationError,
-        ProposalError, QueryEventsError,
+        AgreementError, AgreementEventsError, AmendmentError, GetProposalError,
+        MatchValidationError, ProposalError, QueryEventsError,
     },
-    notifier::NotifierError,
+    notifier::{EventNotifierListener, NotifierError},
+    scoring::ProposalScorer,
     EventNotifier,
 };
 use crate::protocol::negotiation::error::{CallerParseError, RejectProposalError};
@@ -42,15 +46,27 @@ use crate::protocol::negotiation::messages::ProposalRejected;
 use crate::protocol::negotiation::{
     common as protocol_common,
     error::{
-        CounterProposalError, RemoteAgreementError, RemoteProposalError, TerminateAgreementError,
+        CounterProposalError, RemoteAgreementError, RemoteAmendmentError, RemoteProposalError,
+        TerminateAgreementError,
     },
     messages::{AgreementTerminated, ProposalReceived},
 };
-use crate::utils::display::EnableDisplay;
+use crate::utils::display::{DisplayEnabler, EnableDisplay};
 use crate::utils::AgreementLock;
 
 type IsFirst = bool;
 
+/// State of events stream between consecutive polls.
+struct EventStreamState<Type>
+where
+    Type: Debug + PartialEq + Clone + EnableDisplay<Type> + 'static,
+    for<'a> DisplayEnabler<'a, Type>: std::fmt::Display,
+{
+    broker: CommonBroker,
+    id: Type,
+    listener: EventNotifierListener<Type>,
+}
+
 #[derive(Clone)]
 pub struct CommonBroker {
     pub(super) db: DbMixedExecutor,
@@ -60,6 +76,8 @@ pub struct CommonBroker {
     pub(super) agreement_notifier: EventNotifier<AgreementId>,
     pub(super) config: Arc<Config>,
     pub(super) agreement_lock: AgreementLock,
+    /// Set only on Requestor side.
+    pub(super) scorer: Option<Arc<dyn ProposalScorer>>,
 }
 
 impl CommonBroker {
@@ -77,6 +95,7 @@ impl CommonBroker {
             agreement_notifier: EventNotifier::default(),
             config,
             agreement_lock: AgreementLock::new(),
+            scorer: None,
         }
     }
 
@@ -177,6 +196,7 @@ impl CommonBroker {
         timeout: f32,
         max_events: Option<i32>,
         owner: Owner,
+        order_by_score: bool,
     ) -> Result<Vec<MarketEvent>, QueryEventsError> {
         let mut timeout = Duration::from_secs_f32(timeout.max(0.0));
         let stop_time = Instant::now() + timeout;
@@ -194,7 +214,7 @@ impl CommonBroker {
             let events = self
                 .db
                 .as_dao::<NegotiationEventsDao>()
-                .take_events(subscription_id, max_events, owner)
+                .take_events(subscription_id, max_events, owner, order_by_score)
                 .await?;
 
             if !events.is_empty() {
@@ -229,6 +249,33 @@ impl CommonBroker {
         max_events: Option<i32>,
         after_timestamp: DateTime<Utc>,
         id: &Identity,
+    ) -> Result<Vec<AgreementEvent>, AgreementEventsError> {
+        self.query_session_events(session_id, timeout, max_events, after_timestamp, id, false)
+            .await
+    }
+
+    /// Waits for amendment events the same way, as `query_agreement_events`
+    /// waits for Agreement state changes.
+    pub async fn query_amendment_events(
+        &self,
+        session_id: &AppSessionId,
+        timeout: f32,
+        max_events: Option<i32>,
+        after_timestamp: DateTime<Utc>,
+        id: &Identity,
+    ) -> Result<Vec<AgreementEvent>, AgreementEventsError> {
+        self.query_session_events(session_id, timeout, max_events, after_timestamp, id, true)
+            .await
+    }
+
+    async fn query_session_events(
+        &self,
+        session_id: &AppSessionId,
+        timeout: f32,
+        max_events: Option<i32>,
+        after_timestamp: DateTime<Utc>,
+        id: &Identity,
+        amendments: bool,
     ) -> Result<Vec<AgreementEvent>, AgreementEventsError> {
         let mut timeout = Duration::from_secs_f32(timeout.max(0.0));
         let stop_time = Instant::now() + timeout;
@@ -243,17 +290,24 @@ impl CommonBroker {
 
         let mut agreement_notifier = self.session_notifier.listen(session_id);
         loop {
-            let events = self
-                .db
-                .as_dao::<AgreementEventsDao>()
-                .select(
-                    &id.identity,
-                    session_id,
-                    max_events,
-                    after_timestamp.naive_utc(),
-                )
-                .await
-                .map_err(|e| AgreementEventsError::Internal(e.to_string()))?;
+            let dao = self.db.as_dao::<AgreementEventsDao>();
+            let after_timestamp = after_timestamp.naive_utc();
+            let events = match amendments {
+                true => {
+                    dao.select_amendment_events(
+                        &id.identity,
+                        session_id,
+                        max_events,
+                        after_timestamp,
+                    )
+                    .await
+                }
+                false => {
+                    dao.select(&id.identity, session_id, max_events, after_timestamp)
+                        .await
+                }
+            }
+            .map_err(|e| AgreementEventsError::Internal(e.to_string()))?;
 
             if !events.is_empty() {
                 counter!("market.agreements.events.queried", events.len() as u64);
@@ -288,6 +342,125 @@ impl CommonBroker {
         }
     }
 
+    /// Streams events for subscription as soon, as they are produced. Streamed events
+    /// are removed from queue, the same as events returned by `query_events`.
+    /// Events are taken one at a time, when stream is polled, so events won't be
+    /// lost, if stream is dropped. Stream ends, when subscription is unsubscribed.
+    pub async fn stream_events(
+        &self,
+        subscription_id: &SubscriptionId,
+        owner: Owner,
+    ) -> Result<LocalBoxStream<'static, Result<MarketEvent, QueryEventsError>>, QueryEventsError>
+    {
+        // Listen before taking first events, so we won't miss any notification.
+        let listener = self.negotiation_notifier.listen(subscription_id);
+        self.db
+            .as_dao::<NegotiationEventsDao>()
+            .check_subscription(subscription_id, owner)
+            .await?;
+
+        let state = EventStreamState {
+            broker: self.clone(),
+            id: subscription_id.clone(),
+            listener,
+        };
+        Ok(stream::unfold(Some(state), move |state| async move {
+            let mut state = state?;
+            loop {
+                match state
+                    .broker
+                    .db
+                    .as_dao::<NegotiationEventsDao>()
+                    .take_events(&state.id, 1, owner, false)
+                    .await
+                {
+                    Ok(events) => {
+                        if let Some(event) = events.into_iter().next() {
+                            return Some((Ok(event), Some(state)));
+                        }
+                    }
+                    Err(e) => return Some((Err(e.into()), None)),
+                }
+
+                // Expiration of subscription isn't notified, so we check queue periodically.
+                let keepalive = state.broker.config.events.stream_keepalive_interval;
+                match state.listener.wait_for_event_with_timeout(keepalive).await {
+                    Ok(_) | Err(NotifierError::Timeout(_)) => (),
+                    Err(NotifierError::Unsubscribed(_)) => return None,
+                    Err(e) => return Some((Err(QueryEventsError::Internal(e.to_string())), None)),
+                }
+            }
+        })
+        .boxed_local())
+    }
+
+    /// Streams Agreement events for `session_id` after `after_timestamp`,
+    /// as soon as they are produced.
+    pub fn stream_agreement_events(
+        &self,
+        session_id: &AppSessionId,
+        after_timestamp: DateTime<Utc>,
+        id: &Identity,
+    ) -> impl Stream<Item = Result<AgreementEvent, AgreementEventsError>> {
+        let listener = self.session_notifier.listen(session_id);
+        let state = EventStreamState {
+            broker: self.clone(),
+            id: session_id.clone(),
+            listener,
+        };
+        let node_id = id.identity;
+        let cursor = (after_timestamp.naive_utc(), false);
+        // Events already fetched from database, but not yet returned from stream.
+        let pending = VecDeque::<AgreementEvent>::new();
+
+        stream::unfold(Some((state, pending, cursor)), move |state| async move {
+            let (mut state, mut pending, (mut after_timestamp, mut wait)) = state?;
+            loop {
+                if let Some(event) = pending.pop_front() {
+                    after_timestamp = event.timestamp;
+                    counter!("market.agreements.events.queried", 1);
+                    let next = (state, pending, (after_timestamp, wait));
+                    return Some((Ok(event), Some(next)));
+                }
+
+                if wait {
+                    let keepalive = state.broker.config.events.stream_keepalive_interval;
+                    match state.listener.wait_for_event_with_timeout(keepalive).await {
+                        Ok(_) | Err(NotifierError::Timeout(_)) => (),
+                        Err(e) => {
+                            let e = AgreementEventsError::Internal(e.to_string());
+                            return Some((Err(e), None));
+                        }
+                    }
+                }
+
+                match state
+                    .broker
+                    .db
+                    .as_dao::<AgreementEventsDao>()
+                    .select(
+                        &node_id,
+                        &state.id,
+                        state.broker.config.events.max_events_max,
+                        after_timestamp,
+                    )
+                    .await
+                {
+                    Ok(events) => {
+                        // Non empty result means, that there can be more events in database,
+                        // so we shouldn't wait for notification before next query.
+                        wait = events.is_empty();
+                        pending.extend(events)
+                    }
+                    Err(e) => {
+                        let e = AgreementEventsError::Internal(e.to_string());
+                        return Some((Err(e), None));
+                    }
+                }
+            }
+        })
+    }
+
     pub async fn get_proposal(
         &self,
         subs_id: Option<&SubscriptionId>,
@@ -333,6 +506,86 @@ impl CommonBroker {
             })
     }
 
+    /// Gets Agreement, that can be amended by us. Agreement must be owned by
+    /// given side, otherwise we pretend, that it doesn't exist.
+    pub async fn get_amended_agreement(
+        &self,
+        id: &Identity,
+        client_agreement_id: &str,
+        owner: Owner,
+    ) -> Result<Agreement, AmendmentError> {
+        let agreement = self
+            .db
+            .as_dao::<AgreementDao>()
+            .select_by_node(client_agreement_id, id.identity, Utc::now().naive_utc())
+            .await
+            .map_err(|e| AmendmentError::Get(client_agreement_id.to_string(), e))?
+            .filter(|agreement| agreement.id.owner() == owner)
+            .ok_or_else(|| AmendmentError::AgreementNotFound(client_agreement_id.to_string()))?;
+
+        if agreement.state != AgreementState::Approved {
+            return Err(AmendmentError::InvalidState(agreement.id, agreement.state));
+        }
+        Ok(agreement)
+    }
+
+    /// Gets Agreement amended by other party. Caller must be the other side
+    /// of Agreement, otherwise we pretend, that it doesn't exist.
+    pub async fn get_remote_amended_agreement(
+        &self,
+        agreement_id: &AgreementId,
+        caller_id: NodeId,
+        caller_role: Owner,
+    ) -> Result<Agreement, RemoteAmendmentError> {
+        let agreement = self
+            .db
+            .as_dao::<AgreementDao>()
+            .select(agreement_id, None, Utc::now().naive_utc())
+            .await
+            .map_err(|_e| RemoteAmendmentError::AgreementNotFound(agreement_id.clone()))?
+            .ok_or_else(|| RemoteAmendmentError::AgreementNotFound(agreement_id.clone()))?;
+
+        let auth_id = match caller_role {
+            Owner::Provider => agreement.provider_id,
+            Owner::Requestor => agreement.requestor_id,
+        };
+
+        if auth_id != caller_id {
+            // Don't reveal, that we know this Agreement id.
+            Err(RemoteAmendmentError::AgreementNotFound(
+                agreement_id.clone(),
+            ))?
+        }
+
+        if agreement.state != AgreementState::Approved {
+            return Err(RemoteAmendmentError::InvalidState(
+                agreement.id,
+                agreement.state,
+            ));
+        }
+        Ok(agreement)
+    }
+
+    pub async fn list_amendments(
+        &self,
+        id: &Identity,
+        client_agreement_id: &str,
+    ) -> Result<Vec<AgreementAmendment>, AmendmentError> {
+        let agreement = self
+            .db
+            .as_dao::<AgreementDao>()
+            .select_by_node(client_agreement_id, id.identity, Utc::now().naive_utc())
+            .await
+            .map_err(|e| AmendmentError::Get(client_agreement_id.to_string(), e))?
+            .ok_or_else(|| AmendmentError::AgreementNotFound(client_agreement_id.to_string()))?;
+
+        Ok(self
+            .db
+            .as_dao::<AgreementAmendmentDao>()
+            .list(&agreement.id)
+            .await?)
+    }
+
     // Called locally via REST
     pub async fn terminate_agreement(
         &self,
@@ -545,8 +798,9 @@ impl CommonBroker {
             .get_proposal(None, &msg.prev_proposal_id)
             .await
             .map_err(|_e| RemoteProposalError::NotFound(msg.prev_proposal_id.clone()))?;
-        let proposal = prev_proposal.from_draft(msg.proposal);
+        let mut proposal = prev_proposal.from_draft(msg.proposal);
         proposal.validate_id()?;
+        self.score_proposal(&mut proposal);
 
         self.validate_proposal(&prev_proposal, &caller_id, caller_role)
             .await?;
@@ -733,7 +987,8 @@ impl CommonBroker {
         let notifier = self.negotiation_notifier.clone();
 
         // Add proposal to database together with Negotiation record.
-        let proposal = Proposal::new_requestor(proposal.demand, proposal.offer);
+        let mut proposal = Proposal::new_requestor(proposal.demand, proposal.offer);
+        self.score_proposal(&mut proposal);
         let proposal = db
             .as_dao::<ProposalDao>()
             .save_initial_proposal(proposal)
@@ -758,6 +1013,13 @@ impl CommonBroker {
         Ok(())
     }
 
+    fn score_proposal(&self, proposal: &mut Proposal) {
+        proposal.body.score = self
+            .scorer
+            .as_ref()
+            .and_then(|scorer| scorer.score(&proposal.body.properties));
+    }
+
     pub async fn regenerate_proposal(
         &self,
         agreement: &Agreement,
@@ -813,6 +1075,24 @@ pub fn validate_transition(
         .map_err(|e| AgreementError::UpdateState(agreement.id.clone(), e))
 }
 
+/// Translates local error to error, that can be sent to other party
+/// without revealing our internal details.
+pub fn remote_amendment_error(amendment_id: &str, e: AmendmentDaoError) -> RemoteAmendmentError {
+    match e {
+        AmendmentDaoError::NotFound(id) => RemoteAmendmentError::NotFound(id),
+        AmendmentDaoError::NotPending(id, state) => {
+            RemoteAmendmentError::AlreadyResolved(id, state)
+        }
+        AmendmentDaoError::InvalidAgreementState(id, state) => {
+            RemoteAmendmentError::InvalidState(id, state)
+        }
+        AmendmentDaoError::Properties(..) | AmendmentDaoError::DbError(_) => {
+            log::warn!("Failed to process amendment [{}]. {}", amendment_id, e);
+            RemoteAmendmentError::InternalError(amendment_id.to_string())
+        }
+    }
+}
+
 fn get_reason_code(reason: &Option<Reason>, key: &str) -> Option<String> {
     reason
         .as_ref()
@@ -825,6 +1105,17 @@ fn get_reason_code(reason: &Option<Reason>, key: &str) -> Option<String> {
         .flatten()
 }
 
+/// Extracts termination reason code set by Provider or Requestor agent.
+/// Returns `NotSpecified`, if Reason doesn't contain exactly one of codes.
+pub(crate) fn termination_reason_code(reason: &Option<Reason>) -> String {
+    let p_code = get_reason_code(reason, "golem.provider.code");
+    let r_code = get_reason_code(reason, "golem.requestor.code");
+
+    r_code
+        .xor(p_code)
+        .unwrap_or_else(|| "NotSpecified".to_string())
+}
+
 /// This function extract from Reason additional information about termination reason
 /// and increments metric counter. Note that Reason isn't required to have any fields
 /// despite 'message'.
@@ -834,12 +1125,7 @@ pub fn inc_terminate_metrics(reason: &Option<Reason>, owner: Owner) {
         Owner::Requestor => counter!("market.agreements.requestor.terminated", 1),
     };
 
-    let p_code = get_reason_code(reason, "golem.provider.code");
-    let r_code = get_reason_code(reason, "golem.requestor.code");
-
-    let reason_code = r_code
-        .xor(p_code)
-        .unwrap_or_else(|| "NotSpecified".to_string());
+    let reason_code = termination_reason_code(reason);
     match owner {
         Owner::Provider => {
             counter!("market.agreements.provider.terminated.reason", 1, "reason" => reason_code)
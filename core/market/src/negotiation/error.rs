@@ -1,11 +1,13 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use ya_client::model::NodeId;
 
-use crate::db::dao::AgreementDaoError;
+use crate::db::dao::{AgreementDaoError, AmendmentDaoError};
 use crate::db::model::{
-    AgreementId, ProposalId, ProposalIdParseError, SubscriptionId, SubscriptionParseError,
+    AgreementId, AgreementState, AmendmentState, ProposalId, ProposalIdParseError, SubscriptionId,
+    SubscriptionParseError,
 };
 use crate::db::{
     dao::TakeEventsError,
@@ -13,18 +15,23 @@ use crate::db::{
     DbError,
 };
 use crate::matcher::error::{DemandError, QueryOfferError};
+use crate::negotiation::scoring::ScoringError;
 use crate::protocol::negotiation::error::{
-    AgreementProtocolError, CommitAgreementError, CounterProposalError as ProtocolProposalError,
-    GsbAgreementError, NegotiationApiInitError, ProposeAgreementError, RejectProposalError,
-    TerminateAgreementError,
+    AgreementProtocolError, AmendAgreementError, CommitAgreementError,
+    CounterProposalError as ProtocolProposalError, GsbAgreementError, NegotiationApiInitError,
+    ProposeAgreementError, RejectProposalError, TerminateAgreementError,
 };
 
 #[derive(Error, Debug)]
 pub enum NegotiationError {}
 
 #[derive(Error, Debug)]
-#[error("Failed to initialize Negotiation interface. Error: {0}.")]
-pub struct NegotiationInitError(#[from] NegotiationApiInitError);
+pub enum NegotiationInitError {
+    #[error("Failed to initialize Negotiation interface. Error: {0}.")]
+    Api(#[from] NegotiationApiInitError),
+    #[error("Failed to initialize Proposal scoring. Error: {0}")]
+    Scoring(#[from] ScoringError),
+}
 
 #[derive(Error, Debug, Serialize, Deserialize)]
 pub enum MatchValidationError {
@@ -88,6 +95,40 @@ pub enum AgreementError {
     Internal(String),
 }
 
+#[derive(Error, Debug)]
+pub enum BulkAgreementError {
+    #[error("Too many Proposals in single request: {0}, should be between 1 and {1}.")]
+    InvalidCount(usize, usize),
+    #[error("Proposal [{0}] appears more than once in request.")]
+    DuplicateProposal(ProposalId),
+}
+
+#[derive(Error, Debug)]
+pub enum AmendmentError {
+    #[error("Agreement [{0}] not found.")]
+    AgreementNotFound(String),
+    #[error("Amendment [{0}] not found.")]
+    NotFound(String),
+    #[error("Agreement [{0}] in state {1} can't be amended.")]
+    InvalidState(AgreementId, AgreementState),
+    #[error("Agreement [{0}] already has pending amendment [{1}].")]
+    PendingExists(AgreementId, String),
+    #[error("Amendment [{0}] is already {1}.")]
+    AlreadyResolved(String, AmendmentState),
+    #[error("Amendment validTo [{0}] should be in the future.")]
+    InvalidValidTo(DateTime<Utc>),
+    #[error("Invalid amendment properties. {0}")]
+    InvalidProperties(String),
+    #[error("Timeout while waiting for amendment [{0}] resolution.")]
+    Timeout(String),
+    #[error("Protocol error while amending: {0}")]
+    Protocol(#[from] AmendAgreementError),
+    #[error("Failed to get Agreement [{0}]. Error: {1}")]
+    Get(String, AgreementDaoError),
+    #[error("Amendment internal error: {0}")]
+    Internal(String),
+}
+
 #[derive(Error, Debug)]
 pub enum WaitForApprovalError {
     #[error("Agreement [{0}] not found.")]
@@ -150,6 +191,8 @@ pub enum ProposalValidationError {
     OwnProposal(ProposalId),
     #[error("Unauthorized operation attempt on Proposal [{0}] from [{1}].")]
     Unauthorized(ProposalId, NodeId),
+    #[error("Proposals from blocked node [{0}] are rejected.")]
+    Blocked(NodeId),
     #[error("Internal error processing Proposal: {0}.")]
     Internal(String),
 }
@@ -193,6 +236,27 @@ impl AgreementError {
     }
 }
 
+impl From<AmendmentDaoError> for AmendmentError {
+    fn from(e: AmendmentDaoError) -> Self {
+        match e {
+            AmendmentDaoError::NotFound(id) => AmendmentError::NotFound(id),
+            AmendmentDaoError::NotPending(id, state) => AmendmentError::AlreadyResolved(id, state),
+            AmendmentDaoError::InvalidAgreementState(id, state) => {
+                AmendmentError::InvalidState(id, state)
+            }
+            AmendmentDaoError::Properties(..) | AmendmentDaoError::DbError(_) => {
+                AmendmentError::Internal(e.to_string())
+            }
+        }
+    }
+}
+
+impl From<DbError> for AmendmentError {
+    fn from(e: DbError) -> Self {
+        AmendmentError::Internal(e.to_string())
+    }
+}
+
 impl From<MatchValidationError> for ProposalError {
     fn from(e: MatchValidationError) -> Self {
         ProposalValidationError::NotMatching(e).into()
@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 use std::time::{Duration, Instant};
 use thiserror::Error;
-use tokio::sync::broadcast::{channel, Receiver, Sender};
+use tokio::sync::broadcast::{channel, error::RecvError, Receiver, Sender};
 
 use crate::utils::display::{DisplayEnabler, EnableDisplay};
 
@@ -96,21 +96,26 @@ where
     for<'a> DisplayEnabler<'a, Type>: std::fmt::Display,
 {
     pub async fn wait_for_event(&mut self) -> Result<(), NotifierError<Type>> {
-        while let Ok(value) = self.receiver.recv().await {
-            match value {
-                Notification::<Type>::NewEvent(subscription_id) => {
+        loop {
+            match self.receiver.recv().await {
+                Ok(Notification::<Type>::NewEvent(subscription_id)) => {
                     if subscription_id == self.subscription_id {
                         return Ok(());
                     }
                 }
-                Notification::<Type>::StopEvents(subscription_id) => {
+                Ok(Notification::<Type>::StopEvents(subscription_id)) => {
                     if subscription_id == self.subscription_id {
                         return Err(NotifierError::Unsubscribed(subscription_id));
                     }
                 }
+                // Long living listeners (event streams) can miss notifications under load.
+                // We don't know, if any of them was ours, so caller should check for events.
+                Err(RecvError::Lagged(_)) => return Ok(()),
+                Err(RecvError::Closed) => {
+                    return Err(NotifierError::ChannelClosed(self.subscription_id.clone()))
+                }
             }
         }
-        Err(NotifierError::ChannelClosed(self.subscription_id.clone()))
     }
 
     pub async fn wait_for_event_with_timeout(
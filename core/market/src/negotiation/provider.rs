@@ -1,5 +1,5 @@
 use chrono::Utc;
-use futures::stream::StreamExt;
+use futures::stream::{LocalBoxStream, StreamExt};
 use metrics::counter;
 use std::sync::Arc;
 use std::time::Instant;
@@ -10,8 +10,10 @@ use ya_service_api_web::middleware::Identity;
 use ya_std_utils::LogErr;
 
 use crate::db::{
-    dao::{AgreementDao, NegotiationEventsDao, ProposalDao, SaveAgreementError},
-    model::{Agreement, AgreementId, AgreementState, AppSessionId},
+    dao::SaveAgreementError,
+    dao::{AgreementAmendmentDao, AgreementDao, NegotiationEventsDao, ProposalDao},
+    model::AppSessionId,
+    model::{Agreement, AgreementAmendment, AgreementId, AgreementState, AmendmentState},
     model::{Issuer, Offer, Owner, Proposal, ProposalId, SubscriptionId},
     DbMixedExecutor,
 };
@@ -23,7 +25,7 @@ use super::error::*;
 use super::notifier::EventNotifier;
 use crate::config::Config;
 use crate::db::dao::AgreementDaoError;
-use crate::negotiation::common::validate_transition;
+use crate::negotiation::common::{remote_amendment_error, validate_transition};
 use crate::negotiation::notifier::NotifierError;
 use crate::utils::display::EnableDisplay;
 
@@ -58,6 +60,7 @@ impl ProviderBroker {
         let broker_proposal_reject = broker.clone();
         let broker_terminated = broker.clone();
         let commit_broker = broker.clone();
+        let amendment_broker = broker.clone();
 
         let api = NegotiationApi::new(
             move |caller: String, msg: InitialProposalReceived| {
@@ -87,6 +90,9 @@ impl ProviderBroker {
             move |caller: String, msg: AgreementCommitted| {
                 on_agreement_committed(commit_broker.clone(), caller, msg)
             },
+            move |caller: String, msg: AmendmentProposed| {
+                on_amendment_proposed(amendment_broker.clone(), caller, msg)
+            },
         );
 
         // Initialize counters to 0 value. Otherwise they won't appear on metrics endpoint
@@ -100,6 +106,9 @@ impl ProviderBroker {
         counter!("market.agreements.provider.committing", 0);
         counter!("market.agreements.provider.rejected", 0);
         counter!("market.agreements.provider.cancelled", 0);
+        counter!("market.agreements.provider.amendment.received", 0);
+        counter!("market.agreements.provider.amendment.approved", 0);
+        counter!("market.agreements.provider.amendment.rejected", 0);
         counter!("market.events.provider.queried", 0);
         counter!("market.proposals.provider.countered", 0);
         counter!("market.proposals.provider.init-negotiation", 0);
@@ -199,7 +208,7 @@ impl ProviderBroker {
     ) -> Result<Vec<ProviderEvent>, QueryEventsError> {
         let events = self
             .common
-            .query_events(offer_id, timeout, max_events, Owner::Provider)
+            .query_events(offer_id, timeout, max_events, Owner::Provider, false)
             .await?;
 
         // Map model events to client RequestorEvent.
@@ -218,6 +227,37 @@ impl ProviderBroker {
         Ok(events)
     }
 
+    /// Streams events for Offer as soon, as they are produced.
+    pub async fn stream_events(
+        &self,
+        offer_id: &SubscriptionId,
+    ) -> Result<LocalBoxStream<'static, Result<ProviderEvent, QueryEventsError>>, QueryEventsError>
+    {
+        let db = self.common.db.clone();
+        let events = self.common.stream_events(offer_id, Owner::Provider).await?;
+
+        // Map model events to client ProviderEvent.
+        Ok(events
+            .then(move |result| {
+                let db = db.clone();
+                async move {
+                    match result {
+                        Ok(event) => event
+                            .into_client_provider_event(&db)
+                            .await
+                            .map_err(|e| {
+                                log::error!("Error converting event to client type: {}", e)
+                            })
+                            .ok()
+                            .map(Ok),
+                        Err(e) => Some(Err(e)),
+                    }
+                }
+            })
+            .filter_map(|event| async move { event })
+            .boxed_local())
+    }
+
     pub async fn approve_agreement(
         &self,
         id: Identity,
@@ -384,6 +424,112 @@ impl ProviderBroker {
         );
         Ok(())
     }
+
+    /// Accepts new terms proposed by Requestor. Amendment is applied in our
+    /// database first, so Requestor is notified only about terms, that we
+    /// were able to apply. Change is reverted, if Requestor can't be notified.
+    pub async fn approve_amendment(
+        &self,
+        id: &Identity,
+        client_agreement_id: &str,
+        amendment_id: &str,
+    ) -> Result<(), AmendmentError> {
+        let agreement = self
+            .common
+            .get_amended_agreement(id, client_agreement_id, Owner::Provider)
+            .await?;
+        let dao = self.common.db.as_dao::<AgreementAmendmentDao>();
+
+        let agreement = {
+            let _hold = self.common.agreement_lock.lock(&agreement.id).await;
+
+            get_pending_amendment(&dao, &agreement.id, amendment_id).await?;
+            let amended = dao.approve(&agreement.id, amendment_id).await?;
+
+            let timestamp = Utc::now().naive_utc();
+            if let Err(e) = self
+                .api
+                .approve_amendment(&agreement, amendment_id, timestamp)
+                .await
+            {
+                dao.revert_approval(&agreement, amendment_id)
+                    .await
+                    .log_err()
+                    .ok();
+                return Err(e.into());
+            }
+            amended
+        };
+
+        self.common.notify_agreement(&agreement).await;
+
+        counter!("market.agreements.provider.amendment.approved", 1);
+        log::info!(
+            "Provider {} approved amendment [{}] of Agreement [{}].",
+            id.display(),
+            amendment_id,
+            &agreement.id,
+        );
+        Ok(())
+    }
+
+    pub async fn reject_amendment(
+        &self,
+        id: &Identity,
+        client_agreement_id: &str,
+        amendment_id: &str,
+        reason: Option<Reason>,
+    ) -> Result<(), AmendmentError> {
+        let agreement = self
+            .common
+            .get_amended_agreement(id, client_agreement_id, Owner::Provider)
+            .await?;
+        let dao = self.common.db.as_dao::<AgreementAmendmentDao>();
+
+        {
+            let _hold = self.common.agreement_lock.lock(&agreement.id).await;
+
+            get_pending_amendment(&dao, &agreement.id, amendment_id).await?;
+
+            let timestamp = Utc::now().naive_utc();
+            self.api
+                .reject_amendment(&agreement, amendment_id, reason.clone(), timestamp)
+                .await?;
+            dao.reject(&agreement.id, amendment_id, reason.clone())
+                .await?;
+        }
+
+        self.common.notify_agreement(&agreement).await;
+
+        counter!("market.agreements.provider.amendment.rejected", 1);
+        log::info!(
+            "Provider {} rejected amendment [{}] of Agreement [{}]. Reason: {}",
+            id.display(),
+            amendment_id,
+            &agreement.id,
+            reason.display(),
+        );
+        Ok(())
+    }
+}
+
+async fn get_pending_amendment(
+    dao: &AgreementAmendmentDao<'_>,
+    agreement_id: &AgreementId,
+    amendment_id: &str,
+) -> Result<AgreementAmendment, AmendmentError> {
+    let amendment = dao
+        .select(agreement_id, amendment_id)
+        .await?
+        .ok_or_else(|| AmendmentError::NotFound(amendment_id.to_string()))?;
+
+    if amendment.state != AmendmentState::Pending {
+        return Err(AmendmentError::AlreadyResolved(
+            amendment.id,
+            amendment.state,
+        ));
+    }
+    Ok(amendment)
 }
 
 async fn on_agreement_committed(
@@ -672,6 +818,73 @@ async fn agreement_cancelled(
     Ok(())
 }
 
+async fn on_amendment_proposed(
+    broker: CommonBroker,
+    caller: String,
+    msg: AmendmentProposed,
+) -> Result<(), AmendAgreementError> {
+    let caller: NodeId = CommonBroker::parse_caller(&caller)?;
+    let agreement_id = msg.agreement_id.clone();
+    amendment_proposed(broker, caller, msg)
+        .await
+        .map_err(|e| AmendAgreementError::Remote(e, agreement_id))
+}
+
+async fn amendment_proposed(
+    broker: CommonBroker,
+    caller: NodeId,
+    msg: AmendmentProposed,
+) -> Result<(), RemoteAmendmentError> {
+    let dao = broker.db.as_dao::<AgreementAmendmentDao>();
+    let agreement = {
+        let _hold = broker.agreement_lock.lock(&msg.agreement_id).await;
+
+        let agreement = broker
+            .get_remote_amended_agreement(&msg.agreement_id, caller, Owner::Requestor)
+            .await?;
+
+        let pending = dao
+            .select_pending(&agreement.id)
+            .await
+            .map_err(|e| remote_amendment_error(&msg.amendment_id, e.into()))?;
+        if let Some(pending) = pending {
+            return Err(RemoteAmendmentError::PendingExists(
+                agreement.id,
+                pending.id,
+            ));
+        }
+
+        let amendment = AgreementAmendment {
+            id: msg.amendment_id.clone(),
+            agreement_id: agreement.id.clone(),
+            valid_to: msg.valid_to,
+            demand_properties: msg.demand_properties.clone(),
+            offer_properties: msg.offer_properties.clone(),
+            state: AmendmentState::Pending,
+            creation_ts: msg.creation_ts,
+            reason: None,
+        };
+        amendment
+            .validate(Utc::now().naive_utc())
+            .map_err(|e| RemoteAmendmentError::Invalid(msg.amendment_id.clone(), e.to_string()))?;
+        dao.insert(amendment)
+            .await
+            .map_err(|e| remote_amendment_error(&msg.amendment_id, e.into()))?;
+        agreement
+    };
+
+    broker.notify_agreement(&agreement).await;
+
+    counter!("market.agreements.provider.amendment.received", 1);
+    log::info!(
+        "Amendment [{}] of Agreement [{}] proposed by [{}].",
+        &msg.amendment_id,
+        &agreement.id,
+        caller,
+    );
+    Ok(())
+}
+
 impl From<GetProposalError> for RemoteProposeAgreementError {
     fn from(e: GetProposalError) -> Self {
         match e {
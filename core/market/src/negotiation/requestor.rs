@@ -1,25 +1,31 @@
 use chrono::{DateTime, Utc};
-use futures::stream::StreamExt;
+use futures::stream::{LocalBoxStream, StreamExt};
 use metrics::counter;
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::UnboundedReceiver;
 
+use serde_json::Value;
+
 use ya_client::model::market::{event::RequestorEvent, NewProposal, Reason};
 use ya_client::model::NodeId;
 use ya_service_api_web::middleware::Identity;
 use ya_std_utils::LogErr;
 
 use crate::db::{
-    dao::{AgreementDao, AgreementDaoError, SaveAgreementError},
-    model::{Agreement, AgreementId, AgreementState, AppSessionId},
-    model::{Demand, Issuer, Owner, ProposalId, SubscriptionId},
+    dao::{AgreementAmendmentDao, AgreementDao, AgreementDaoError, SaveAgreementError},
+    model::AppSessionId,
+    model::{Agreement, AgreementAmendment, AgreementId, AgreementState, AmendmentState},
+    model::{Demand, Issuer, Owner, ProposalId, ScoredRequestorEvent, SubscriptionId},
     DbMixedExecutor,
 };
 use crate::matcher::{store::SubscriptionStore, RawProposal};
 use crate::protocol::negotiation::{error::*, messages::*, requestor::NegotiationApi};
 
-use super::{common::*, error::*, notifier::NotifierError, EventNotifier};
+use super::{
+    common::*, error::*, notifier::NotifierError, scoring::scorer_from_config, EventNotifier,
+};
 use crate::config::Config;
 use crate::db::dao::AgreementEventsDao;
 use crate::db::model::ProposalState;
@@ -35,6 +41,17 @@ pub enum ApprovalStatus {
     Rejected { reason: Option<Reason> },
 }
 
+/// Result of promoting single Proposal to Agreement in bulk request.
+/// Agreement id is set, if Agreement was created, even if later steps failed.
+#[derive(Debug)]
+pub struct BulkAgreementOutcome {
+    pub proposal_id: ProposalId,
+    pub agreement_id: Option<AgreementId>,
+    /// Set only if caller requested waiting for approval.
+    pub approval: Option<ApprovalStatus>,
+    pub error: Option<String>,
+}
+
 /// Requestor part of negotiation logic.
 pub struct RequestorBroker {
     pub(crate) common: CommonBroker,
@@ -49,19 +66,20 @@ impl RequestorBroker {
         session_notifier: EventNotifier<AppSessionId>,
         config: Arc<Config>,
     ) -> Result<RequestorBroker, NegotiationInitError> {
-        let broker = CommonBroker::new(db, store, session_notifier, config);
+        let mut broker = CommonBroker::new(db, store, session_notifier, config);
+        broker.scorer = scorer_from_config(&broker.config.scoring)?;
 
         let broker1 = broker.clone();
         let broker2 = broker.clone();
         let broker3 = broker.clone();
         let broker_proposal_reject = broker.clone();
         let broker_terminated = broker.clone();
+        let broker_amendment_approved = broker.clone();
+        let broker_amendment_rejected = broker.clone();
 
         let api = NegotiationApi::new(
             move |caller: String, msg: ProposalReceived| {
-                broker1
-                    .clone()
-                    .on_proposal_received(msg, caller, Owner::Provider)
+                on_proposal_received(broker1.clone(), caller, msg)
             },
             move |caller: String, msg: ProposalRejected| {
                 broker_proposal_reject
@@ -79,6 +97,12 @@ impl RequestorBroker {
                     .clone()
                     .on_agreement_terminated(msg, caller, Owner::Provider)
             },
+            move |caller: String, msg: AmendmentApproved| {
+                on_amendment_approved(broker_amendment_approved.clone(), caller, msg)
+            },
+            move |caller: String, msg: AmendmentRejected| {
+                on_amendment_rejected(broker_amendment_rejected.clone(), caller, msg)
+            },
         );
 
         let engine = RequestorBroker {
@@ -98,10 +122,14 @@ impl RequestorBroker {
         counter!("market.agreements.requestor.terminated.reason", 0, "reason" => "NotSpecified");
         counter!("market.agreements.requestor.terminated.reason", 0, "reason" => "Success");
         counter!("market.agreements.requestor.committing", 0);
+        counter!("market.agreements.requestor.amendment.proposed", 0);
+        counter!("market.agreements.requestor.amendment.approved", 0);
+        counter!("market.agreements.requestor.amendment.rejected", 0);
         counter!("market.events.requestor.queried", 0);
         counter!("market.proposals.requestor.countered", 0);
         counter!("market.proposals.requestor.generated", 0);
         counter!("market.proposals.requestor.received", 0);
+        counter!("market.proposals.requestor.rejected.blocked", 0);
         counter!("market.proposals.requestor.rejected.initial", 0);
         counter!("market.proposals.requestor.rejected.by-them", 0);
         counter!("market.proposals.requestor.rejected.by-us", 0);
@@ -200,27 +228,87 @@ impl RequestorBroker {
         timeout: f32,
         max_events: Option<i32>,
     ) -> Result<Vec<RequestorEvent>, QueryEventsError> {
+        Ok(self
+            .query_scored_events(demand_id, timeout, max_events, false)
+            .await?
+            .into_iter()
+            .map(|scored| scored.event)
+            .collect())
+    }
+
+    /// Queries events together with scores of Proposals. If `order_by_score` is set,
+    /// Proposal events are returned starting from the best scored.
+    pub async fn query_scored_events(
+        &self,
+        demand_id: &SubscriptionId,
+        timeout: f32,
+        max_events: Option<i32>,
+        order_by_score: bool,
+    ) -> Result<Vec<ScoredRequestorEvent>, QueryEventsError> {
         let events = self
             .common
-            .query_events(demand_id, timeout, max_events, Owner::Requestor)
+            .query_events(
+                demand_id,
+                timeout,
+                max_events,
+                Owner::Requestor,
+                order_by_score,
+            )
             .await?;
 
         // Map model events to client RequestorEvent.
         let events = futures::stream::iter(events)
-            .then(|event| event.into_client_requestor_event(&self.common.db))
+            .then(|event| event.into_scored_requestor_event(&self.common.db))
             .inspect(|result| {
                 if let Err(error) = result {
                     log::error!("Error converting event to client type: {}", error);
                 }
             })
             .filter_map(|event| async move { event.ok() })
-            .collect::<Vec<RequestorEvent>>()
+            .collect::<Vec<ScoredRequestorEvent>>()
             .await;
 
         counter!("market.events.requestor.queried", events.len() as u64);
         Ok(events)
     }
 
+    /// Streams events for Demand together with scores of Proposals,
+    /// as soon, as they are produced.
+    pub async fn stream_scored_events(
+        &self,
+        demand_id: &SubscriptionId,
+    ) -> Result<
+        LocalBoxStream<'static, Result<ScoredRequestorEvent, QueryEventsError>>,
+        QueryEventsError,
+    > {
+        let db = self.common.db.clone();
+        let events = self
+            .common
+            .stream_events(demand_id, Owner::Requestor)
+            .await?;
+
+        // Map model events to client RequestorEvent.
+        Ok(events
+            .then(move |result| {
+                let db = db.clone();
+                async move {
+                    match result {
+                        Ok(event) => event
+                            .into_scored_requestor_event(&db)
+                            .await
+                            .map_err(|e| {
+                                log::error!("Error converting event to client type: {}", e)
+                            })
+                            .ok()
+                            .map(Ok),
+                        Err(e) => Some(Err(e)),
+                    }
+                }
+            })
+            .filter_map(|event| async move { event })
+            .boxed_local())
+    }
+
     /// Initiates the Agreement handshake phase.
     ///
     /// Formulates an Agreement artifact from the Proposal indicated by the
@@ -405,6 +493,98 @@ impl RequestorBroker {
         }
     }
 
+    /// Creates and confirms Agreements for multiple Proposals concurrently.
+    /// If `approval_timeout` is set, waits for Providers' approvals as well.
+    /// Failure of one Agreement doesn't affect the others, so outcome is
+    /// returned for each Proposal, in the same order as `proposal_ids`.
+    pub async fn create_agreements(
+        &self,
+        id: Identity,
+        proposal_ids: Vec<ProposalId>,
+        valid_to: DateTime<Utc>,
+        app_session_id: AppSessionId,
+        approval_timeout: Option<f32>,
+    ) -> Result<Vec<BulkAgreementOutcome>, BulkAgreementError> {
+        let config = &self.common.config.agreements;
+        if proposal_ids.is_empty() || proposal_ids.len() > config.max_bulk_agreements {
+            return Err(BulkAgreementError::InvalidCount(
+                proposal_ids.len(),
+                config.max_bulk_agreements,
+            ));
+        }
+
+        let mut unique = HashSet::new();
+        if let Some(duplicate) = proposal_ids
+            .iter()
+            .find(|proposal_id| !unique.insert(*proposal_id))
+        {
+            return Err(BulkAgreementError::DuplicateProposal(duplicate.clone()));
+        }
+
+        let outcomes = futures::stream::iter(proposal_ids)
+            .map(|proposal_id| {
+                self.create_single_agreement(
+                    id.clone(),
+                    proposal_id,
+                    valid_to,
+                    app_session_id.clone(),
+                    approval_timeout,
+                )
+            })
+            .buffered(config.bulk_agreements_concurrency.max(1))
+            .collect::<Vec<_>>()
+            .await;
+
+        let failed = outcomes.iter().filter(|o| o.error.is_some()).count();
+        counter!("market.agreements.requestor.bulk", outcomes.len() as u64);
+        counter!("market.agreements.requestor.bulk.failed", failed as u64);
+        Ok(outcomes)
+    }
+
+    async fn create_single_agreement(
+        &self,
+        id: Identity,
+        proposal_id: ProposalId,
+        valid_to: DateTime<Utc>,
+        app_session_id: AppSessionId,
+        approval_timeout: Option<f32>,
+    ) -> BulkAgreementOutcome {
+        let mut outcome = BulkAgreementOutcome {
+            proposal_id,
+            agreement_id: None,
+            approval: None,
+            error: None,
+        };
+
+        let agreement_id = match self
+            .create_agreement(id.clone(), &outcome.proposal_id, valid_to)
+            .await
+        {
+            Ok(agreement_id) => agreement_id,
+            Err(e) => {
+                outcome.error = Some(e.to_string());
+                return outcome;
+            }
+        };
+        outcome.agreement_id = Some(agreement_id.clone());
+
+        if let Err(e) = self
+            .confirm_agreement(id, &agreement_id, app_session_id)
+            .await
+        {
+            outcome.error = Some(e.to_string());
+            return outcome;
+        }
+
+        if let Some(timeout) = approval_timeout {
+            match self.wait_for_approval(&agreement_id, timeout).await {
+                Ok(status) => outcome.approval = Some(status),
+                Err(e) => outcome.error = Some(e.to_string()),
+            }
+        }
+        outcome
+    }
+
     /// Signs (not yet) Agreement self-created via `create_agreement`
     /// and sends it to the Provider.
     pub async fn confirm_agreement(
@@ -457,6 +637,120 @@ impl RequestorBroker {
         Ok(())
     }
 
+    /// Proposes new terms of approved Agreement to Provider. Agreement stays
+    /// unchanged until Provider approves amendment, so Activities created
+    /// for this Agreement are not affected.
+    pub async fn propose_amendment(
+        &self,
+        id: &Identity,
+        client_agreement_id: &str,
+        valid_to: DateTime<Utc>,
+        demand_properties: Option<Value>,
+        offer_properties: Option<Value>,
+    ) -> Result<AgreementAmendment, AmendmentError> {
+        if valid_to <= Utc::now() {
+            return Err(AmendmentError::InvalidValidTo(valid_to));
+        }
+        for properties in demand_properties.iter().chain(offer_properties.iter()) {
+            if !properties.is_object() {
+                return Err(AmendmentError::InvalidProperties(
+                    "Properties should be JSON object.".to_string(),
+                ));
+            }
+        }
+
+        let agreement = self
+            .common
+            .get_amended_agreement(id, client_agreement_id, Owner::Requestor)
+            .await?;
+        let dao = self.common.db.as_dao::<AgreementAmendmentDao>();
+
+        let amendment = {
+            // Amendment resolution from Provider can't be processed, before
+            // we store amendment in database.
+            let _hold = self.common.agreement_lock.lock(&agreement.id).await;
+
+            if let Some(pending) = dao.select_pending(&agreement.id).await? {
+                return Err(AmendmentError::PendingExists(agreement.id, pending.id));
+            }
+
+            let amendment = AgreementAmendment::new(
+                agreement.id.clone(),
+                valid_to.naive_utc(),
+                demand_properties,
+                offer_properties,
+            )
+            .map_err(|e| AmendmentError::InvalidProperties(e.to_string()))?;
+            amendment
+                .validate(Utc::now().naive_utc())
+                .map_err(|e| AmendmentError::InvalidProperties(e.to_string()))?;
+            dao.insert(amendment.clone()).await?;
+
+            if let Err(e) = self.api.propose_amendment(&agreement, &amendment).await {
+                dao.remove(&agreement.id, &amendment.id)
+                    .await
+                    .log_err()
+                    .ok();
+                return Err(e.into());
+            }
+            amendment
+        };
+
+        self.common.notify_agreement(&agreement).await;
+
+        counter!("market.agreements.requestor.amendment.proposed", 1);
+        log::info!(
+            "Requestor {} proposed amendment [{}] of Agreement [{}].",
+            id.display(),
+            &amendment.id,
+            &agreement.id,
+        );
+        Ok(amendment)
+    }
+
+    /// Waits until Provider approves or rejects amendment.
+    pub async fn wait_for_amendment(
+        &self,
+        id: &Identity,
+        client_agreement_id: &str,
+        amendment_id: &str,
+        timeout: f32,
+    ) -> Result<AgreementAmendment, AmendmentError> {
+        let timeout = Duration::from_secs_f32(timeout.max(0.0));
+        let agreement_id = AgreementId::from_client(client_agreement_id, Owner::Requestor)
+            .map_err(|_| AmendmentError::AgreementNotFound(client_agreement_id.to_string()))?;
+        let mut notifier = self.common.agreement_notifier.listen(&agreement_id);
+
+        self.common
+            .db
+            .as_dao::<AgreementDao>()
+            .select(&agreement_id, Some(id.identity), Utc::now().naive_utc())
+            .await
+            .map_err(|e| AmendmentError::Get(client_agreement_id.to_string(), e))?
+            .ok_or_else(|| AmendmentError::AgreementNotFound(client_agreement_id.to_string()))?;
+
+        let dao = self.common.db.as_dao::<AgreementAmendmentDao>();
+        loop {
+            let amendment = dao
+                .select(&agreement_id, amendment_id)
+                .await?
+                .ok_or_else(|| AmendmentError::NotFound(amendment_id.to_string()))?;
+
+            if amendment.state != AmendmentState::Pending {
+                return Ok(amendment);
+            }
+
+            if let Err(error) = notifier.wait_for_event_with_timeout(timeout).await {
+                return match error {
+                    NotifierError::Timeout(_) => {
+                        Err(AmendmentError::Timeout(amendment_id.to_string()))
+                    }
+                    e => Err(AmendmentError::Internal(e.to_string())),
+                };
+            }
+        }
+    }
+
     async fn query_reason_for(&self, agreement_id: &AgreementId) -> Option<Reason> {
         self.common
             .db
@@ -475,6 +769,36 @@ impl RequestorBroker {
     }
 }
 
+/// Counter Proposals from blocked Providers are rejected without
+/// notifying Requestor about them.
+async fn on_proposal_received(
+    broker: CommonBroker,
+    caller: String,
+    msg: ProposalReceived,
+) -> Result<(), CounterProposalError> {
+    let proposal_id = msg.proposal.proposal_id.clone();
+    let caller_id = CommonBroker::parse_caller(&caller)?;
+
+    let blocked = broker.store.is_node_blocked(caller_id).await.map_err(|e| {
+        let error = ProposalValidationError::Internal(e.to_string());
+        CounterProposalError::Remote(error.into(), proposal_id.clone())
+    })?;
+    if blocked {
+        counter!("market.proposals.requestor.rejected.blocked", 1);
+        log::info!(
+            "Rejecting counter Proposal [{}] from blocked Provider [{}].",
+            &proposal_id,
+            &caller_id
+        );
+        let error = ProposalValidationError::Blocked(caller_id);
+        return Err(CounterProposalError::Remote(error.into(), proposal_id));
+    }
+
+    broker
+        .on_proposal_received(msg, caller, Owner::Provider)
+        .await
+}
+
 async fn on_agreement_approved(
     broker: CommonBroker,
     caller: String,
@@ -692,6 +1016,94 @@ async fn agreement_rejected(
     Ok(())
 }
 
+async fn on_amendment_approved(
+    broker: CommonBroker,
+    caller: String,
+    msg: AmendmentApproved,
+) -> Result<(), AmendAgreementError> {
+    let caller: NodeId = CommonBroker::parse_caller(&caller)?;
+    let agreement_id = msg.agreement_id.clone();
+    amendment_approved(broker, caller, msg)
+        .await
+        .map_err(|e| AmendAgreementError::Remote(e, agreement_id))
+}
+
+async fn amendment_approved(
+    broker: CommonBroker,
+    caller: NodeId,
+    msg: AmendmentApproved,
+) -> Result<(), RemoteAmendmentError> {
+    let agreement = {
+        let _hold = broker.agreement_lock.lock(&msg.agreement_id).await;
+
+        broker
+            .get_remote_amended_agreement(&msg.agreement_id, caller, Owner::Provider)
+            .await?;
+        broker
+            .db
+            .as_dao::<AgreementAmendmentDao>()
+            .approve(&msg.agreement_id, &msg.amendment_id)
+            .await
+            .map_err(|e| remote_amendment_error(&msg.amendment_id, e))?
+    };
+
+    broker.notify_agreement(&agreement).await;
+
+    counter!("market.agreements.requestor.amendment.approved", 1);
+    log::info!(
+        "Amendment [{}] of Agreement [{}] approved by [{}].",
+        &msg.amendment_id,
+        &agreement.id,
+        caller,
+    );
+    Ok(())
+}
+
+async fn on_amendment_rejected(
+    broker: CommonBroker,
+    caller: String,
+    msg: AmendmentRejected,
+) -> Result<(), AmendAgreementError> {
+    let caller: NodeId = CommonBroker::parse_caller(&caller)?;
+    let agreement_id = msg.agreement_id.clone();
+    amendment_rejected(broker, caller, msg)
+        .await
+        .map_err(|e| AmendAgreementError::Remote(e, agreement_id))
+}
+
+async fn amendment_rejected(
+    broker: CommonBroker,
+    caller: NodeId,
+    msg: AmendmentRejected,
+) -> Result<(), RemoteAmendmentError> {
+    let agreement = {
+        let _hold = broker.agreement_lock.lock(&msg.agreement_id).await;
+
+        let agreement = broker
+            .get_remote_amended_agreement(&msg.agreement_id, caller, Owner::Provider)
+            .await?;
+        broker
+            .db
+            .as_dao::<AgreementAmendmentDao>()
+            .reject(&msg.agreement_id, &msg.amendment_id, msg.reason.clone())
+            .await
+            .map_err(|e| remote_amendment_error(&msg.amendment_id, e))?;
+        agreement
+    };
+
+    broker.notify_agreement(&agreement).await;
+
+    counter!("market.agreements.requestor.amendment.rejected", 1);
+    log::info!(
+        "Amendment [{}] of Agreement [{}] rejected by [{}]. Reason: {}",
+        &msg.amendment_id,
+        &agreement.id,
+        caller,
+        msg.reason.display(),
+    );
+    Ok(())
+}
+
 pub async fn proposal_receiver_thread(
     broker: CommonBroker,
     mut proposal_receiver: UnboundedReceiver<RawProposal>,
@@ -0,0 +1,305 @@
+//! Scoring of Proposals on Requestor side.
+//!
+//! Score is computed once, when Proposal from Provider is received, and stored
+//! together with Proposal, so Requestor can collect Proposals ordered by score
+//! without ranking them on his own.
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::config::ScoringConfig;
+
+pub trait ProposalScorer: Send + Sync {
+    /// Computes score of Proposal with given (flattened) properties.
+    /// Higher score means better Proposal. Returns None, if Proposal can't be scored.
+    fn score(&self, properties: &str) -> Option<f64>;
+}
+
+#[derive(Error, Debug)]
+#[error("Invalid scoring expression '{expression}': {reason}.")]
+pub struct ScoringError {
+    expression: String,
+    reason: String,
+}
+
+/// Scores Proposals with arithmetic expression over their properties.
+/// Supports `+`, `-`, `*`, `/`, parentheses, numeric constants and property names.
+/// Elements of list properties can be referenced by index, for example:
+/// `golem.inf.mem.gib / golem.com.pricing.model.linear.coeffs[1]`.
+pub struct ExpressionScorer {
+    expression: ScoreExpression,
+}
+
+#[derive(Debug, PartialEq)]
+enum ScoreExpression {
+    Constant(f64),
+    Property(String, Option<usize>),
+    Negate(Box<ScoreExpression>),
+    Binary(Operator, Box<ScoreExpression>, Box<ScoreExpression>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// Creates scorer configured for this node. None means, that Proposals aren't scored.
+pub fn scorer_from_config(
+    config: &ScoringConfig,
+) -> Result<Option<Arc<dyn ProposalScorer>>, ScoringError> {
+    match &config.proposal_score_expression {
+        Some(expression) if !expression.trim().is_empty() => {
+            Ok(Some(Arc::new(ExpressionScorer::new(expression)?)))
+        }
+        _ => Ok(None),
+    }
+}
+
+impl ExpressionScorer {
+    pub fn new(expression: &str) -> Result<ExpressionScorer, ScoringError> {
+        Ok(ExpressionScorer {
+            expression: Parser::parse(expression).map_err(|reason| ScoringError {
+                expression: expression.to_string(),
+                reason,
+            })?,
+        })
+    }
+}
+
+impl ProposalScorer for ExpressionScorer {
+    fn score(&self, properties: &str) -> Option<f64> {
+        let properties = serde_json::from_str::<Map<String, Value>>(properties).ok()?;
+        self.expression
+            .evaluate(&properties)
+            .filter(|score| score.is_finite())
+    }
+}
+
+impl ScoreExpression {
+    fn evaluate(&self, properties: &Map<String, Value>) -> Option<f64> {
+        match self {
+            ScoreExpression::Constant(value) => Some(*value),
+            ScoreExpression::Property(name, index) => {
+                let value = properties.get(name)?;
+                let value = match index {
+                    Some(index) => value.as_array()?.get(*index)?,
+                    None => value,
+                };
+                as_number(value)
+            }
+            ScoreExpression::Negate(expression) => expression.evaluate(properties).map(|v| -v),
+            ScoreExpression::Binary(operator, left, right) => {
+                let left = left.evaluate(properties)?;
+                let right = right.evaluate(properties)?;
+                Some(match operator {
+                    Operator::Add => left + right,
+                    Operator::Subtract => left - right,
+                    Operator::Multiply => left * right,
+                    Operator::Divide => left / right,
+                })
+            }
+        }
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+/// Recursive descent parser of scoring expressions.
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(input: &'a str) -> Result<ScoreExpression, String> {
+        let mut parser = Parser {
+            input: input.as_bytes(),
+            pos: 0,
+        };
+        let expression = parser.sum()?;
+        match parser.peek() {
+            None => Ok(expression),
+            Some(_) => Err(parser.unexpected()),
+        }
+    }
+
+    fn sum(&mut self) -> Result<ScoreExpression, String> {
+        let mut left = self.product()?;
+        loop {
+            let operator = match self.peek() {
+                Some(b'+') => Operator::Add,
+                Some(b'-') => Operator::Subtract,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.product()?;
+            left = ScoreExpression::Binary(operator, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn product(&mut self) -> Result<ScoreExpression, String> {
+        let mut left = self.factor()?;
+        loop {
+            let operator = match self.peek() {
+                Some(b'*') => Operator::Multiply,
+                Some(b'/') => Operator::Divide,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.factor()?;
+            left = ScoreExpression::Binary(operator, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn factor(&mut self) -> Result<ScoreExpression, String> {
+        match self.peek() {
+            Some(b'-') => {
+                self.pos += 1;
+                Ok(ScoreExpression::Negate(Box::new(self.factor()?)))
+            }
+            Some(b'(') => {
+                self.pos += 1;
+                let expression = self.sum()?;
+                self.expect(b')')?;
+                Ok(expression)
+            }
+            Some(c) if c.is_ascii_digit() || c == b'.' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || c == b'.');
+                number
+                    .parse::<f64>()
+                    .map(ScoreExpression::Constant)
+                    .map_err(|_| format!("invalid number '{}'", number))
+            }
+            Some(c) if c.is_ascii_alphabetic() || c == b'_' => {
+                let name = self
+                    .take_while(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'.')
+                    .to_string();
+                let index = match self.peek() {
+                    Some(b'[') => {
+                        self.pos += 1;
+                        let index = self.take_while(|c| c.is_ascii_digit());
+                        let index = index
+                            .parse::<usize>()
+                            .map_err(|_| format!("invalid index of property '{}'", name))?;
+                        self.expect(b']')?;
+                        Some(index)
+                    }
+                    _ => None,
+                };
+                Ok(ScoreExpression::Property(name, index))
+            }
+            Some(_) => Err(self.unexpected()),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+
+    /// Returns next non-whitespace character without consuming it.
+    fn peek(&mut self) -> Option<u8> {
+        while matches!(self.input.get(self.pos), Some(c) if c.is_ascii_whitespace()) {
+            self.pos += 1;
+        }
+        self.input.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), String> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(_) => Err(self.unexpected()),
+            None => Err(format!("expected '{}'", expected as char)),
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos;
+        while matches!(self.input.get(self.pos), Some(c) if predicate(*c)) {
+            self.pos += 1;
+        }
+        // Only ASCII characters are taken, so slice is always valid utf-8.
+        std::str::from_utf8(&self.input[start..self.pos]).unwrap_or_default()
+    }
+
+    fn unexpected(&self) -> String {
+        format!("unexpected character at position {}", self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{ExpressionScorer, ProposalScorer};
+
+    fn score(expression: &str, properties: serde_json::Value) -> Option<f64> {
+        ExpressionScorer::new(expression)
+            .unwrap()
+            .score(&properties.to_string())
+    }
+
+    #[test]
+    fn score_price_per_cpu_hour_weighted_by_memory() {
+        let properties = json!({
+            "golem.inf.mem.gib": 8,
+            "golem.inf.cpu.threads": "4",
+            "golem.com.pricing.model.linear.coeffs": [0.001, 0.0002, 0.0],
+        });
+        let expression = "golem.inf.mem.gib / (golem.com.pricing.model.linear.coeffs[1] * 3600)";
+
+        assert_eq!(
+            score(expression, properties.clone()),
+            Some(8.0 / (0.0002 * 3600.0))
+        );
+        assert_eq!(
+            score("-(golem.inf.cpu.threads - 1) * 2", properties),
+            Some(-6.0)
+        );
+    }
+
+    #[test]
+    fn score_undefined() {
+        let properties = json!({
+            "golem.inf.mem.gib": 8,
+            "golem.runtime.name": "vm",
+            "golem.com.pricing.model.linear.coeffs": [0.0],
+        });
+
+        assert_eq!(score("golem.inf.cpu.threads", properties.clone()), None);
+        assert_eq!(score("golem.runtime.name", properties.clone()), None);
+        assert_eq!(
+            score(
+                "golem.com.pricing.model.linear.coeffs[1]",
+                properties.clone()
+            ),
+            None
+        );
+        assert_eq!(
+            score(
+                "golem.inf.mem.gib / golem.com.pricing.model.linear.coeffs[0]",
+                properties
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_expression() {
+        for expression in &["", "a +", "(a * 2", "a[x]", "a ! b", "1.2.3", "a b"] {
+            assert!(
+                ExpressionScorer::new(expression).is_err(),
+                "Expression '{}' should be invalid",
+                expression
+            );
+        }
+    }
+}
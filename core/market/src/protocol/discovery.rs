@@ -23,10 +23,12 @@ use crate::identity::{IdentityApi, IdentityError};
 pub mod builder;
 pub mod error;
 pub mod message;
+pub mod rate_limit;
 
 use crate::PROTOCOL_VERSION;
 use error::*;
 use message::*;
+use rate_limit::RateLimiter;
 
 const MAX_OFFER_IDS_PER_BROADCAST: usize = 8;
 
@@ -53,6 +55,9 @@ pub struct DiscoveryImpl {
     get_local_offers_handler: HandlerSlot<RetrieveOffers>,
     offer_unsubscribe_handler: HandlerSlot<UnsubscribedOffersBcast>,
 
+    bcast_limiter: RateLimiter,
+    retrieve_limiter: RateLimiter,
+
     config: DiscoveryConfig,
 }
 
@@ -314,6 +319,15 @@ impl Discovery {
             return Ok(());
         }
 
+        if !self.inner.bcast_limiter.check(&caller) {
+            log::debug!(
+                "Rejecting Offers broadcast from [{}]: rate limit exceeded.",
+                &caller
+            );
+            counter!("market.offers.broadcasts.rejected", 1);
+            return Ok(());
+        }
+
         // We should do filtering and getting Offers in single transaction. Otherwise multiple
         // broadcasts can overlap and we will ask other nodes for the same Offers more than once.
         // Note that it wouldn't cause incorrect behavior, because we will add Offers only once.
@@ -382,9 +396,27 @@ impl Discovery {
     async fn on_get_remote_offers(
         self,
         caller: String,
-        msg: RetrieveOffers,
+        mut msg: RetrieveOffers,
     ) -> Result<Vec<ModelOffer>, DiscoveryRemoteError> {
         log::trace!("[{}] asks for {} Offers.", &caller, msg.offer_ids.len());
+        if !self.inner.retrieve_limiter.check(&caller) {
+            log::debug!(
+                "Rejecting Offers request from [{}]: rate limit exceeded.",
+                &caller
+            );
+            counter!("market.offers.retrieve.rejected", 1);
+            return Err(DiscoveryRemoteError::rate_limited());
+        }
+
+        // Requesting node should never ask for more Offers, than we could broadcast.
+        // Ids are ordered from the oldest Offer, so we serve the newest ones,
+        // the same way as `filter_out_known_offer_ids` does.
+        let max_offers = self.inner.config.max_bcasted_offers as usize;
+        let num_ids = msg.offer_ids.len();
+        if num_ids > max_offers {
+            msg.offer_ids.drain(..num_ids - max_offers);
+        }
+
         let get_local_offers = self.inner.get_local_offers_handler.clone();
         get_local_offers.call(caller, msg).await
     }
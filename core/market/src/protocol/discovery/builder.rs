@@ -8,6 +8,7 @@ use crate::protocol::callback::{CallbackHandler, CallbackMessage, HandlerSlot};
 
 use super::{Discovery, DiscoveryImpl};
 use crate::config::DiscoveryConfig;
+use crate::protocol::discovery::rate_limit::RateLimiter;
 use crate::protocol::discovery::OfferHandlers;
 
 #[derive(Default)]
@@ -70,6 +71,7 @@ impl DiscoveryBuilder {
             receive_remote_offers: self.get_handler(),
         });
 
+        let config = self.config.take().unwrap();
         Discovery {
             inner: Arc::new(DiscoveryImpl {
                 identity: self.get_data(),
@@ -79,7 +81,15 @@ impl DiscoveryBuilder {
                 lazy_binder_prefix: Mutex::new(None),
                 get_local_offers_handler: self.get_handler(),
                 offer_unsubscribe_handler: self.get_handler(),
-                config: self.config.unwrap(),
+                bcast_limiter: RateLimiter::new(
+                    config.max_incoming_bcasts_per_node,
+                    config.rate_limit_window,
+                ),
+                retrieve_limiter: RateLimiter::new(
+                    config.max_offer_retrievals_per_node,
+                    config.rate_limit_window,
+                ),
+                config,
             }),
         }
     }
@@ -24,6 +24,21 @@ pub enum DiscoveryRemoteError {
     InternalError(String),
 }
 
+/// Message returned in `DiscoveryRemoteError::InternalError`, when caller exceeded
+/// Offers retrieval rate limit. We don't add new variant for this, because
+/// older nodes wouldn't be able to deserialize it.
+pub const RATE_LIMITED_MESSAGE: &str = "Too many requests. Try again later";
+
+impl DiscoveryRemoteError {
+    pub fn rate_limited() -> Self {
+        DiscoveryRemoteError::InternalError(RATE_LIMITED_MESSAGE.to_string())
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, DiscoveryRemoteError::InternalError(msg) if msg == RATE_LIMITED_MESSAGE)
+    }
+}
+
 #[derive(Error, Debug, Serialize, Deserialize)]
 pub enum DiscoveryInitError {
     #[error("Failed to bind broadcast `{0}` to gsb. Error: {1}.")]
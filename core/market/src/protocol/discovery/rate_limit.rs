@@ -0,0 +1,80 @@
+//! Limits number of discovery messages accepted from single node.
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of tracked nodes, above which counters of expired windows are dropped.
+const PRUNE_THRESHOLD: usize = 1000;
+
+/// Counts messages from each node in fixed time windows.
+pub struct RateLimiter {
+    /// Max number of messages accepted from single node in `window`. 0 means no limit.
+    limit: u32,
+    window: Duration,
+    counters: Mutex<HashMap<String, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        RateLimiter {
+            limit,
+            window,
+            counters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Registers message from `caller` and returns false, if caller exceeded limit.
+    pub fn check(&self, caller: &str) -> bool {
+        self.check_at(caller, Instant::now())
+    }
+
+    fn check_at(&self, caller: &str, now: Instant) -> bool {
+        if self.limit == 0 {
+            return true;
+        }
+
+        let mut counters = self.counters.lock().unwrap();
+        if counters.len() >= PRUNE_THRESHOLD {
+            let window = self.window;
+            counters.retain(|_, (start, _)| now.duration_since(*start) < window);
+        }
+
+        let (start, count) = counters.entry(caller.to_string()).or_insert((now, 0));
+        if now.duration_since(*start) >= self.window {
+            *start = now;
+            *count = 0;
+        }
+
+        if *count >= self.limit {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::RateLimiter;
+
+    #[test]
+    fn limit_per_node_and_window() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let now = Instant::now();
+
+        assert!(limiter.check_at("0xa", now));
+        assert!(limiter.check_at("0xa", now));
+        assert!(!limiter.check_at("0xa", now + Duration::from_secs(59)));
+        assert!(limiter.check_at("0xb", now));
+
+        assert!(limiter.check_at("0xa", now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn zero_limit_is_unlimited() {
+        let limiter = RateLimiter::new(0, Duration::from_secs(60));
+        assert!((0..100).all(|_| limiter.check("0xa")));
+    }
+}
@@ -2,7 +2,9 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::db::dao::ChangeProposalStateError;
-use crate::db::model::{AgreementId, AgreementState, ProposalId, ProposalIdValidationError};
+use crate::db::model::{
+    AgreementId, AgreementState, AmendmentState, ProposalId, ProposalIdValidationError,
+};
 use crate::matcher::error::QueryOfferError;
 use crate::negotiation::error::{GetProposalError, MatchValidationError, ProposalValidationError};
 
@@ -176,6 +178,35 @@ pub enum RemoteCommitAgreementError {
     },
 }
 
+#[derive(Error, Debug, Serialize, Deserialize)]
+pub enum AmendAgreementError {
+    #[error("Amend {0}.")]
+    Gsb(#[from] GsbAgreementError),
+    #[error("Remote amend Agreement [{1}] error: {0}")]
+    Remote(RemoteAmendmentError, AgreementId),
+    #[error(transparent)]
+    CallerParse(#[from] CallerParseError),
+}
+
+#[derive(Error, Debug, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum RemoteAmendmentError {
+    #[error("Agreement [{0}] not found.")]
+    AgreementNotFound(AgreementId),
+    #[error("Agreement [{0}] in state {1}, can't be amended.")]
+    InvalidState(AgreementId, AgreementState),
+    #[error("Amendment [{0}] not found.")]
+    NotFound(String),
+    #[error("Amendment [{0}] is already {1}.")]
+    AlreadyResolved(String, AmendmentState),
+    #[error("Agreement [{0}] has pending amendment [{1}].")]
+    PendingExists(AgreementId, String),
+    #[error("Amendment [{0}] is invalid. {1}")]
+    Invalid(String, String),
+    #[error("Can't finish amendment [{0}] due to internal error.")]
+    InternalError(String),
+}
+
 impl RemoteSensitiveError for RemoteProposeAgreementError {
     fn hide_sensitive_info(self) -> RemoteProposeAgreementError {
         match self {
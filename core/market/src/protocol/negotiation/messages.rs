@@ -10,7 +10,9 @@ use crate::protocol::negotiation::error::{
 };
 
 use super::super::callback::CallbackMessage;
-use super::error::{AgreementProtocolError, CounterProposalError, TerminateAgreementError};
+use super::error::{
+    AgreementProtocolError, AmendAgreementError, CounterProposalError, TerminateAgreementError,
+};
 
 pub mod provider {
     pub fn proposal_addr(prefix: &str) -> String {
@@ -205,6 +207,55 @@ impl RpcMessage for AgreementCommitted {
     type Error = CommitAgreementError;
 }
 
+/// Requestor proposes change of terms of already approved Agreement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendmentProposed {
+    pub agreement_id: AgreementId,
+    pub amendment_id: String,
+    pub valid_to: NaiveDateTime,
+    /// Flattened properties, that will be added or replaced in Agreement Demand.
+    pub demand_properties: Option<String>,
+    /// Flattened properties, that will be added or replaced in Agreement Offer.
+    pub offer_properties: Option<String>,
+    pub creation_ts: NaiveDateTime,
+}
+
+impl RpcMessage for AmendmentProposed {
+    const ID: &'static str = "AmendmentProposed";
+    type Item = ();
+    type Error = AmendAgreementError;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendmentApproved {
+    pub agreement_id: AgreementId,
+    pub amendment_id: String,
+    pub approved_ts: NaiveDateTime,
+}
+
+impl RpcMessage for AmendmentApproved {
+    const ID: &'static str = "AmendmentApproved";
+    type Item = ();
+    type Error = AmendAgreementError;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendmentRejected {
+    pub agreement_id: AgreementId,
+    pub amendment_id: String,
+    pub reason: Option<Reason>,
+    pub rejection_ts: NaiveDateTime,
+}
+
+impl RpcMessage for AmendmentRejected {
+    const ID: &'static str = "AmendmentRejected";
+    type Item = ();
+    type Error = AmendAgreementError;
+}
+
 /// The same messaged will be used on GSB and as messages in callbacks.
 impl<Message: RpcMessage> CallbackMessage for Message {
     type Ok = <Message as RpcMessage>::Item;
@@ -287,3 +338,24 @@ impl AgreementCommitted {
         self
     }
 }
+
+impl AmendmentProposed {
+    pub fn translate(mut self, owner: Owner) -> Self {
+        self.agreement_id = self.agreement_id.translate(owner);
+        self
+    }
+}
+
+impl AmendmentApproved {
+    pub fn translate(mut self, owner: Owner) -> Self {
+        self.agreement_id = self.agreement_id.translate(owner);
+        self
+    }
+}
+
+impl AmendmentRejected {
+    pub fn translate(mut self, owner: Owner) -> Self {
+        self.agreement_id = self.agreement_id.translate(owner);
+        self
+    }
+}
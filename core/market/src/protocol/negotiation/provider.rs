@@ -11,13 +11,14 @@ use crate::db::model::{Agreement, Owner, Proposal};
 
 use super::super::callback::{CallbackHandler, HandlerSlot};
 use super::error::{
-    AgreementProtocolError, CounterProposalError, GsbAgreementError, GsbProposalError,
-    NegotiationApiInitError, TerminateAgreementError,
+    AgreementProtocolError, AmendAgreementError, CounterProposalError, GsbAgreementError,
+    GsbProposalError, NegotiationApiInitError, TerminateAgreementError,
 };
 use super::messages::{
     provider, requestor, AgreementApproved, AgreementCancelled, AgreementCommitted,
-    AgreementReceived, AgreementRejected, AgreementTerminated, InitialProposalReceived,
-    ProposalContent, ProposalReceived, ProposalRejected,
+    AgreementReceived, AgreementRejected, AgreementTerminated, AmendmentApproved,
+    AmendmentProposed, AmendmentRejected, InitialProposalReceived, ProposalContent,
+    ProposalReceived, ProposalRejected,
 };
 use crate::protocol::negotiation::error::{
     CommitAgreementError, ProposeAgreementError, RejectProposalError,
@@ -39,6 +40,7 @@ struct NegotiationImpl {
     agreement_cancelled: HandlerSlot<AgreementCancelled>,
     agreement_terminated: HandlerSlot<AgreementTerminated>,
     agreement_committed: HandlerSlot<AgreementCommitted>,
+    amendment_proposed: HandlerSlot<AmendmentProposed>,
 }
 
 // TODO: Most of these functions don't need to be members of NegotiationApi.
@@ -53,6 +55,7 @@ impl NegotiationApi {
         agreement_cancelled: impl CallbackHandler<AgreementCancelled>,
         agreement_terminated: impl CallbackHandler<AgreementTerminated>,
         agreement_committed: impl CallbackHandler<AgreementCommitted>,
+        amendment_proposed: impl CallbackHandler<AmendmentProposed>,
     ) -> NegotiationApi {
         let negotiation_impl = NegotiationImpl {
             initial_proposal_received: HandlerSlot::new(initial_proposal_received),
@@ -62,6 +65,7 @@ impl NegotiationApi {
             agreement_cancelled: HandlerSlot::new(agreement_cancelled),
             agreement_terminated: HandlerSlot::new(agreement_terminated),
             agreement_committed: HandlerSlot::new(agreement_committed),
+            amendment_proposed: HandlerSlot::new(amendment_proposed),
         };
         NegotiationApi {
             inner: Arc::new(negotiation_impl),
@@ -159,6 +163,48 @@ impl NegotiationApi {
         Ok(())
     }
 
+    pub async fn approve_amendment(
+        &self,
+        agreement: &Agreement,
+        amendment_id: &str,
+        timestamp: NaiveDateTime,
+    ) -> Result<(), AmendAgreementError> {
+        let msg = AmendmentApproved {
+            agreement_id: agreement.id.clone(),
+            amendment_id: amendment_id.to_string(),
+            approved_ts: timestamp,
+        };
+        net::from(agreement.provider_id)
+            .to(agreement.requestor_id)
+            .service(&requestor::agreement_addr(BUS_ID))
+            .send(msg)
+            .await
+            .map_err(|e| GsbAgreementError(e.to_string(), agreement.id.clone()))??;
+        Ok(())
+    }
+
+    pub async fn reject_amendment(
+        &self,
+        agreement: &Agreement,
+        amendment_id: &str,
+        reason: Option<Reason>,
+        timestamp: NaiveDateTime,
+    ) -> Result<(), AmendAgreementError> {
+        let msg = AmendmentRejected {
+            agreement_id: agreement.id.clone(),
+            amendment_id: amendment_id.to_string(),
+            reason,
+            rejection_ts: timestamp,
+        };
+        net::from(agreement.provider_id)
+            .to(agreement.requestor_id)
+            .service(&requestor::agreement_addr(BUS_ID))
+            .send(msg)
+            .await
+            .map_err(|e| GsbAgreementError(e.to_string(), agreement.id.clone()))??;
+        Ok(())
+    }
+
     async fn on_initial_proposal_received(
         self,
         caller: String,
@@ -280,6 +326,23 @@ impl NegotiationApi {
             .await
     }
 
+    async fn on_amendment_proposed(
+        self,
+        caller: String,
+        msg: AmendmentProposed,
+    ) -> Result<(), AmendAgreementError> {
+        log::debug!(
+            "Negotiation API: Amendment [{}] of Agreement [{}] proposed by [{}].",
+            &msg.amendment_id,
+            &msg.agreement_id,
+            &caller
+        );
+        self.inner
+            .amendment_proposed
+            .call(caller, msg.translate(Owner::Provider))
+            .await
+    }
+
     pub async fn bind_gsb(
         &self,
         public_prefix: &str,
@@ -319,6 +382,10 @@ impl NegotiationApi {
             .bind_with_processor(move |_, myself, caller: String, msg: AgreementCommitted| {
                 let myself = myself;
                 myself.on_agreement_committed(caller, msg)
+            })
+            .bind_with_processor(move |_, myself, caller: String, msg: AmendmentProposed| {
+                let myself = myself;
+                myself.on_amendment_proposed(caller, msg)
             });
         Ok(())
     }
@@ -7,17 +7,18 @@ use ya_core_model::market::BUS_ID;
 use ya_net::{self as net, RemoteEndpoint};
 use ya_service_bus::{typed::ServiceBinder, RpcEndpoint};
 
-use crate::db::model::{Agreement, Owner, Proposal};
+use crate::db::model::{Agreement, AgreementAmendment, Owner, Proposal};
 
 use super::super::callback::{CallbackHandler, HandlerSlot};
 use super::error::{
-    AgreementProtocolError, CounterProposalError, GsbAgreementError, GsbProposalError,
-    NegotiationApiInitError, TerminateAgreementError,
+    AgreementProtocolError, AmendAgreementError, CounterProposalError, GsbAgreementError,
+    GsbProposalError, NegotiationApiInitError, TerminateAgreementError,
 };
 use super::messages::{
     provider, requestor, AgreementApproved, AgreementCancelled, AgreementReceived,
-    AgreementRejected, AgreementTerminated, InitialProposalReceived, ProposalContent,
-    ProposalReceived, ProposalRejected,
+    AgreementRejected, AgreementTerminated, AmendmentApproved, AmendmentProposed,
+    AmendmentRejected, InitialProposalReceived, ProposalContent, ProposalReceived,
+    ProposalRejected,
 };
 use crate::protocol::negotiation::error::{
     CommitAgreementError, ProposeAgreementError, RejectProposalError,
@@ -38,6 +39,8 @@ struct NegotiationImpl {
     agreement_approved: HandlerSlot<AgreementApproved>,
     agreement_rejected: HandlerSlot<AgreementRejected>,
     agreement_terminated: HandlerSlot<AgreementTerminated>,
+    amendment_approved: HandlerSlot<AmendmentApproved>,
+    amendment_rejected: HandlerSlot<AmendmentRejected>,
 }
 
 // TODO: Most of these functions don't need to be members of NegotiationApi.
@@ -50,6 +53,8 @@ impl NegotiationApi {
         agreement_approved: impl CallbackHandler<AgreementApproved>,
         agreement_rejected: impl CallbackHandler<AgreementRejected>,
         agreement_terminated: impl CallbackHandler<AgreementTerminated>,
+        amendment_approved: impl CallbackHandler<AmendmentApproved>,
+        amendment_rejected: impl CallbackHandler<AmendmentRejected>,
     ) -> NegotiationApi {
         let negotiation_impl = NegotiationImpl {
             proposal_received: HandlerSlot::new(proposal_received),
@@ -57,6 +62,8 @@ impl NegotiationApi {
             agreement_approved: HandlerSlot::new(agreement_approved),
             agreement_rejected: HandlerSlot::new(agreement_rejected),
             agreement_terminated: HandlerSlot::new(agreement_terminated),
+            amendment_approved: HandlerSlot::new(amendment_approved),
+            amendment_rejected: HandlerSlot::new(amendment_rejected),
         };
         NegotiationApi {
             inner: Arc::new(negotiation_impl),
@@ -197,6 +204,30 @@ impl NegotiationApi {
         Ok(())
     }
 
+    /// Sent to provider, when Requestor wants to change terms
+    /// of already approved Agreement.
+    pub async fn propose_amendment(
+        &self,
+        agreement: &Agreement,
+        amendment: &AgreementAmendment,
+    ) -> Result<(), AmendAgreementError> {
+        let msg = AmendmentProposed {
+            agreement_id: agreement.id.clone(),
+            amendment_id: amendment.id.clone(),
+            valid_to: amendment.valid_to,
+            demand_properties: amendment.demand_properties.clone(),
+            offer_properties: amendment.offer_properties.clone(),
+            creation_ts: amendment.creation_ts,
+        };
+        net::from(agreement.requestor_id)
+            .to(agreement.provider_id)
+            .service(&provider::agreement_addr(BUS_ID))
+            .send(msg)
+            .await
+            .map_err(|e| GsbAgreementError(e.to_string(), agreement.id.clone()))??;
+        Ok(())
+    }
+
     async fn on_proposal_received(
         self,
         caller: String,
@@ -277,6 +308,40 @@ impl NegotiationApi {
             .await
     }
 
+    async fn on_amendment_approved(
+        self,
+        caller: String,
+        msg: AmendmentApproved,
+    ) -> Result<(), AmendAgreementError> {
+        log::debug!(
+            "Negotiation API: Amendment [{}] of Agreement [{}] approved by [{}].",
+            &msg.amendment_id,
+            &msg.agreement_id,
+            &caller
+        );
+        self.inner
+            .amendment_approved
+            .call(caller, msg.translate(Owner::Requestor))
+            .await
+    }
+
+    async fn on_amendment_rejected(
+        self,
+        caller: String,
+        msg: AmendmentRejected,
+    ) -> Result<(), AmendAgreementError> {
+        log::debug!(
+            "Negotiation API: Amendment [{}] of Agreement [{}] rejected by [{}].",
+            &msg.amendment_id,
+            &msg.agreement_id,
+            &caller
+        );
+        self.inner
+            .amendment_rejected
+            .call(caller, msg.translate(Owner::Requestor))
+            .await
+    }
+
     pub async fn bind_gsb(
         &self,
         public_prefix: &str,
@@ -306,6 +371,14 @@ impl NegotiationApi {
             .bind_with_processor(move |_, myself, caller: String, msg: AgreementTerminated| {
                 let myself = myself;
                 myself.on_agreement_terminated(caller, msg)
+            })
+            .bind_with_processor(move |_, myself, caller: String, msg: AmendmentApproved| {
+                let myself = myself;
+                myself.on_amendment_approved(caller, msg)
+            })
+            .bind_with_processor(move |_, myself, caller: String, msg: AmendmentRejected| {
+                let myself = myself;
+                myself.on_amendment_rejected(caller, msg)
             });
         Ok(())
     }
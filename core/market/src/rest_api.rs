@@ -5,15 +5,27 @@
 //! No market logic is allowed here.
 
 use actix_web::web::JsonConfig;
-use actix_web::{error::InternalError, http::StatusCode, web::PathConfig};
+use actix_web::{error::InternalError, http::header, http::StatusCode, web::PathConfig};
+use actix_web::{web::Bytes, HttpResponse, HttpResponseBuilder};
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use futures::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::convert::TryFrom;
+use std::time::Duration;
 
-use ya_client::model::{market::agreement::State, ErrorMessage};
+use ya_client::model::market::{agreement::State, Reason};
+use ya_client::model::{ErrorMessage, NodeId};
 
 use crate::db::model::{
-    AgreementId, AppSessionId, Owner, ProposalId, ProposalIdParseError, SubscriptionId,
+    AgreementAmendment, AgreementEvent, AgreementEventType, AgreementId, AmendmentState,
+    AppSessionId, EventTypeError, OfferSortKey, Owner, ProposalId, ProposalIdParseError,
+    SubscriptionId,
 };
+use crate::market::export::ExportFormat;
+use crate::matcher::schema::SchemaViolation;
+use crate::negotiation::error::AmendmentError;
+use crate::negotiation::BulkAgreementOutcome;
 
 pub(crate) mod common;
 mod error;
@@ -43,22 +55,240 @@ pub fn json_config() -> JsonConfig {
     })
 }
 
+/// Response to successful subscription. Property schema violations, that didn't
+/// prevent subscription, are returned as `Warning` headers.
+pub(crate) fn subscribed(violations: &[SchemaViolation]) -> HttpResponseBuilder {
+    let mut response = HttpResponse::Created();
+    for violation in violations {
+        // Header value can contain only visible ASCII characters.
+        let text = violation
+            .to_string()
+            .chars()
+            .map(|c| match c {
+                '"' | '\\' => '\'',
+                c if c == ' ' || c.is_ascii_graphic() => c,
+                _ => '?',
+            })
+            .collect::<String>();
+        response.append_header((header::WARNING, format!("199 - \"{}\"", text)));
+    }
+    response
+}
+
+/// Wraps stream of events into Server-Sent Events response. Each event is sent as
+/// `name` event with json data. Comment line is sent as keep-alive, if no event
+/// appears within `keepalive` interval. Error is sent as `error` event and ends stream.
+pub(crate) fn event_stream<Event, Error>(
+    events: impl Stream<Item = Result<Event, Error>> + 'static,
+    name: &'static str,
+    keepalive: Duration,
+) -> HttpResponse
+where
+    Event: Serialize,
+    Error: std::fmt::Display,
+{
+    let events = Box::pin(events);
+    let frames = stream::unfold(Some((events, 0u64)), move |state| async move {
+        let (mut events, mut seq) = state?;
+        let (frame, next) = match tokio::time::timeout(keepalive, events.next()).await {
+            Err(_) => (":ping\n\n".to_string(), true),
+            Ok(None) => return None,
+            Ok(Some(Ok(event))) => match serde_json::to_string(&event) {
+                Ok(data) => {
+                    seq += 1;
+                    (
+                        format!("event: {}\ndata: {}\nid: {}\n\n", name, data, seq),
+                        true,
+                    )
+                }
+                Err(e) => (error_frame(e), false),
+            },
+            Ok(Some(Err(e))) => (error_frame(e), false),
+        };
+        let next = if next { Some((events, seq)) } else { None };
+        Some((Ok::<_, actix_web::Error>(Bytes::from(frame)), next))
+    });
+
+    HttpResponse::Ok()
+        .append_header((header::CONTENT_TYPE, "text/event-stream"))
+        .append_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(frames)
+}
+
+fn error_frame(e: impl std::fmt::Display) -> String {
+    log::warn!("Closing events stream. Error: {}", e);
+    let message = ErrorMessage::new(e.to_string());
+    format!(
+        "event: error\ndata: {}\n\n",
+        serde_json::to_string(&message).unwrap_or_default()
+    )
+}
+
 #[derive(Deserialize, Clone)]
 pub struct PathAgreement {
     pub agreement_id: String,
 }
 
+#[derive(Deserialize, Clone)]
+pub struct PathAmendment {
+    pub agreement_id: String,
+    pub amendment_id: String,
+}
+
 #[derive(Deserialize)]
 pub struct PathSubscription {
     pub subscription_id: SubscriptionId,
 }
 
+#[derive(Deserialize)]
+pub struct PathSubscriptionOffer {
+    pub subscription_id: SubscriptionId,
+    pub offer_id: SubscriptionId,
+}
+
 #[derive(Deserialize)]
 pub struct PathSubscriptionProposal {
     pub subscription_id: SubscriptionId,
     pub proposal_id: ProposalId,
 }
 
+#[derive(Deserialize)]
+pub struct PathNode {
+    pub node_id: NodeId,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BlockNodeReason {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkAgreementProposals {
+    pub proposal_ids: Vec<String>,
+    pub valid_to: DateTime<Utc>,
+    pub app_session_id: AppSessionId,
+    /// Number of seconds to wait for Providers' approvals. Agreements aren't
+    /// awaited, if not set.
+    pub approval_timeout: Option<f32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkAgreementResult {
+    pub proposal_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agreement_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorMessage>,
+}
+
+impl From<BulkAgreementOutcome> for BulkAgreementResult {
+    fn from(outcome: BulkAgreementOutcome) -> Self {
+        BulkAgreementResult {
+            proposal_id: outcome.proposal_id.into_client(),
+            agreement_id: outcome.agreement_id.map(|id| id.into_client()),
+            approval: outcome.approval.map(|status| status.to_string()),
+            error: outcome.error.map(ErrorMessage::new),
+        }
+    }
+}
+
+/// New terms of approved Agreement. Properties are merged with
+/// current Agreement properties, so only changed values should be sent.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewAmendment {
+    pub valid_to: DateTime<Utc>,
+    pub demand_properties: Option<Value>,
+    pub offer_properties: Option<Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Amendment {
+    pub amendment_id: String,
+    pub agreement_id: String,
+    pub valid_to: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub demand_properties: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offer_properties: Option<Value>,
+    pub state: AmendmentState,
+    pub timestamp: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<Reason>,
+}
+
+impl TryFrom<AgreementAmendment> for Amendment {
+    type Error = AmendmentError;
+
+    fn try_from(amendment: AgreementAmendment) -> Result<Self, Self::Error> {
+        let parse = |properties: Option<&String>| {
+            properties
+                .map(|properties| serde_json::from_str::<Value>(properties))
+                .transpose()
+                .map_err(|e| AmendmentError::Internal(format!("Invalid properties. {}", e)))
+        };
+        Ok(Amendment {
+            demand_properties: parse(amendment.demand_properties.as_ref())?,
+            offer_properties: parse(amendment.offer_properties.as_ref())?,
+            reason: amendment.reason(),
+            amendment_id: amendment.id,
+            agreement_id: amendment.agreement_id.into_client(),
+            valid_to: DateTime::<Utc>::from_utc(amendment.valid_to, Utc),
+            state: amendment.state,
+            timestamp: DateTime::<Utc>::from_utc(amendment.creation_ts, Utc),
+        })
+    }
+}
+
+/// Amendment events aren't part of ya-client `AgreementOperationEvent`, so they are
+/// returned from separate endpoint. Older clients would fail to parse them.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendmentEvent {
+    pub agreement_id: String,
+    pub amendment_id: String,
+    pub event_date: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event_type: AmendmentEventType,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "eventType")]
+pub enum AmendmentEventType {
+    AmendmentProposedEvent,
+    AmendmentApprovedEvent,
+    AmendmentRejectedEvent {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<Reason>,
+    },
+}
+
+impl TryFrom<AgreementEvent> for AmendmentEvent {
+    type Error = EventTypeError;
+
+    fn try_from(event: AgreementEvent) -> Result<Self, Self::Error> {
+        let event_type = match event.event_type {
+            AgreementEventType::AmendmentProposed => AmendmentEventType::AmendmentProposedEvent,
+            AgreementEventType::AmendmentApproved => AmendmentEventType::AmendmentApprovedEvent,
+            AgreementEventType::AmendmentRejected => AmendmentEventType::AmendmentRejectedEvent {
+                reason: event.reason.map(|reason| reason.0),
+            },
+            _ => return Err(EventTypeError(event.id, event.event_type)),
+        };
+        Ok(AmendmentEvent {
+            agreement_id: event.agreement_id.into_client(),
+            amendment_id: event.amendment_id.unwrap_or_default(),
+            event_date: DateTime::<Utc>::from_utc(event.timestamp, Utc),
+            event_type,
+        })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QueryAgreementList {
@@ -68,6 +298,31 @@ pub struct QueryAgreementList {
     pub app_session_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryAgreementExport {
+    pub peer_id: Option<NodeId>,
+    pub state: Option<State>,
+    pub before_date: Option<DateTime<Utc>>,
+    pub after_date: Option<DateTime<Utc>>,
+    pub app_session_id: Option<String>,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryOffers {
+    /// Constraints in LDAP filter syntax, which Offer properties must fulfill.
+    pub constraints: Option<String>,
+    #[serde(default)]
+    pub sort_by: OfferSortKey,
+    /// Cursor returned with previous page of results.
+    pub cursor: Option<String>,
+    /// Maximal number of Offers to return.
+    pub limit: Option<i32>,
+}
+
 #[derive(Deserialize)]
 pub struct QueryAppSessionId {
     #[serde(rename = "appSessionId")]
@@ -106,6 +361,19 @@ pub struct QueryTimeoutMaxEvents {
     pub max_events: Option<i32>,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct QueryRequestorEvents {
+    /// number of seconds to wait
+    #[serde(rename = "timeout", default = "default_event_timeout")]
+    pub timeout: f32,
+    /// maximum count of events to return
+    #[serde(rename = "maxEvents")]
+    pub max_events: Option<i32>,
+    /// return Proposal events starting from the best scored
+    #[serde(rename = "orderByScore", default)]
+    pub order_by_score: bool,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct QueryAgreementEvents {
     /// number of seconds to wait
@@ -120,6 +388,14 @@ pub struct QueryAgreementEvents {
     pub after_timestamp: Option<DateTime<Utc>>,
 }
 
+#[derive(Deserialize)]
+pub struct QueryAgreementEventsStream {
+    #[serde(rename = "appSessionId")]
+    pub app_session_id: AppSessionId,
+    #[serde(rename = "afterTimestamp")]
+    pub after_timestamp: Option<DateTime<Utc>>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct QueryTerminateAgreement {
     pub reason: Option<String>,
@@ -1,24 +1,38 @@
 use actix_web::web::{Data, Json, Path, Query};
 use actix_web::{HttpResponse, Responder, Scope};
 use chrono::{TimeZone, Utc};
+use std::convert::TryFrom;
 use std::sync::Arc;
 
 use ya_client::model::market::Reason;
+use ya_core_model::market::ExportAgreements;
 use ya_service_api_web::middleware::Identity;
 use ya_std_utils::LogErr;
 
-use super::PathAgreement;
+use super::{event_stream, Amendment, BlockNodeReason, PathAgreement, PathNode};
 use crate::db::model::Owner;
+use crate::market::export::{self, ExportFormat};
 use crate::market::MarketService;
 use crate::negotiation::error::AgreementError;
-use crate::rest_api::{QueryAgreementEvents, QueryAgreementList};
+use crate::rest_api::{
+    QueryAgreementEvents, QueryAgreementEventsStream, QueryAgreementExport, QueryAgreementList,
+    QueryOffers,
+};
 
 pub fn register_endpoints(scope: Scope) -> Scope {
     scope
         .service(list_agreements)
+        .service(export_agreements)
         .service(collect_agreement_events)
+        .service(stream_agreement_events)
+        .service(collect_amendment_events)
         .service(get_agreement)
+        .service(list_amendments)
         .service(terminate_agreement)
+        .service(query_offers)
+        .service(list_blocked_nodes)
+        .service(block_node)
+        .service(unblock_node)
 }
 
 #[actix_web::get("/agreements")]
@@ -41,6 +55,34 @@ async fn list_agreements(
         .map(|list| HttpResponse::Ok().json(list))
 }
 
+/// Registered before `get_agreement`, so `export` isn't taken for Agreement id.
+#[actix_web::get("/agreements/export")]
+async fn export_agreements(
+    market: Data<Arc<MarketService>>,
+    query: Query<QueryAgreementExport>,
+    id: Identity,
+) -> impl Responder {
+    let query = query.into_inner();
+    let filter = ExportAgreements {
+        peer_id: query.peer_id,
+        state: query.state,
+        before_date: query.before_date,
+        after_date: query.after_date,
+        app_session_id: query.app_session_id,
+    };
+
+    market
+        .export_agreements(&id, filter)
+        .await
+        .log_err()
+        .map(|export| match query.format {
+            ExportFormat::Json => HttpResponse::Ok().json(export),
+            ExportFormat::Csv => HttpResponse::Ok()
+                .content_type("text/csv")
+                .body(export::to_csv(&export.agreements)),
+        })
+}
+
 #[actix_web::get("/agreements/{agreement_id}")]
 async fn get_agreement(
     market: Data<Arc<MarketService>>,
@@ -69,6 +111,28 @@ async fn get_agreement(
     }
 }
 
+#[actix_web::get("/agreements/{agreement_id}/amendments")]
+async fn list_amendments(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathAgreement>,
+    id: Identity,
+) -> impl Responder {
+    let agreement_id = path.into_inner().agreement_id;
+    market
+        .requestor_engine
+        .common
+        .list_amendments(&id, &agreement_id)
+        .await
+        .and_then(|amendments| {
+            amendments
+                .into_iter()
+                .map(Amendment::try_from)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .log_err()
+        .map(|amendments| HttpResponse::Ok().json(amendments))
+}
+
 #[actix_web::get("/agreementEvents")]
 async fn collect_agreement_events(
     market: Data<Arc<MarketService>>,
@@ -93,6 +157,43 @@ async fn collect_agreement_events(
         .map(|events| HttpResponse::Ok().json(events))
 }
 
+#[actix_web::get("/agreementEvents/stream")]
+async fn stream_agreement_events(
+    market: Data<Arc<MarketService>>,
+    query: Query<QueryAgreementEventsStream>,
+    id: Identity,
+) -> impl Responder {
+    let after_timestamp = query
+        .after_timestamp
+        .unwrap_or_else(|| Utc.ymd(2016, 11, 11).and_hms(15, 12, 0));
+    let keepalive = market.config.events.stream_keepalive_interval;
+    let events = market.stream_agreement_events(&query.app_session_id, after_timestamp, &id);
+    event_stream(events, "agreement", keepalive)
+}
+
+#[actix_web::get("/amendmentEvents")]
+async fn collect_amendment_events(
+    market: Data<Arc<MarketService>>,
+    query: Query<QueryAgreementEvents>,
+    id: Identity,
+) -> impl Responder {
+    let after_timestamp = query
+        .after_timestamp
+        .unwrap_or_else(|| Utc.ymd(2016, 11, 11).and_hms(15, 12, 0));
+
+    market
+        .query_amendment_events(
+            &query.app_session_id,
+            query.timeout,
+            query.max_events,
+            after_timestamp,
+            &id,
+        )
+        .await
+        .log_err()
+        .map(|events| HttpResponse::Ok().json(events))
+}
+
 #[actix_web::post("/agreements/{agreement_id}/terminate")]
 async fn terminate_agreement(
     market: Data<Arc<MarketService>>,
@@ -107,3 +208,59 @@ async fn terminate_agreement(
         .log_err()
         .map(|_| HttpResponse::Ok().finish())
 }
+
+#[actix_web::get("/offers/query")]
+async fn query_offers(
+    market: Data<Arc<MarketService>>,
+    query: Query<QueryOffers>,
+    _id: Identity,
+) -> impl Responder {
+    let query = query.into_inner();
+    market
+        .query_offers(
+            query.constraints.as_deref(),
+            query.sort_by,
+            query.cursor.as_deref(),
+            query.limit,
+        )
+        .await
+        .log_err()
+        .map(|page| HttpResponse::Ok().json(page))
+}
+
+#[actix_web::get("/blocklist")]
+async fn list_blocked_nodes(market: Data<Arc<MarketService>>, _id: Identity) -> impl Responder {
+    market
+        .list_blocked_nodes()
+        .await
+        .log_err()
+        .map(|nodes| HttpResponse::Ok().json(nodes))
+}
+
+#[actix_web::put("/blocklist/{node_id}")]
+async fn block_node(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathNode>,
+    body: Json<Option<BlockNodeReason>>,
+    _id: Identity,
+) -> impl Responder {
+    let reason = body.into_inner().unwrap_or_default().reason;
+    market
+        .block_node(path.into_inner().node_id, reason)
+        .await
+        .log_err()
+        .map(|node| HttpResponse::Ok().json(node))
+}
+
+#[actix_web::delete("/blocklist/{node_id}")]
+async fn unblock_node(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathNode>,
+    _id: Identity,
+) -> impl Responder {
+    market
+        .unblock_node(path.into_inner().node_id)
+        .await
+        .log_err()
+        .map(|_| HttpResponse::NoContent())
+}
@@ -10,12 +10,12 @@ use crate::{
     db::dao::TakeEventsError,
     market::MarketError,
     matcher::error::{
-        DemandError, MatcherError, ModifyOfferError, QueryDemandsError, QueryOfferError,
-        QueryOffersError, ResolverError, SaveOfferError,
+        BlocklistError, DemandError, MatcherError, ModifyOfferError, QueryDemandsError,
+        QueryOfferError, QueryOffersError, ResolverError, SaveOfferError,
     },
     negotiation::error::{
-        AgreementError, GetProposalError, NegotiationError, ProposalError, QueryEventsError,
-        WaitForApprovalError,
+        AgreementError, AmendmentError, BulkAgreementError, GetProposalError, NegotiationError,
+        ProposalError, QueryEventsError, WaitForApprovalError,
     },
 };
 
@@ -34,6 +34,7 @@ impl ResponseError for MarketError {
             MarketError::QueryOffersError(e) => e.error_response(),
             MarketError::DemandError(e) => e.error_response(),
             MarketError::Negotiation(e) => e.error_response(),
+            MarketError::Blocklist(e) => e.error_response(),
         }
     }
 }
@@ -46,6 +47,23 @@ impl ResponseError for MatcherError {
             MatcherError::QueryOffer(e) => e.error_response(),
             MatcherError::SaveOffer(e) => e.error_response(),
             MatcherError::ModifyOffer(e) => e.error_response(),
+            MatcherError::Explain(_) => {
+                HttpResponse::InternalServerError().json(ErrorMessage::new(self.to_string()))
+            }
+            MatcherError::Schema(_) => {
+                HttpResponse::BadRequest().json(ErrorMessage::new(self.to_string()))
+            }
+            MatcherError::Blocklist(e) => e.error_response(),
+        }
+    }
+}
+
+impl ResponseError for BlocklistError {
+    fn error_response(&self) -> HttpResponse {
+        let msg = ErrorMessage::new(self.to_string());
+        match self {
+            BlocklistError::NotBlocked(_) => HttpResponse::NotFound().json(msg),
+            BlocklistError::Db(_) => HttpResponse::InternalServerError().json(msg),
         }
     }
 }
@@ -77,7 +95,13 @@ impl ResponseError for QueryDemandsError {
 
 impl ResponseError for QueryOffersError {
     fn error_response(&self) -> HttpResponse {
-        HttpResponse::InternalServerError().json(ErrorMessage::new(self.to_string()))
+        let msg = ErrorMessage::new(self.to_string());
+        match self {
+            QueryOffersError::InvalidConstraints(_)
+            | QueryOffersError::InvalidCursor(_)
+            | QueryOffersError::InvalidLimit(..) => HttpResponse::BadRequest().json(msg),
+            _ => HttpResponse::InternalServerError().json(msg),
+        }
     }
 }
 
@@ -171,6 +195,7 @@ impl ResponseError for ProposalValidationError {
             | ProposalValidationError::OwnProposal(_) => HttpResponse::BadRequest().json(msg),
             ProposalValidationError::SubscriptionExpired(_) => HttpResponse::Gone().json(msg),
             ProposalValidationError::Unauthorized(_, _) => HttpResponse::Unauthorized().json(msg),
+            ProposalValidationError::Blocked(_) => HttpResponse::Forbidden().json(msg),
             ProposalValidationError::Internal(_) => HttpResponse::InternalServerError().json(msg),
         }
     }
@@ -215,6 +240,33 @@ impl ResponseError for AgreementError {
     }
 }
 
+impl ResponseError for BulkAgreementError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::BadRequest().json(ErrorMessage::new(self.to_string()))
+    }
+}
+
+impl ResponseError for AmendmentError {
+    fn error_response(&self) -> HttpResponse {
+        let msg = ErrorMessage::new(self.to_string());
+        match self {
+            AmendmentError::AgreementNotFound(_) | AmendmentError::NotFound(_) => {
+                HttpResponse::NotFound().json(msg)
+            }
+            AmendmentError::InvalidState(..)
+            | AmendmentError::PendingExists(..)
+            | AmendmentError::AlreadyResolved(..) => HttpResponse::Conflict().json(msg),
+            AmendmentError::InvalidValidTo(_) | AmendmentError::InvalidProperties(_) => {
+                HttpResponse::BadRequest().json(msg)
+            }
+            AmendmentError::Timeout(_) => HttpResponse::RequestTimeout().json(msg),
+            AmendmentError::Protocol(_) | AmendmentError::Get(..) | AmendmentError::Internal(_) => {
+                HttpResponse::InternalServerError().json(msg)
+            }
+        }
+    }
+}
+
 impl ResponseError for AgreementDaoError {
     fn error_response(&self) -> HttpResponse {
         let msg = ErrorMessage::new(self.to_string());
@@ -9,7 +9,10 @@ use ya_std_utils::LogErr;
 use crate::db::model::Owner;
 use crate::market::MarketService;
 
-use super::{PathAgreement, PathSubscription, PathSubscriptionProposal, QueryTimeoutMaxEvents};
+use super::{
+    event_stream, subscribed, PathAgreement, PathAmendment, PathSubscription,
+    PathSubscriptionProposal, QueryTimeoutMaxEvents,
+};
 use crate::negotiation::ApprovalResult;
 use crate::rest_api::QueryTimeoutAppSessionId;
 use ya_client::model::ErrorMessage;
@@ -20,11 +23,14 @@ pub fn register_endpoints(scope: Scope) -> Scope {
         .service(get_offers)
         .service(unsubscribe)
         .service(collect)
+        .service(stream_events)
         .service(counter_proposal)
         .service(get_proposal)
         .service(reject_proposal)
         .service(approve_agreement)
         .service(reject_agreement)
+        .service(approve_amendment)
+        .service(reject_amendment)
 }
 
 #[actix_web::post("/offers")]
@@ -33,11 +39,15 @@ async fn subscribe(
     body: Json<NewOffer>,
     id: Identity,
 ) -> impl Responder {
+    let offer = body.into_inner();
+    let violations = market
+        .validate_schema(&offer.properties, &offer.constraints)
+        .log_err()?;
     market
-        .subscribe_offer(&body.into_inner(), &id)
+        .subscribe_offer(&offer, &id)
         .await
         .log_err()
-        .map(|id| HttpResponse::Created().json(id))
+        .map(|id| subscribed(&violations).json(id))
 }
 
 #[actix_web::get("/offers")]
@@ -80,6 +90,22 @@ async fn collect(
         .map(|events| HttpResponse::Ok().json(events))
 }
 
+#[actix_web::get("/offers/{subscription_id}/events/stream")]
+async fn stream_events(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathSubscription>,
+    _id: Identity,
+) -> impl Responder {
+    let subscription_id = path.into_inner().subscription_id;
+    let keepalive = market.config.events.stream_keepalive_interval;
+    market
+        .provider_engine
+        .stream_events(&subscription_id)
+        .await
+        .log_err()
+        .map(|events| event_stream(events, "provider", keepalive))
+}
+
 #[actix_web::post("/offers/{subscription_id}/proposals/{proposal_id}")]
 async fn counter_proposal(
     market: Data<Arc<MarketService>>,
@@ -176,3 +202,39 @@ async fn reject_agreement(
         .log_err()
         .map(|_| HttpResponse::Ok().finish())
 }
+
+#[actix_web::post("/agreements/{agreement_id}/amendments/{amendment_id}/approve")]
+async fn approve_amendment(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathAmendment>,
+    id: Identity,
+) -> impl Responder {
+    let path = path.into_inner();
+    market
+        .provider_engine
+        .approve_amendment(&id, &path.agreement_id, &path.amendment_id)
+        .await
+        .log_err()
+        .map(|_| HttpResponse::NoContent().finish())
+}
+
+#[actix_web::post("/agreements/{agreement_id}/amendments/{amendment_id}/reject")]
+async fn reject_amendment(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathAmendment>,
+    id: Identity,
+    body: Json<Option<Reason>>,
+) -> impl Responder {
+    let path = path.into_inner();
+    market
+        .provider_engine
+        .reject_amendment(
+            &id,
+            &path.agreement_id,
+            &path.amendment_id,
+            body.into_inner(),
+        )
+        .await
+        .log_err()
+        .map(|_| HttpResponse::Ok().finish())
+}
@@ -1,5 +1,6 @@
 use actix_web::web::{Data, Json, Path, Query};
 use actix_web::{HttpResponse, Responder, Scope};
+use std::convert::TryFrom;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -8,12 +9,13 @@ use ya_client::model::ErrorMessage;
 use ya_service_api_web::middleware::Identity;
 use ya_std_utils::LogErr;
 
-use crate::db::model::Owner;
+use crate::db::model::{AmendmentState, Owner};
 use crate::market::MarketService;
 
 use super::{
-    PathAgreement, PathSubscription, PathSubscriptionProposal, ProposalId, QueryTimeout,
-    QueryTimeoutMaxEvents,
+    event_stream, subscribed, Amendment, BulkAgreementProposals, BulkAgreementResult, NewAmendment,
+    PathAgreement, PathAmendment, PathSubscription, PathSubscriptionOffer,
+    PathSubscriptionProposal, ProposalId, QueryRequestorEvents, QueryTimeout,
 };
 use crate::negotiation::ApprovalStatus;
 use crate::rest_api::QueryAppSessionId;
@@ -24,13 +26,18 @@ pub fn register_endpoints(scope: Scope) -> Scope {
         .service(get_demands)
         .service(unsubscribe)
         .service(collect)
+        .service(stream_events)
+        .service(explain_match)
         .service(counter_proposal)
         .service(get_proposal)
         .service(reject_proposal)
         .service(create_agreement)
+        .service(create_agreements)
         .service(confirm_agreement)
         .service(wait_for_approval)
         .service(cancel_agreement)
+        .service(propose_amendment)
+        .service(wait_for_amendment)
 }
 
 #[actix_web::post("/demands")]
@@ -39,11 +46,15 @@ async fn subscribe(
     body: Json<NewDemand>,
     id: Identity,
 ) -> impl Responder {
+    let demand = body.into_inner();
+    let violations = market
+        .validate_schema(&demand.properties, &demand.constraints)
+        .log_err()?;
     market
-        .subscribe_demand(&body.into_inner(), &id)
+        .subscribe_demand(&demand, &id)
         .await
         .log_err()
-        .map(|id| HttpResponse::Created().json(id))
+        .map(|id| subscribed(&violations).json(id))
 }
 
 #[actix_web::get("/demands")]
@@ -73,7 +84,7 @@ async fn unsubscribe(
 async fn collect(
     market: Data<Arc<MarketService>>,
     path: Path<PathSubscription>,
-    query: Query<QueryTimeoutMaxEvents>,
+    query: Query<QueryRequestorEvents>,
     _id: Identity, // TODO: use it
 ) -> impl Responder {
     let subscription_id = path.into_inner().subscription_id;
@@ -81,12 +92,45 @@ async fn collect(
     let max_events = query.max_events;
     market
         .requestor_engine
-        .query_events(&subscription_id, timeout, max_events)
+        .query_scored_events(&subscription_id, timeout, max_events, query.order_by_score)
         .await
         .log_err()
         .map(|events| HttpResponse::Ok().json(events))
 }
 
+#[actix_web::get("/demands/{subscription_id}/events/stream")]
+async fn stream_events(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathSubscription>,
+    _id: Identity,
+) -> impl Responder {
+    let subscription_id = path.into_inner().subscription_id;
+    let keepalive = market.config.events.stream_keepalive_interval;
+    market
+        .requestor_engine
+        .stream_scored_events(&subscription_id)
+        .await
+        .log_err()
+        .map(|events| event_stream(events, "requestor", keepalive))
+}
+
+#[actix_web::get("/demands/{subscription_id}/offers/{offer_id}/explain")]
+async fn explain_match(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathSubscriptionOffer>,
+    id: Identity,
+) -> impl Responder {
+    let PathSubscriptionOffer {
+        subscription_id,
+        offer_id,
+    } = path.into_inner();
+    market
+        .explain_match(&subscription_id, &offer_id, &id)
+        .await
+        .log_err()
+        .map(|explanation| HttpResponse::Ok().json(explanation))
+}
+
 #[actix_web::post("/demands/{subscription_id}/proposals/{proposal_id}")]
 async fn counter_proposal(
     market: Data<Arc<MarketService>>,
@@ -163,6 +207,38 @@ async fn create_agreement(
         .map(|agreement_id| HttpResponse::Ok().json(agreement_id.into_client()))
 }
 
+#[actix_web::post("/agreements/bulk")]
+async fn create_agreements(
+    market: Data<Arc<MarketService>>,
+    body: Json<BulkAgreementProposals>,
+    id: Identity,
+) -> impl Responder {
+    let body = body.into_inner();
+    let proposal_ids = body
+        .proposal_ids
+        .iter()
+        .map(|proposal_id| ProposalId::from_str(proposal_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    market
+        .requestor_engine
+        .create_agreements(
+            id,
+            proposal_ids,
+            body.valid_to,
+            body.app_session_id,
+            body.approval_timeout,
+        )
+        .await
+        .log_err()
+        .map(|outcomes| {
+            let results = outcomes
+                .into_iter()
+                .map(BulkAgreementResult::from)
+                .collect::<Vec<_>>();
+            HttpResponse::Ok().json(results)
+        })
+}
+
 #[actix_web::post("/agreements/{agreement_id}/confirm")]
 async fn confirm_agreement(
     market: Data<Arc<MarketService>>,
@@ -214,3 +290,49 @@ async fn cancel_agreement(
         .log_err()
         .map(|_| HttpResponse::Ok().finish())
 }
+
+#[actix_web::post("/agreements/{agreement_id}/amendments")]
+async fn propose_amendment(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathAgreement>,
+    body: Json<NewAmendment>,
+    id: Identity,
+) -> impl Responder {
+    let agreement_id = path.into_inner().agreement_id;
+    let amendment = body.into_inner();
+    market
+        .requestor_engine
+        .propose_amendment(
+            &id,
+            &agreement_id,
+            amendment.valid_to,
+            amendment.demand_properties,
+            amendment.offer_properties,
+        )
+        .await
+        .and_then(Amendment::try_from)
+        .log_err()
+        .map(|amendment| HttpResponse::Created().json(amendment))
+}
+
+#[actix_web::post("/agreements/{agreement_id}/amendments/{amendment_id}/wait")]
+async fn wait_for_amendment(
+    market: Data<Arc<MarketService>>,
+    path: Path<PathAmendment>,
+    query: Query<QueryTimeout>,
+    id: Identity,
+) -> impl Responder {
+    let path = path.into_inner();
+    market
+        .requestor_engine
+        .wait_for_amendment(&id, &path.agreement_id, &path.amendment_id, query.timeout)
+        .await
+        .log_err()
+        .map(|amendment| match amendment.state {
+            AmendmentState::Rejected => HttpResponse::Gone().json(ErrorMessage::new(format!(
+                "Amendment [{}] rejected.",
+                amendment.id
+            ))),
+            _ => HttpResponse::NoContent().finish(),
+        })
+}
@@ -5,9 +5,11 @@
 pub use super::config::*;
 pub use super::db::dao::*;
 pub use super::db::model::*;
-pub use super::matcher::{error::*, *};
+pub use super::market::export;
+pub use super::matcher::{error::*, store::SubscriptionStore, *};
 pub use super::negotiation::{error::*, *};
 pub use super::protocol::*;
+pub use super::rest_api::{AmendmentEvent, AmendmentEventType};
 
 pub mod agreement_utils;
 pub mod backtrace_util;
@@ -20,6 +22,7 @@ pub mod mock_net;
 pub mod mock_node;
 pub mod mock_offer;
 pub mod proposal_util;
+pub mod simulation;
 
 pub use mock_node::{MarketServiceExt, MarketsNetwork};
 pub use mock_offer::{client, sample_demand, sample_offer};
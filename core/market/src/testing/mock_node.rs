@@ -122,19 +122,22 @@ impl MarketsNetwork {
     pub async fn new(test_name: Option<&str>) -> Self {
         std::env::set_var("RUST_LOG", "debug");
         let _ = env_logger::builder().try_init();
-        // level 1 is this function.
-        // level 2 is <core::future::from_generator::GenFuture<T> as
-        // core::future::future::Future>::poll::XXX> (async)
-        // We want to know the caller.
-        let mut bn = crate::testing::backtrace_util::generate_backtraced_name(Some(3));
-        // Special case for mac&windows. Tests are run in adifferent way on those systems and we
-        // have to dive one less level down the stack to find the caller (test_* module).
-        if !bn.starts_with("test_") {
-            bn = crate::testing::backtrace_util::generate_backtraced_name(Some(2));
-        }
-        bn = testname_from_backtrace(&bn);
-
-        let test_name = test_name.unwrap_or(&bn).to_string();
+        let test_name = match test_name {
+            Some(test_name) => test_name.to_string(),
+            None => {
+                // level 1 is this function.
+                // level 2 is <core::future::from_generator::GenFuture<T> as
+                // core::future::future::Future>::poll::XXX> (async)
+                // We want to know the caller.
+                let mut bn = crate::testing::backtrace_util::generate_backtraced_name(Some(3));
+                // Special case for mac&windows. Tests are run in adifferent way on those systems
+                // and we have to dive one less level down the stack to find the caller (test_* module).
+                if !bn.starts_with("test_") {
+                    bn = crate::testing::backtrace_util::generate_backtraced_name(Some(2));
+                }
+                testname_from_backtrace(&bn)
+            }
+        };
         log::info!("Intializing MarketsNetwork. tn={}", test_name);
 
         MockNet::default().bind_gsb();
@@ -321,6 +324,7 @@ impl MarketsNetwork {
             prov_agreement_cancelled,
             prov_agreement_terminated,
             prov_agreement_committed,
+            default::empty_on_amendment_proposed,
         );
 
         let requestor = requestor::NegotiationApi::new(
@@ -329,6 +333,8 @@ impl MarketsNetwork {
             req_agreement_approved,
             req_agreement_rejected,
             req_agreement_terminated,
+            default::empty_on_amendment_approved,
+            default::empty_on_amendment_rejected,
         );
 
         let identity_api = MockIdentity::new(name);
@@ -594,8 +600,8 @@ impl MarketServiceExt for MarketService {
 pub mod default {
     use super::*;
     use crate::protocol::negotiation::error::{
-        AgreementProtocolError, CommitAgreementError, CounterProposalError, ProposeAgreementError,
-        RejectProposalError, TerminateAgreementError,
+        AgreementProtocolError, AmendAgreementError, CommitAgreementError, CounterProposalError,
+        ProposeAgreementError, RejectProposalError, TerminateAgreementError,
     };
 
     pub async fn empty_on_offers_retrieved(
@@ -688,6 +694,27 @@ pub mod default {
     ) -> Result<(), TerminateAgreementError> {
         Ok(())
     }
+
+    pub async fn empty_on_amendment_proposed(
+        _caller: String,
+        _msg: AmendmentProposed,
+    ) -> Result<(), AmendAgreementError> {
+        Ok(())
+    }
+
+    pub async fn empty_on_amendment_approved(
+        _caller: String,
+        _msg: AmendmentApproved,
+    ) -> Result<(), AmendAgreementError> {
+        Ok(())
+    }
+
+    pub async fn empty_on_amendment_rejected(
+        _caller: String,
+        _msg: AmendmentRejected,
+    ) -> Result<(), AmendAgreementError> {
+        Ok(())
+    }
 }
 
 pub fn create_market_config_for_test() -> Config {
@@ -699,6 +726,10 @@ pub fn create_market_config_for_test() -> Config {
         mean_cyclic_unsubscribes_interval: Duration::from_millis(200),
         offer_broadcast_delay: Duration::from_millis(200),
         unsub_broadcast_delay: Duration::from_millis(200),
+        max_incoming_bcasts_per_node: 0,
+        max_offer_retrievals_per_node: 0,
+        rate_limit_window: Duration::from_secs(60),
+        max_offers_per_node: 0,
     };
 
     let mut cfg = Config::from_env().unwrap();
@@ -37,6 +37,7 @@ pub fn generate_proposal(
         state: ProposalState::Initial,
         creation_ts: Utc::now().naive_utc(),
         expiration_ts,
+        score: None,
     }
 }
 
@@ -0,0 +1,443 @@
+//! Offline market simulation built on `MarketsNetwork`.
+//!
+//! Scenario describes groups of Providers and Requestors, which are started as
+//! separate Market instances communicating over mocked GSB. Requestors subscribe
+//! Demands, Providers subscribe Offers and both sides negotiate received Proposals
+//! according to scenario. Report summarizes how fast Offers were matched and how
+//! many Proposals were exchanged, so different market configurations can be compared
+//! without running live network.
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ya_client::model::market::event::{ProviderEvent, RequestorEvent};
+use ya_client::model::market::{NewDemand, NewOffer, Proposal};
+use ya_client::model::NodeId;
+use ya_service_api_web::middleware::Identity;
+
+use crate::config::Config;
+use crate::db::model::{ProposalId, SubscriptionId};
+use crate::testing::mock_node::create_market_config_for_test;
+use crate::testing::MarketsNetwork;
+use crate::MarketService;
+
+/// Timeout of single events query. Loops check simulation end after each query.
+const QUERY_TIMEOUT: f32 = 0.5;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default)]
+    pub config: ScenarioConfig,
+    /// Simulation ends after this time, even if Requestors didn't get all
+    /// expected Proposals.
+    #[serde(deserialize_with = "deserialize_duration")]
+    pub timeout: Duration,
+    pub providers: Vec<ProviderGroup>,
+    pub requestors: Vec<RequestorGroup>,
+}
+
+/// Overrides of market configuration. Values not set here are taken
+/// from the same configuration, that is used in tests.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioConfig {
+    pub max_bcasted_offers: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_opt_duration")]
+    pub mean_cyclic_bcast_interval: Option<Duration>,
+    #[serde(default, deserialize_with = "deserialize_opt_duration")]
+    pub offer_broadcast_delay: Option<Duration>,
+    #[serde(default, deserialize_with = "deserialize_opt_duration")]
+    pub subscription_ttl: Option<Duration>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderGroup {
+    pub name: String,
+    pub count: usize,
+    pub offer: NewOffer,
+    /// Counter Proposals received from Requestors with the same Offer.
+    #[serde(default)]
+    pub respond: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestorGroup {
+    pub name: String,
+    pub count: usize,
+    pub demand: NewDemand,
+    /// Counter initial Proposals with the same Demand.
+    #[serde(default)]
+    pub negotiate: bool,
+    /// Number of initial Proposals each Requestor should receive. Requestor finishes
+    /// after receiving them and responses to all his counter Proposals.
+    /// If not set, Requestor waits until scenario timeout.
+    pub expected_proposals: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyStats {
+    pub count: usize,
+    pub min_ms: Option<u64>,
+    pub avg_ms: Option<u64>,
+    pub p95_ms: Option<u64>,
+    pub max_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationReport {
+    pub scenario: String,
+    pub providers: usize,
+    pub requestors: usize,
+    pub duration_ms: u64,
+    /// True if all Requestors received expected Proposals before timeout.
+    pub completed: bool,
+    pub initial_proposals: usize,
+    pub requestor_counter_proposals: usize,
+    pub provider_received_proposals: usize,
+    pub provider_counter_proposals: usize,
+    pub requestor_received_counters: usize,
+    /// Time between Offer subscription and initial Proposal at Requestor.
+    pub matching_latency: LatencyStats,
+    /// Time between sending counter Proposal and receiving it on the other side.
+    pub negotiation_latency: LatencyStats,
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Scenario> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Reading scenario {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Parsing scenario {}", path.display()))
+    }
+
+    pub fn market_config(&self) -> Result<Config> {
+        let mut config = create_market_config_for_test();
+        let overrides = &self.config;
+        if let Some(max_bcasted_offers) = overrides.max_bcasted_offers {
+            config.discovery.max_bcasted_offers = max_bcasted_offers;
+        }
+        if let Some(interval) = overrides.mean_cyclic_bcast_interval {
+            config.discovery.mean_cyclic_bcast_interval = interval;
+        }
+        if let Some(delay) = overrides.offer_broadcast_delay {
+            config.discovery.offer_broadcast_delay = delay;
+        }
+        if let Some(ttl) = overrides.subscription_ttl {
+            config.subscription.default_ttl = chrono::Duration::from_std(ttl)?;
+        }
+        Ok(config)
+    }
+}
+
+struct SimNode {
+    market: Arc<MarketService>,
+    id: Identity,
+}
+
+/// State shared between all simulated nodes.
+#[derive(Default)]
+struct Shared {
+    offers_subscribed: HashMap<NodeId, Instant>,
+    responding_providers: HashSet<NodeId>,
+    counters_sent: Mutex<HashMap<String, Instant>>,
+    matching: Mutex<Vec<Duration>>,
+    negotiation: Mutex<Vec<Duration>>,
+    requestors_finished: AtomicUsize,
+    initial_proposals: AtomicUsize,
+    requestor_counters: AtomicUsize,
+    provider_received: AtomicUsize,
+    provider_counters: AtomicUsize,
+    requestor_received: AtomicUsize,
+}
+
+impl Shared {
+    fn counter_sent(&self, proposal_id: &ProposalId) {
+        self.counters_sent
+            .lock()
+            .unwrap()
+            .insert(proposal_id.into_client(), Instant::now());
+    }
+
+    fn counter_received(&self, proposal: &Proposal) {
+        let sent = ProposalId::from_str(&proposal.proposal_id)
+            .ok()
+            .and_then(|id| self.counters_sent.lock().unwrap().remove(&id.into_client()));
+        if let Some(sent) = sent {
+            self.negotiation.lock().unwrap().push(sent.elapsed());
+        }
+    }
+}
+
+/// Runs scenario and returns report. Markets are created in `name` subdirectory
+/// of market tests working directory.
+pub async fn run(scenario: &Scenario) -> Result<SimulationReport> {
+    let config = Arc::new(scenario.market_config()?);
+    let mut network = MarketsNetwork::new(Some(&format!("simulation.{}", scenario.name)))
+        .await
+        .with_config(config);
+
+    let mut names = Vec::new();
+    for group in &scenario.requestors {
+        for i in 0..group.count {
+            names.push(format!("{}-{}", group.name, i));
+        }
+    }
+    for group in &scenario.providers {
+        for i in 0..group.count {
+            names.push(format!("{}-{}", group.name, i));
+        }
+    }
+    for name in &names {
+        network = network.add_market_instance(name).await;
+    }
+    let node = |name: String| SimNode {
+        market: network.get_market(&name),
+        id: network.get_default_id(&name),
+    };
+
+    let mut shared = Shared::default();
+    let started = Instant::now();
+
+    let mut requestors = Vec::new();
+    for group in &scenario.requestors {
+        for i in 0..group.count {
+            let node = node(format!("{}-{}", group.name, i));
+            let demand_id = node
+                .market
+                .subscribe_demand(&group.demand, &node.id)
+                .await?;
+            requestors.push((node, demand_id, group));
+        }
+    }
+
+    let mut providers = Vec::new();
+    for group in &scenario.providers {
+        for i in 0..group.count {
+            let node = node(format!("{}-{}", group.name, i));
+            let offer_id = node.market.subscribe_offer(&group.offer, &node.id).await?;
+            shared
+                .offers_subscribed
+                .insert(node.id.identity, Instant::now());
+            if group.respond {
+                shared.responding_providers.insert(node.id.identity);
+            }
+            providers.push((node, offer_id, group));
+        }
+    }
+
+    log::info!(
+        "Simulation [{}] started with {} Requestors and {} Providers.",
+        scenario.name,
+        requestors.len(),
+        providers.len()
+    );
+
+    let deadline = started + scenario.timeout;
+    let shared = &shared;
+    let requestors_count = requestors.len();
+    let requestor_loops = requestors
+        .iter()
+        .map(|(node, demand_id, group)| run_requestor(node, demand_id, group, shared, deadline));
+    let provider_loops = providers.iter().map(|(node, offer_id, group)| {
+        run_provider(node, offer_id, group, shared, deadline, requestors_count)
+    });
+
+    let (requestor_results, provider_results) = futures::future::join(
+        futures::future::join_all(requestor_loops),
+        futures::future::join_all(provider_loops),
+    )
+    .await;
+    requestor_results
+        .into_iter()
+        .chain(provider_results)
+        .collect::<Result<Vec<_>>>()?;
+
+    let matching = shared.matching.lock().unwrap().clone();
+    let negotiation = shared.negotiation.lock().unwrap().clone();
+    Ok(SimulationReport {
+        scenario: scenario.name.clone(),
+        providers: providers.len(),
+        requestors: requestors_count,
+        duration_ms: started.elapsed().as_millis() as u64,
+        completed: shared.requestors_finished.load(Ordering::SeqCst) == requestors_count,
+        initial_proposals: shared.initial_proposals.load(Ordering::SeqCst),
+        requestor_counter_proposals: shared.requestor_counters.load(Ordering::SeqCst),
+        provider_received_proposals: shared.provider_received.load(Ordering::SeqCst),
+        provider_counter_proposals: shared.provider_counters.load(Ordering::SeqCst),
+        requestor_received_counters: shared.requestor_received.load(Ordering::SeqCst),
+        matching_latency: LatencyStats::new(matching),
+        negotiation_latency: LatencyStats::new(negotiation),
+    })
+}
+
+async fn run_requestor(
+    node: &SimNode,
+    demand_id: &SubscriptionId,
+    group: &RequestorGroup,
+    shared: &Shared,
+    deadline: Instant,
+) -> Result<()> {
+    let mut initial = 0;
+    let mut awaiting_response = HashSet::new();
+
+    while Instant::now() < deadline {
+        let events = node
+            .market
+            .requestor_engine
+            .query_events(demand_id, QUERY_TIMEOUT, None)
+            .await?;
+
+        for event in events {
+            let proposal = match event {
+                RequestorEvent::ProposalEvent { proposal, .. } => proposal,
+                _ => continue,
+            };
+
+            if proposal.prev_proposal_id.is_some() {
+                shared.requestor_received.fetch_add(1, Ordering::SeqCst);
+                shared.counter_received(&proposal);
+                awaiting_response.remove(&proposal.issuer_id);
+                continue;
+            }
+
+            initial += 1;
+            shared.initial_proposals.fetch_add(1, Ordering::SeqCst);
+            if let Some(subscribed) = shared.offers_subscribed.get(&proposal.issuer_id) {
+                shared.matching.lock().unwrap().push(subscribed.elapsed());
+            }
+
+            if group.negotiate {
+                let proposal_id = node
+                    .market
+                    .requestor_engine
+                    .counter_proposal(
+                        demand_id,
+                        &ProposalId::from_str(&proposal.proposal_id)?,
+                        &group.demand,
+                        &node.id,
+                    )
+                    .await?;
+                shared.counter_sent(&proposal_id);
+                shared.requestor_counters.fetch_add(1, Ordering::SeqCst);
+                if shared.responding_providers.contains(&proposal.issuer_id) {
+                    awaiting_response.insert(proposal.issuer_id);
+                }
+            }
+        }
+
+        let expected = match group.expected_proposals {
+            Some(expected) => expected,
+            None => continue,
+        };
+        if initial >= expected && awaiting_response.is_empty() {
+            shared.requestors_finished.fetch_add(1, Ordering::SeqCst);
+            break;
+        }
+    }
+    Ok(())
+}
+
+async fn run_provider(
+    node: &SimNode,
+    offer_id: &SubscriptionId,
+    group: &ProviderGroup,
+    shared: &Shared,
+    deadline: Instant,
+    requestors_count: usize,
+) -> Result<()> {
+    while Instant::now() < deadline
+        && shared.requestors_finished.load(Ordering::SeqCst) < requestors_count
+    {
+        let events = node
+            .market
+            .provider_engine
+            .query_events(offer_id, QUERY_TIMEOUT, None)
+            .await?;
+
+        for event in events {
+            let proposal = match event {
+                ProviderEvent::ProposalEvent { proposal, .. } => proposal,
+                _ => continue,
+            };
+
+            shared.provider_received.fetch_add(1, Ordering::SeqCst);
+            shared.counter_received(&proposal);
+
+            if group.respond {
+                let proposal_id = node
+                    .market
+                    .provider_engine
+                    .counter_proposal(
+                        offer_id,
+                        &ProposalId::from_str(&proposal.proposal_id)?,
+                        &group.offer,
+                        &node.id,
+                    )
+                    .await?;
+                shared.counter_sent(&proposal_id);
+                shared.provider_counters.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+    Ok(())
+}
+
+impl LatencyStats {
+    fn new(mut samples: Vec<Duration>) -> LatencyStats {
+        if samples.is_empty() {
+            return LatencyStats::default();
+        }
+        samples.sort();
+
+        let millis = |duration: &Duration| duration.as_millis() as u64;
+        let sum: u64 = samples.iter().map(millis).sum();
+        let p95 = ((samples.len() * 95 + 99) / 100).max(1) - 1;
+        LatencyStats {
+            count: samples.len(),
+            min_ms: samples.first().map(millis),
+            avg_ms: Some(sum / samples.len() as u64),
+            p95_ms: samples.get(p95).map(millis),
+            max_ms: samples.last().map(millis),
+        }
+    }
+}
+
+fn deserialize_duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    humantime::parse_duration(&text).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_opt_duration<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    deserialize_duration(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_stats() {
+        let samples = (1..=20).map(Duration::from_millis).collect();
+        let stats = LatencyStats::new(samples);
+        assert_eq!(stats.count, 20);
+        assert_eq!(stats.min_ms, Some(1));
+        assert_eq!(stats.avg_ms, Some(10));
+        assert_eq!(stats.p95_ms, Some(19));
+        assert_eq!(stats.max_ms, Some(20));
+
+        assert_eq!(LatencyStats::new(vec![]), LatencyStats::default());
+    }
+}
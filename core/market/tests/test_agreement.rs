@@ -1,18 +1,21 @@
 use actix_web::{http::StatusCode, web::Bytes};
 use chrono::{Duration, Utc};
+use serde_json::json;
 
-use ya_client::model::market::Role;
+use ya_client::model::market::{agreement::State as ClientState, Role};
 use ya_core_model::market;
 use ya_market::assert_err_eq;
 use ya_market::testing::{
     agreement_utils::{gen_reason, negotiate_agreement},
     client::{sample_demand, sample_offer},
     events_helper::*,
+    export,
     mock_agreement::generate_agreement,
     mock_node::MarketServiceExt,
-    proposal_util::{exchange_draft_proposals, NegotiationHelper},
-    AgreementDao, AgreementDaoError, AgreementError, AgreementState, ApprovalStatus,
-    MarketsNetwork, Owner, ProposalState, WaitForApprovalError,
+    proposal_util::{exchange_draft_proposals, exchange_proposals_exclusive, NegotiationHelper},
+    AgreementDao, AgreementDaoError, AgreementError, AgreementState, AmendmentError,
+    AmendmentEventType, AmendmentState, ApprovalStatus, BulkAgreementError, MarketsNetwork, Owner,
+    ProposalState, WaitForApprovalError,
 };
 use ya_service_bus::{typed as bus, RpcEndpoint};
 
@@ -256,6 +259,82 @@ async fn full_market_interaction_aka_happy_path() {
         .unwrap();
 }
 
+/// Agreements for multiple Proposals can be created and confirmed in single call.
+/// Failure of one of them, doesn't affect the others.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_create_agreements_bulk() {
+    let network = MarketsNetwork::new(None)
+        .await
+        .add_market_instance(REQ_NAME)
+        .await
+        .add_market_instance(PROV_NAME)
+        .await;
+
+    let proposal1 = exchange_proposals_exclusive(&network, REQ_NAME, PROV_NAME, "bulk-1")
+        .await
+        .unwrap()
+        .proposal_id;
+    let proposal2 = exchange_proposals_exclusive(&network, REQ_NAME, PROV_NAME, "bulk-2")
+        .await
+        .unwrap()
+        .proposal_id;
+    // Provider's id of Proposal can't be found on Requestor side.
+    let not_existing = proposal2.clone().translate(Owner::Provider);
+
+    let req_market = network.get_market(REQ_NAME);
+    let req_engine = &req_market.requestor_engine;
+    let req_id = network.get_default_id(REQ_NAME);
+    let valid_to = Utc::now() + Duration::hours(1);
+
+    let result = req_engine
+        .create_agreements(
+            req_id.clone(),
+            vec![proposal1.clone(), proposal1.clone()],
+            valid_to,
+            None,
+            None,
+        )
+        .await;
+    assert_err_eq!(
+        BulkAgreementError::DuplicateProposal(proposal1.clone()),
+        result
+    );
+
+    let outcomes = req_engine
+        .create_agreements(
+            req_id.clone(),
+            vec![proposal1.clone(), not_existing.clone(), proposal2.clone()],
+            valid_to,
+            Some("bulk-session".to_string()),
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(outcomes.len(), 3);
+    assert_eq!(outcomes[0].proposal_id, proposal1);
+    assert_eq!(outcomes[1].proposal_id, not_existing);
+    assert_eq!(outcomes[2].proposal_id, proposal2);
+    assert!(outcomes[1].agreement_id.is_none());
+    assert!(outcomes[1].error.is_some());
+
+    for outcome in &[&outcomes[0], &outcomes[2]] {
+        assert_eq!(outcome.error, None);
+        assert_eq!(outcome.approval, None);
+        let agreement_id = outcome.agreement_id.clone().unwrap();
+        let agreement = req_market
+            .db
+            .as_dao::<AgreementDao>()
+            .select(&agreement_id, None, Utc::now().naive_utc())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(agreement.state, AgreementState::Pending);
+        assert_eq!(agreement.session_id, Some("bulk-session".to_string()));
+    }
+}
+
 /// Requestor can't counter the same Proposal for the second time.
 // TODO: Should it be allowed after expiration.unwrap().unwrap() For sure it shouldn't be allowed
 // TODO: after rejection, because rejection always ends negotiations.
@@ -1260,3 +1339,296 @@ async fn test_terminate_invalid_reason() {
     let resp = actix_web::test::call_service(&app, req).await;
     assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 }
+
+/// Requestor extends approved Agreement and changes its properties.
+/// Changes are visible on both sides only after Provider approves them.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_amend_approved_agreement() {
+    let network = MarketsNetwork::new(None)
+        .await
+        .add_market_instance(REQ_NAME)
+        .await
+        .add_market_instance(PROV_NAME)
+        .await;
+
+    let req_market = network.get_market(REQ_NAME);
+    let prov_market = network.get_market(PROV_NAME);
+    let req_id = network.get_default_id(REQ_NAME);
+    let prov_id = network.get_default_id(PROV_NAME);
+
+    let negotiation = negotiate_agreement(
+        &network,
+        REQ_NAME,
+        PROV_NAME,
+        "negotiation",
+        "r-session",
+        "p-session",
+    )
+    .await
+    .unwrap();
+    let client_agreement_id = negotiation.r_agreement.into_client();
+    let amendment_ts = Utc::now();
+
+    let valid_to = Utc::now() + Duration::hours(2);
+    let amendment = req_market
+        .requestor_engine
+        .propose_amendment(
+            &req_id,
+            &client_agreement_id,
+            valid_to,
+            None,
+            Some(json!({"golem.amended": "yes"})),
+        )
+        .await
+        .unwrap();
+    assert_eq!(amendment.state, AmendmentState::Pending);
+
+    // Only one amendment can be negotiated at the same time.
+    let result = req_market
+        .requestor_engine
+        .propose_amendment(&req_id, &client_agreement_id, valid_to, None, None)
+        .await;
+    assert_err_eq!(
+        AmendmentError::PendingExists(negotiation.r_agreement.clone(), amendment.id.clone()),
+        result
+    );
+
+    // Provider can't approve amendment of Agreement, he doesn't own.
+    let result = req_market
+        .provider_engine
+        .approve_amendment(&req_id, &client_agreement_id, &amendment.id)
+        .await;
+    assert_err_eq!(
+        AmendmentError::AgreementNotFound(client_agreement_id.clone()),
+        result
+    );
+
+    prov_market
+        .provider_engine
+        .approve_amendment(&prov_id, &client_agreement_id, &amendment.id)
+        .await
+        .unwrap();
+
+    let resolved = req_market
+        .requestor_engine
+        .wait_for_amendment(&req_id, &client_agreement_id, &amendment.id, 0.5)
+        .await
+        .unwrap();
+    assert_eq!(resolved.state, AmendmentState::Approved);
+
+    let r_agreement = req_market
+        .get_agreement(&negotiation.r_agreement, &req_id)
+        .await
+        .unwrap();
+    let p_agreement = prov_market
+        .get_agreement(&negotiation.p_agreement, &prov_id)
+        .await
+        .unwrap();
+    for agreement in &[r_agreement, p_agreement] {
+        assert_eq!(agreement.state, ClientState::Approved);
+        assert_eq!(agreement.valid_to.timestamp(), valid_to.timestamp());
+        assert_eq!(agreement.offer.properties["golem.amended"], json!("yes"));
+    }
+
+    // Both sides are notified about amendment through amendment events,
+    // which don't appear among Agreement state events.
+    for (market, id) in &[(&req_market, &req_id), (&prov_market, &prov_id)] {
+        let events = market
+            .query_amendment_events(&None, 0.0, Some(10), amendment_ts, id)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|event| event.amendment_id == amendment.id));
+        assert!(matches!(
+            events[0].event_type,
+            AmendmentEventType::AmendmentProposedEvent
+        ));
+        assert!(matches!(
+            events[1].event_type,
+            AmendmentEventType::AmendmentApprovedEvent
+        ));
+
+        let events = market
+            .query_agreement_events(&None, 0.0, Some(10), amendment_ts, id)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 0);
+    }
+
+    // Amendment can't be resolved twice.
+    let result = prov_market
+        .provider_engine
+        .reject_amendment(&prov_id, &client_agreement_id, &amendment.id, None)
+        .await;
+    assert_err_eq!(
+        AmendmentError::AlreadyResolved(amendment.id.clone(), AmendmentState::Approved),
+        result
+    );
+
+    // Rejected amendment doesn't change Agreement.
+    let rejected = req_market
+        .requestor_engine
+        .propose_amendment(
+            &req_id,
+            &client_agreement_id,
+            valid_to + Duration::hours(1),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    prov_market
+        .provider_engine
+        .reject_amendment(
+            &prov_id,
+            &client_agreement_id,
+            &rejected.id,
+            Some(gen_reason("Too long")),
+        )
+        .await
+        .unwrap();
+
+    let amendments = req_market
+        .requestor_engine
+        .common
+        .list_amendments(&req_id, &client_agreement_id)
+        .await
+        .unwrap();
+    assert_eq!(amendments.len(), 2);
+    assert_eq!(amendments[1].state, AmendmentState::Rejected);
+    assert_eq!(amendments[1].reason().unwrap().message, "Too long");
+
+    let r_agreement = req_market
+        .get_agreement(&negotiation.r_agreement, &req_id)
+        .await
+        .unwrap();
+    assert_eq!(r_agreement.valid_to.timestamp(), valid_to.timestamp());
+
+    // Price can be amended, if Provider approves it.
+    let price = json!({"golem.com.pricing.model.linear.coeffs": [0.0, 0.1, 0.2]});
+    let repriced = req_market
+        .requestor_engine
+        .propose_amendment(&req_id, &client_agreement_id, valid_to, None, Some(price))
+        .await
+        .unwrap();
+    prov_market
+        .provider_engine
+        .approve_amendment(&prov_id, &client_agreement_id, &repriced.id)
+        .await
+        .unwrap();
+    let resolved = req_market
+        .requestor_engine
+        .wait_for_amendment(&req_id, &client_agreement_id, &repriced.id, 0.5)
+        .await
+        .unwrap();
+    assert_eq!(resolved.state, AmendmentState::Approved);
+
+    let r_agreement = req_market
+        .get_agreement(&negotiation.r_agreement, &req_id)
+        .await
+        .unwrap();
+    let p_agreement = prov_market
+        .get_agreement(&negotiation.p_agreement, &prov_id)
+        .await
+        .unwrap();
+    for agreement in &[r_agreement, p_agreement] {
+        assert_eq!(
+            agreement.offer.properties["golem.com.pricing.model.linear.coeffs"],
+            json!([0.0, 0.1, 0.2])
+        );
+    }
+
+    // Other payment terms can't be amended.
+    let result = req_market
+        .requestor_engine
+        .propose_amendment(
+            &req_id,
+            &client_agreement_id,
+            valid_to + Duration::hours(1),
+            None,
+            Some(json!({"golem.com.payment.chosen-platform": "erc20-rinkeby-tglm"})),
+        )
+        .await;
+    assert!(matches!(result, Err(AmendmentError::InvalidProperties(_))));
+}
+
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_export_terminated_agreement() {
+    let network = MarketsNetwork::new(None)
+        .await
+        .add_market_instance(REQ_NAME)
+        .await
+        .add_market_instance(PROV_NAME)
+        .await;
+
+    let req_market = network.get_market(REQ_NAME);
+    let req_id = network.get_default_id(REQ_NAME);
+    let prov_id = network.get_default_id(PROV_NAME);
+
+    let negotiation = negotiate_agreement(
+        &network,
+        REQ_NAME,
+        PROV_NAME,
+        "negotiation",
+        "r-session",
+        "p-session",
+    )
+    .await
+    .unwrap();
+
+    let reason = serde_json::from_value(json!({
+        "message": "Work, finished",
+        "golem.requestor.code": "Success",
+    }))
+    .unwrap();
+    req_market
+        .terminate_agreement(
+            req_id.clone(),
+            negotiation.r_agreement.into_client(),
+            Some(reason),
+        )
+        .await
+        .unwrap();
+
+    let result = req_market
+        .export_agreements(&req_id, Default::default())
+        .await
+        .unwrap();
+    assert_eq!(result.agreements.len(), 1);
+    assert_eq!(result.statistics.count, 1);
+    assert_eq!(result.statistics.states.get("Terminated"), Some(&1));
+    assert_eq!(
+        result.statistics.termination_reasons.get("Success"),
+        Some(&1)
+    );
+    assert!(result.statistics.average_duration_secs.unwrap() >= 0.0);
+
+    let exported = &result.agreements[0];
+    assert_eq!(exported.role, Role::Requestor);
+    assert_eq!(exported.peer_id, prov_id.identity);
+    assert_eq!(exported.ended_by, Some(Role::Requestor));
+    assert_eq!(exported.reason.as_ref().unwrap().message, "Work, finished");
+
+    let csv = export::to_csv(&result.agreements);
+    let rows = csv.lines().collect::<Vec<_>>();
+    assert_eq!(rows.len(), 2);
+    assert!(rows[1].starts_with(&exported.agreement.agreement_id));
+    assert!(rows[1].contains(",Success,\"Work, finished\","));
+
+    // Filtering by other peer excludes Agreement.
+    let filter = market::ExportAgreements {
+        peer_id: Some(
+            "0xbabe000000000000000000000000000000000000"
+                .parse()
+                .unwrap(),
+        ),
+        ..Default::default()
+    };
+    let result = req_market.export_agreements(&req_id, filter).await.unwrap();
+    assert!(result.agreements.is_empty());
+    assert_eq!(result.statistics, market::AgreementStatistics::default());
+}
@@ -9,6 +9,7 @@ use ya_market::testing::{QueryEventsError, TakeEventsError};
 use ya_market::MarketService;
 
 use chrono::Utc;
+use futures::StreamExt;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -669,3 +670,109 @@ async fn test_respawn_proposal_after_terminate_by_provider() -> anyhow::Result<(
     assert_eq!(proposal.state, State::Initial);
     Ok(())
 }
+
+/// Initial proposal should be pushed to events stream. Stream should end,
+/// when Demand is unsubscribed.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_stream_initial_proposal() {
+    let network = MarketsNetwork::new(None)
+        .await
+        .add_market_instance("Node-1")
+        .await;
+
+    let market1 = network.get_market("Node-1");
+    let identity1 = network.get_default_id("Node-1");
+    let identity2 = network.create_identity("Node-1", "Identity2");
+
+    let demand_id = market1
+        .subscribe_demand(&sample_demand(), &identity1)
+        .await
+        .unwrap();
+    let mut events = market1
+        .requestor_engine
+        .stream_scored_events(&demand_id)
+        .await
+        .unwrap();
+
+    market1
+        .subscribe_offer(&sample_offer(), &identity2)
+        .await
+        .unwrap();
+
+    // Proposal should be pushed without polling market again.
+    let event = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    match event.event {
+        RequestorEvent::ProposalEvent { proposal, .. } => {
+            assert_eq!(proposal.state, State::Initial)
+        }
+        e => panic!("Invalid event Type. ProposalEvent expected, got: {:?}", e),
+    };
+
+    // Event was taken from queue by stream.
+    let events_query = market1
+        .query_events(&demand_id, 0.1, Some(5))
+        .await
+        .unwrap();
+    assert_eq!(events_query.len(), 0);
+
+    market1
+        .unsubscribe_demand(&demand_id, &identity1)
+        .await
+        .unwrap();
+    let end = tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap();
+    assert!(end.is_none());
+}
+
+/// Stream takes events from queue only when polled, so events not delivered
+/// before dropping stream, should be still available for `query_events`.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_dropped_stream_keeps_events() {
+    let network = MarketsNetwork::new(None)
+        .await
+        .add_market_instance("Node-1")
+        .await;
+
+    let market1 = network.get_market("Node-1");
+    let identity1 = network.get_default_id("Node-1");
+    let identity2 = network.create_identity("Node-1", "Identity2");
+    let identity3 = network.create_identity("Node-1", "Identity3");
+
+    let demand_id = market1
+        .subscribe_demand(&sample_demand(), &identity1)
+        .await
+        .unwrap();
+    market1
+        .subscribe_offer(&sample_offer(), &identity2)
+        .await
+        .unwrap();
+    market1
+        .subscribe_offer(&sample_offer(), &identity3)
+        .await
+        .unwrap();
+
+    let mut events = market1
+        .requestor_engine
+        .stream_scored_events(&demand_id)
+        .await
+        .unwrap();
+    tokio::time::timeout(Duration::from_secs(5), events.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    drop(events);
+
+    let events_query = market1
+        .query_events(&demand_id, 5.0, Some(5))
+        .await
+        .unwrap();
+    assert_eq!(events_query.len(), 1);
+}
@@ -616,6 +616,76 @@ async fn test_counter_draft_unsubscribed_remote_demand() {
     }
 }
 
+/// Provider tries to counter draft Proposal after Requestor blocked him.
+/// Counter Proposal should be rejected by Requestor Node.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_counter_proposal_from_blocked_node() {
+    let network = MarketsNetwork::new(None)
+        .await
+        .add_market_instance("Node-1")
+        .await
+        .add_market_instance("Node-2")
+        .await;
+
+    let NegotiationHelper {
+        proposal_id: proposal0_id,
+        offer_id,
+        demand_id,
+        ..
+    } = exchange_draft_proposals(&network, "Node-1", "Node-2")
+        .await
+        .unwrap();
+
+    let market1 = network.get_market("Node-1");
+    let market2 = network.get_market("Node-2");
+    let identity1 = network.get_default_id("Node-1");
+    let identity2 = network.get_default_id("Node-2");
+
+    market1
+        .requestor_engine
+        .counter_proposal(&demand_id, &proposal0_id, &sample_demand(), &identity1)
+        .await
+        .unwrap();
+    let proposal2 = provider::query_proposal(&market2, &offer_id, "Counter #P")
+        .await
+        .unwrap();
+
+    market1
+        .block_node(identity2.identity, Some("Failed tasks".to_string()))
+        .await
+        .unwrap();
+
+    let result = market2
+        .provider_engine
+        .counter_proposal(
+            &offer_id,
+            &proposal2.get_proposal_id().unwrap(),
+            &sample_offer(),
+            &identity2,
+        )
+        .await;
+
+    match result.err().unwrap() {
+        ProposalError::Send(
+            _,
+            CounterProposalError::Remote(
+                RemoteProposalError::Validation(ProposalValidationError::Blocked(node_id)),
+                _,
+            ),
+        ) => assert_eq!(node_id, identity2.identity),
+        e => panic!("Expected ProposalValidationError::Blocked, got: {}", e),
+    }
+
+    // Requestor shouldn't get any event about rejected Proposal.
+    let events = market1
+        .requestor_engine
+        .query_events(&demand_id, 0.5, Some(5))
+        .await
+        .unwrap();
+    assert!(events.is_empty());
+}
+
 /// Try to send not matching counter Proposal to Provider. Our market
 /// should reject such Proposal. Error should occur on Requestor side.
 #[cfg_attr(not(feature = "test-suite"), ignore)]
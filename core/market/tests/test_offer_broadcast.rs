@@ -1,14 +1,21 @@
 use chrono::Utc;
 use futures::{channel::mpsc, prelude::*};
+use serde_json::json;
 use std::str::FromStr;
+use std::sync::Arc;
 use tokio::time::Duration;
 
+use ya_client::model::market::NewOffer;
 use ya_market::assert_err_eq;
+use ya_market::testing::discovery::error::DiscoveryError;
 use ya_market::testing::discovery::{message::*, Discovery};
-use ya_market::testing::mock_node::{assert_offers_broadcasted, assert_unsunbscribes_broadcasted};
+use ya_market::testing::mock_identity::generate_identity;
+use ya_market::testing::mock_node::{
+    assert_offers_broadcasted, assert_unsunbscribes_broadcasted, create_market_config_for_test,
+};
 use ya_market::testing::mock_offer::{client, sample_offer, sample_offer_with_expiration};
 use ya_market::testing::{MarketServiceExt, MarketsNetwork};
-use ya_market::testing::{QueryOfferError, SubscriptionId};
+use ya_market::testing::{Offer, QueryOfferError, SaveOfferError, SubscriptionId};
 
 /// Test adds offer. It should be broadcasted to other nodes in the network.
 /// Than sending unsubscribe should remove Offer from other nodes.
@@ -339,6 +346,107 @@ async fn test_discovery_get_offers() {
     assert_eq!(offers[0].id, subscription_id);
 }
 
+/// Node asking for Offers too often should be rejected, until rate limit window passes.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_discovery_get_offers_rate_limit() {
+    let _ = env_logger::builder().try_init();
+    let mut config = create_market_config_for_test();
+    config.discovery.max_offer_retrievals_per_node = 2;
+    config.discovery.rate_limit_window = std::time::Duration::from_millis(500);
+
+    let network = MarketsNetwork::new(None)
+        .await
+        .with_config(Arc::new(config))
+        .add_market_instance("Node-1")
+        .await;
+
+    let discovery_builder = network.discovery_builder();
+    let network = network
+        .add_discovery_instance("Node-2", discovery_builder)
+        .await;
+
+    let mkt1 = network.get_market("Node-1");
+    let id1 = network.get_default_id("Node-1");
+    let discovery2 = network.get_discovery("Node-2");
+
+    let subscription_id = mkt1
+        .subscribe_offer(&client::sample_offer(), &id1)
+        .await
+        .unwrap();
+
+    let get_offers =
+        || discovery2.get_remote_offers(id1.identity.to_string(), vec![subscription_id.clone()], 5);
+    assert_eq!(get_offers().await.unwrap().len(), 1);
+    assert_eq!(get_offers().await.unwrap().len(), 1);
+    assert!(matches!(
+        get_offers().await,
+        Err(DiscoveryError::RemoteError(e)) if e.is_rate_limited()
+    ));
+
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert_eq!(get_offers().await.unwrap().len(), 1);
+}
+
+/// Offers from other nodes are stored only up to `max_offers_per_node` per Provider.
+/// Local Offers aren't limited.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_remote_offers_quota_per_node() {
+    let _ = env_logger::builder().try_init();
+    let mut config = create_market_config_for_test();
+    config.discovery.max_offers_per_node = 2;
+
+    let network = MarketsNetwork::new(None)
+        .await
+        .with_config(Arc::new(config))
+        .add_market_instance("Node-1")
+        .await;
+
+    let mkt1 = network.get_market("Node-1");
+    let id1 = network.get_default_id("Node-1");
+    let store = &mkt1.matcher.store;
+
+    let provider = generate_identity("Provider");
+    let offer = |mem: u32| {
+        let creation_ts = Utc::now().naive_utc();
+        let expiration_ts = creation_ts + chrono::Duration::hours(1);
+        let offer = NewOffer::new(json!({"golem.inf.mem.gib": mem}), "()".to_string());
+        Offer::from_new(&offer, &provider, creation_ts, expiration_ts).unwrap()
+    };
+
+    store.save_offer(offer(1)).await.unwrap();
+    store.save_offer(offer(2)).await.unwrap();
+    assert_err_eq!(
+        SaveOfferError::QuotaExceeded(provider.identity, 2),
+        store.save_offer(offer(3)).await
+    );
+
+    // Other Provider has separate quota.
+    store.save_offer(sample_offer()).await.unwrap();
+
+    // Concurrent saves can't exceed quota either.
+    let provider = generate_identity("Provider-2");
+    let saves = (0..10).map(|mem| {
+        let creation_ts = Utc::now().naive_utc();
+        let expiration_ts = creation_ts + chrono::Duration::hours(1);
+        let offer = NewOffer::new(json!({"golem.inf.mem.gib": mem}), "()".to_string());
+        store.save_offer(Offer::from_new(&offer, &provider, creation_ts, expiration_ts).unwrap())
+    });
+    let saved = futures::future::join_all(saves)
+        .await
+        .into_iter()
+        .filter(Result::is_ok)
+        .count();
+    assert_eq!(saved, 2);
+
+    for _ in 0..3 {
+        mkt1.subscribe_offer(&client::sample_offer(), &id1)
+            .await
+            .unwrap();
+    }
+}
+
 /// Ensure that node is ready to handle broadcast message with more offers than
 /// `max_bcasted_offers` or more unsubscribes than `max_bcasted_unsubscribes`. We will use sets
 /// larger than 32766 as it's SQLITE_MAX_VARIABLE_NUMBER as of 3.32.0 (2020-05-22).
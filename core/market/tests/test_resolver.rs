@@ -161,6 +161,64 @@ async fn test_resolve_2xoffer_demand() {
     assert!(timeout3s(listener.proposal_receiver.recv()).await.is_err());
 }
 
+/// Requestor blocked Provider. Resolver should not emit Proposals for his Offers.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_not_resolve_offer_from_blocked_node() {
+    // given
+    let _ = env_logger::builder().try_init();
+    let mut network = MarketsNetwork::new(None)
+        .await
+        .add_matcher_instance("Provider-1")
+        .await
+        .add_matcher_instance("Provider-2")
+        .await
+        .add_matcher_instance("Requestor-1")
+        .await;
+
+    let id1 = network.get_default_id("Provider-1");
+    let provider1 = network.get_matcher("Provider-1");
+    let id2 = network.get_default_id("Provider-2");
+    let provider2 = network.get_matcher("Provider-2");
+    let id3 = network.get_default_id("Requestor-1");
+    let requestor = network.get_matcher("Requestor-1");
+
+    requestor
+        .store
+        .block_node(id1.identity, None)
+        .await
+        .unwrap();
+
+    // when: Offer from blocked Provider is subscribed before Demand
+    provider1
+        .subscribe_offer(&sample_offer(), &id1)
+        .await
+        .unwrap();
+    let demand = requestor
+        .subscribe_demand(&sample_demand(), &id3)
+        .await
+        .unwrap();
+    // and: the other one after Demand.
+    let offer2 = provider2
+        .subscribe_offer(&sample_offer(), &id2)
+        .await
+        .unwrap();
+    provider1
+        .subscribe_offer(&sample_offer(), &id1)
+        .await
+        .unwrap();
+
+    // then: Only Offer from not blocked Provider should be resolved.
+    let listener = network.get_event_listeners("Requestor-1");
+    let proposal = timeout3s(listener.proposal_receiver.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(proposal.offer, offer2);
+    assert_eq!(proposal.demand, demand);
+    assert!(timeout3s(listener.proposal_receiver.recv()).await.is_err());
+}
+
 fn timeout3s<T: Future>(fut: T) -> Timeout<T> {
     timeout(Duration::from_secs(3), fut)
 }
@@ -0,0 +1,180 @@
+use all_asserts::assert_lt;
+use chrono::{Duration, Utc};
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::time::timeout;
+
+use ya_client::model::market::{NewDemand, NewOffer};
+use ya_market::testing::mock_identity::generate_identity;
+use ya_market::testing::mock_node::create_market_config_for_test;
+use ya_market::testing::{
+    Demand, IndexConfig, MarketsNetwork, Offer, SubscriptionId, SubscriptionStore,
+};
+
+const NUM_OFFERS: usize = 5000;
+const RUNTIMES: &[&str] = &["vm", "wasmtime", "emscripten", "gvmkit", "docker"];
+
+/// Resolver should find the same Offers with and without index, but indexed
+/// lookup must be faster, because only candidate Offers are matched.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_resolver_index_speedup() {
+    let _ = env_logger::builder().try_init();
+
+    let mut config = create_market_config_for_test();
+    config.index = IndexConfig::disabled();
+    let (linear_offers, linear_time) =
+        resolve_demand("test_resolver_index_speedup_linear", config).await;
+
+    let config = create_market_config_for_test();
+    let (indexed_offers, indexed_time) =
+        resolve_demand("test_resolver_index_speedup_indexed", config).await;
+
+    println!(
+        "Resolving Demand against {} Offers. Linear scan: {:?}, indexed: {:?}",
+        NUM_OFFERS, linear_time, indexed_time
+    );
+
+    assert_eq!(linear_offers.len(), expected_matches());
+    assert_eq!(linear_offers, indexed_offers);
+    assert_lt!(indexed_time, linear_time);
+}
+
+/// Index is kept in memory, so after restart it must be rebuilt from Offers
+/// stored in database. Otherwise new Demands wouldn't match them.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_resolver_index_rebuilt_after_restart() {
+    let _ = env_logger::builder().try_init();
+
+    let config = Arc::new(create_market_config_for_test());
+    let network = MarketsNetwork::new(None)
+        .await
+        .with_config(config.clone())
+        .add_matcher_instance("Requestor-1")
+        .await;
+
+    let requestor = network.get_matcher("Requestor-1");
+    let mut expected = HashSet::new();
+    for i in 0..20 {
+        let (runtime, mem) = offer_params(i);
+        let offer = NewOffer::new(
+            json!({
+                "golem": {
+                    "runtime.name": runtime,
+                    "inf.mem.gib": mem,
+                },
+            }),
+            "()".to_string(),
+        );
+        let creation_ts = Utc::now().naive_utc();
+        let expiration_ts = creation_ts + Duration::hours(1);
+        let offer =
+            Offer::from_new(&offer, &generate_identity(""), creation_ts, expiration_ts).unwrap();
+        let offer = requestor.store.save_offer(offer).await.unwrap();
+        if runtime == "wasmtime" {
+            expected.insert(offer.id);
+        }
+    }
+
+    // Store created on the same database, as if yagna was restarted.
+    let store = SubscriptionStore::new(network.init_database("Requestor-1"), config);
+    store.rebuild_index().await.unwrap();
+
+    let creation_ts = Utc::now().naive_utc();
+    let demand = Demand::from_new(
+        &NewDemand::new(json!({}), "(golem.runtime.name=wasmtime)".to_string()),
+        &network.get_default_id("Requestor-1"),
+        creation_ts,
+        creation_ts + Duration::hours(1),
+    )
+    .unwrap();
+    let candidates = store
+        .get_offer_candidates_before(&demand, Utc::now().naive_utc())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|offer| offer.id)
+        .collect::<HashSet<_>>();
+
+    assert_eq!(expected.len(), 4);
+    assert_eq!(candidates, expected);
+}
+
+async fn resolve_demand(
+    test_name: &str,
+    config: ya_market::testing::Config,
+) -> (HashSet<SubscriptionId>, std::time::Duration) {
+    let mut network = MarketsNetwork::new(Some(test_name))
+        .await
+        .with_config(Arc::new(config))
+        .add_matcher_instance("Requestor-1")
+        .await;
+
+    let id = network.get_default_id("Requestor-1");
+    let requestor = network.get_matcher("Requestor-1");
+
+    // Offers from other nodes, as if they were received by discovery.
+    for i in 0..NUM_OFFERS {
+        let (runtime, mem) = offer_params(i);
+        let offer = NewOffer::new(
+            json!({
+                "golem": {
+                    "runtime.name": runtime,
+                    "inf.mem.gib": mem,
+                    "inf.cpu.cores": 4,
+                },
+            }),
+            "()".to_string(),
+        );
+        let creation_ts = Utc::now().naive_utc();
+        let expiration_ts = creation_ts + Duration::hours(1);
+        let offer =
+            Offer::from_new(&offer, &generate_identity(""), creation_ts, expiration_ts).unwrap();
+        requestor.store.save_offer(offer).await.unwrap();
+    }
+
+    let demand = NewDemand::new(
+        json!({}),
+        "(&(golem.runtime.name=wasmtime)(golem.inf.mem.gib>=12))".to_string(),
+    );
+
+    let start = Instant::now();
+    requestor.subscribe_demand(&demand, &id).await.unwrap();
+
+    let listener = network.get_event_listeners("Requestor-1");
+    let mut offers = HashSet::new();
+    while offers.len() < expected_matches() {
+        let proposal = timeout(
+            std::time::Duration::from_secs(30),
+            listener.proposal_receiver.recv(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        offers.insert(proposal.offer.id);
+    }
+    let elapsed = start.elapsed();
+
+    // No more Proposals should be emitted.
+    assert!(timeout(
+        std::time::Duration::from_millis(500),
+        listener.proposal_receiver.recv()
+    )
+    .await
+    .is_err());
+    (offers, elapsed)
+}
+
+fn offer_params(i: usize) -> (&'static str, usize) {
+    (RUNTIMES[i % RUNTIMES.len()], 1 + (i / RUNTIMES.len()) % 16)
+}
+
+fn expected_matches() -> usize {
+    (0..NUM_OFFERS)
+        .map(offer_params)
+        .filter(|(runtime, mem)| *runtime == "wasmtime" && *mem >= 12)
+        .count()
+}
@@ -2,6 +2,7 @@ use actix_web::{body::MessageBody, dev::ServiceResponse, error::PathError, http:
 use chrono::Utc;
 use serde::de::DeserializeOwned;
 use serde_json::json;
+use std::sync::Arc;
 
 use ya_client::model::market::agreement::State as ClientAgreementState;
 use ya_client::model::market::{
@@ -10,15 +11,18 @@ use ya_client::model::market::{
 };
 use ya_client::model::ErrorMessage;
 use ya_client::web::QueryParamsBuilder;
+use ya_core_model::market::{ConstraintsExplanation, MatchExplanation};
 use ya_market::testing::agreement_utils::negotiate_agreement;
 use ya_market::testing::events_helper::requestor::expect_approve;
 use ya_market::testing::{
     agreement_utils::gen_reason,
     client::{sample_demand, sample_offer},
-    mock_node::{assert_offers_broadcasted, MarketServiceExt},
+    mock_node::{assert_offers_broadcasted, create_market_config_for_test, MarketServiceExt},
     mock_offer::flatten_json,
     proposal_util::exchange_draft_proposals,
-    DemandError, MarketsNetwork, ModifyOfferError, Owner, SubscriptionId, SubscriptionParseError,
+    schema::SchemaValidation,
+    DemandError, MarketsNetwork, ModifyOfferError, OffersPage, Owner, ScoredRequestorEvent,
+    SubscriptionId, SubscriptionParseError,
 };
 
 const REQ_NAME: &str = "Node-1";
@@ -87,6 +91,90 @@ async fn test_rest_get_offers() {
     assert_eq!(vec![offer_local.into_client_offer().unwrap()], result);
 }
 
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_rest_query_offers() {
+    let network = MarketsNetwork::new(None)
+        .await
+        .add_market_instance("Node-1")
+        .await;
+
+    let market_local = network.get_market("Node-1");
+    let identity_local = network.get_default_id("Node-1");
+
+    let mut offers = vec![];
+    for (runtime, mem) in &[("vm", 8), ("wasmtime", 8), ("vm", 2), ("vm", 16)] {
+        let offer = NewOffer::new(
+            json!({"golem": {"runtime.name": runtime, "inf.mem.gib": mem}}),
+            "()".to_string(),
+        );
+        let subscription_id = market_local
+            .subscribe_offer(&offer, &identity_local)
+            .await
+            .unwrap();
+        let offer = market_local.get_offer(&subscription_id).await.unwrap();
+        offers.push(offer.into_client_offer().unwrap());
+    }
+
+    let app = network.get_rest_app("Node-1").await;
+    let constraints = "(&(golem.runtime.name=vm)(golem.inf.mem.gib>=4))";
+
+    // First page should contain single Offer and cursor to the next one.
+    let url = format!(
+        "/market-api/v1/offers/query?{}",
+        QueryParamsBuilder::new()
+            .put("constraints", Some(constraints))
+            .put("limit", Some(1))
+            .build()
+    );
+    let req = actix_web::test::TestRequest::get().uri(&url).to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let page: OffersPage = read_response_json(resp).await;
+    assert_eq!(page.offers, vec![offers[0].clone()]);
+    assert!(page.next_cursor.is_some());
+
+    // Last page shouldn't have cursor.
+    let url = format!(
+        "/market-api/v1/offers/query?{}",
+        QueryParamsBuilder::new()
+            .put("constraints", Some(constraints))
+            .put("limit", Some(1))
+            .put("cursor", page.next_cursor)
+            .build()
+    );
+    let req = actix_web::test::TestRequest::get().uri(&url).to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let page: OffersPage = read_response_json(resp).await;
+    assert_eq!(page.offers, vec![offers[3].clone()]);
+    assert_eq!(page.next_cursor, None);
+
+    // Without constraints all Offers are returned.
+    let req = actix_web::test::TestRequest::get()
+        .uri("/market-api/v1/offers/query?sortBy=expirationTs")
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let page: OffersPage = read_response_json(resp).await;
+    assert_eq!(page.offers, offers);
+
+    // Invalid constraints are rejected.
+    let url = format!(
+        "/market-api/v1/offers/query?{}",
+        QueryParamsBuilder::new()
+            .put("constraints", Some("(golem.runtime.name=vm"))
+            .build()
+    );
+    let req = actix_web::test::TestRequest::get().uri(&url).to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
+
 #[cfg_attr(not(feature = "test-suite"), ignore)]
 #[serial_test::serial]
 async fn test_rest_get_demands() {
@@ -115,6 +203,75 @@ async fn test_rest_get_demands() {
     assert_eq!(vec![demand_local.into_client_demand().unwrap()], result);
 }
 
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_rest_explain_match() {
+    let network = MarketsNetwork::new(None)
+        .await
+        .add_market_instance(REQ_NAME)
+        .await
+        .add_market_instance(PROV_NAME)
+        .await;
+
+    let req_market = network.get_market(REQ_NAME);
+    let req_identity = network.get_default_id(REQ_NAME);
+    let prov_market = network.get_market(PROV_NAME);
+    let prov_identity = network.get_default_id(PROV_NAME);
+
+    let demand_id = req_market
+        .subscribe_demand(
+            &NewDemand::new(
+                json!({}),
+                "(&(golem.runtime.name=vm)(golem.inf.mem.gib>=8))".to_string(),
+            ),
+            &req_identity,
+        )
+        .await
+        .unwrap();
+    let offer_id = prov_market
+        .subscribe_offer(
+            &NewOffer::new(
+                json!({"golem.runtime.name": "wasmtime"}),
+                "(golem.node.debug.subnet=*)".to_string(),
+            ),
+            &prov_identity,
+        )
+        .await
+        .unwrap();
+    assert_offers_broadcasted(&[&req_market], &[offer_id.clone()]).await;
+
+    let app = network.get_rest_app(REQ_NAME).await;
+    let req = actix_web::test::TestRequest::get()
+        .uri(&format!(
+            "/market-api/v1/demands/{}/offers/{}/explain",
+            demand_id, offer_id
+        ))
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let result: MatchExplanation = read_response_json(resp).await;
+    assert_eq!(
+        result,
+        MatchExplanation {
+            demand_id: demand_id.to_string(),
+            offer_id: offer_id.to_string(),
+            matching: false,
+            same_identity: false,
+            demand_constraints: ConstraintsExplanation {
+                failing: vec!["(golem.runtime.name=vm)".to_string()],
+                undefined: vec!["(golem.inf.mem.gib>=8)".to_string()],
+                missing_properties: vec!["golem.inf.mem.gib".to_string()],
+            },
+            offer_constraints: ConstraintsExplanation {
+                failing: vec!["(golem.node.debug.subnet=*)".to_string()],
+                undefined: vec![],
+                missing_properties: vec!["golem.node.debug.subnet".to_string()],
+            },
+        }
+    );
+}
+
 #[cfg_attr(not(feature = "test-suite"), ignore)]
 #[serial_test::serial]
 async fn test_rest_invalid_subscription_id_should_return_400() {
@@ -273,6 +430,62 @@ async fn test_rest_subscribe_unsubscribe_demand() {
     );
 }
 
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_rest_subscribe_property_schema_validation() {
+    let offer = NewOffer::new(
+        json!({"golem": {"inf.mem.gb": 8, "runtime.name": "vm"}}),
+        "(golem.srv.comp.expiration>0)".to_string(),
+    );
+
+    // By default violations are returned as warnings.
+    let network = MarketsNetwork::new(None)
+        .await
+        .add_market_instance("Node-1")
+        .await;
+    let app = network.get_rest_app("Node-1").await;
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/market-api/v1/offers")
+        .set_json(&offer)
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let warnings = resp
+        .headers()
+        .get_all(actix_web::http::header::WARNING)
+        .map(|value| value.to_str().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            "199 - \"Unknown property 'golem.inf.mem.gb'. Did you mean 'golem.inf.mem.gib'?\""
+                .to_string()
+        ]
+    );
+
+    // In strict mode subscription is rejected.
+    let mut config = create_market_config_for_test();
+    config.schema.validation = SchemaValidation::Strict;
+    let network = MarketsNetwork::new(Some("test_rest_subscribe_property_schema_strict"))
+        .await
+        .with_config(Arc::new(config))
+        .add_market_instance("Node-2")
+        .await;
+    let app = network.get_rest_app("Node-2").await;
+
+    let req = actix_web::test::TestRequest::post()
+        .uri("/market-api/v1/offers")
+        .set_json(&offer)
+        .to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    let result: ErrorMessage = read_response_json(resp).await;
+    assert!(result.message.unwrap().contains("golem.inf.mem.gb"));
+}
+
 #[cfg_attr(not(feature = "test-suite"), ignore)]
 #[serial_test::serial]
 async fn test_rest_get_proposal() {
@@ -331,6 +544,55 @@ async fn test_rest_get_proposal() {
     assert_eq!(proposal, resp_demands);
 }
 
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_rest_collect_scored_proposals() {
+    let mut config = create_market_config_for_test();
+    config.scoring.proposal_score_expression = Some("golem.inf.mem.gib * 2".to_string());
+    let network = MarketsNetwork::new(None)
+        .await
+        .with_config(Arc::new(config))
+        .add_market_instance("Node-1")
+        .await;
+
+    let market = network.get_market("Node-1");
+    let identity_req = network.get_default_id("Node-1");
+    let identity_prov = network.create_identity("Node-1", "Provider");
+
+    for mem in &[2, 16, 8] {
+        let offer = NewOffer::new(json!({"golem": {"inf.mem.gib": mem}}), "()".to_string());
+        market
+            .subscribe_offer(&offer, &identity_prov)
+            .await
+            .unwrap();
+    }
+    let demand = NewDemand::new(json!({}), "()".to_string());
+    let demand_id = market
+        .subscribe_demand(&demand, &identity_req)
+        .await
+        .unwrap();
+
+    // Wait until Proposals for all Offers are generated.
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let app = network.get_rest_app("Node-1").await;
+    let url = format!(
+        "/market-api/v1/demands/{}/events?{}",
+        demand_id,
+        QueryParamsBuilder::new()
+            .put("maxEvents", Some(10))
+            .put("orderByScore", Some(true))
+            .build()
+    );
+    let req = actix_web::test::TestRequest::get().uri(&url).to_request();
+    let resp = actix_web::test::call_service(&app, req).await;
+
+    assert_eq!(resp.status(), StatusCode::OK);
+    let events: Vec<ScoredRequestorEvent> = read_response_json(resp).await;
+    let scores = events.iter().map(|event| event.score).collect::<Vec<_>>();
+    assert_eq!(scores, vec![Some(32.0), Some(16.0), Some(4.0)]);
+}
+
 #[cfg_attr(not(feature = "test-suite"), ignore)]
 #[serial_test::serial]
 async fn test_rest_get_agreement() {
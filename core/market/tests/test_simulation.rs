@@ -0,0 +1,53 @@
+use serde_json::json;
+
+use ya_market::testing::simulation::{self, Scenario};
+
+/// Small scenario should finish long before timeout with every
+/// Proposal delivered to the other side.
+#[cfg_attr(not(feature = "test-suite"), ignore)]
+#[serial_test::serial]
+async fn test_simulation_negotiates_all_offers() {
+    let offer = json!({
+        "properties": {
+            "golem.node.debug.subnet": "simulation",
+            "golem.com.pricing.model": "linear"
+        },
+        "constraints": "(golem.srv.comp.expiration>0)"
+    });
+    let scenario: Scenario = serde_json::from_value(json!({
+        "name": "test-simulation",
+        "timeout": "30s",
+        "config": { "meanCyclicBcastInterval": "200ms", "offerBroadcastDelay": "100ms" },
+        "providers": [
+            { "name": "prov", "count": 3, "respond": true, "offer": offer },
+            { "name": "silent", "count": 1, "offer": offer }
+        ],
+        "requestors": [{
+            "name": "req",
+            "count": 2,
+            "negotiate": true,
+            "expectedProposals": 4,
+            "demand": {
+                "properties": {
+                    "golem.node.debug.subnet": "simulation",
+                    "golem.srv.comp.expiration": 3
+                },
+                "constraints": "(golem.com.pricing.model=linear)"
+            }
+        }]
+    }))
+    .unwrap();
+
+    let report = simulation::run(&scenario).await.unwrap();
+
+    assert!(report.completed);
+    assert_eq!(report.providers, 4);
+    assert_eq!(report.requestors, 2);
+    assert_eq!(report.initial_proposals, 8);
+    assert_eq!(report.requestor_counter_proposals, 8);
+    assert_eq!(report.provider_received_proposals, 8);
+    assert_eq!(report.provider_counter_proposals, 6);
+    assert_eq!(report.requestor_received_counters, 6);
+    assert_eq!(report.matching_latency.count, 8);
+    assert_eq!(report.negotiation_latency.count, 6);
+}
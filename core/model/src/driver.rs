@@ -541,6 +541,40 @@ impl RpcMessage for ShutDown {
     type Error = GenericError;
 }
 
+// ************************* DRIVER STATUS *************************
+
+/// Health of blockchain RPC endpoints used by the driver.
+/// Without network, endpoints of all networks in use are listed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DriverStatus {
+    pub network: Option<String>,
+}
+
+impl DriverStatus {
+    pub fn new(network: Option<String>) -> Self {
+        Self { network }
+    }
+}
+
+impl RpcMessage for DriverStatus {
+    const ID: &'static str = "DriverStatus";
+    type Item = Vec<RpcEndpointStatus>;
+    type Error = GenericError;
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RpcEndpointStatus {
+    pub network: String,
+    pub url: String,
+    pub is_healthy: bool,
+    /// Average response time in milliseconds.
+    pub latency_ms: Option<u64>,
+    pub successes: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+    pub last_checked: Option<DateTime<Utc>>,
+}
+
 // ************************* GAS DETAILS *************************
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
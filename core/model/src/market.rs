@@ -1,9 +1,11 @@
 //! Market service bus API.
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-use ya_client_model::market::{agreement::State, Role};
+use ya_client_model::market::{agreement::State, Reason, Role};
 pub use ya_client_model::market::{Agreement, AgreementListEntry};
+use ya_client_model::NodeId;
 use ya_service_bus::RpcMessage;
 
 /// Public Market bus address.
@@ -58,6 +60,145 @@ impl RpcMessage for ListAgreements {
     type Error = RpcMessageError;
 }
 
+/// Exports Agreements stored by this node together with their terms,
+/// termination details and summary statistics.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportAgreements {
+    /// Only Agreements with this node on the other side.
+    pub peer_id: Option<NodeId>,
+    pub state: Option<State>,
+    pub before_date: Option<DateTime<Utc>>,
+    pub after_date: Option<DateTime<Utc>>,
+    pub app_session_id: Option<String>,
+}
+
+impl RpcMessage for ExportAgreements {
+    const ID: &'static str = "ExportAgreements";
+    type Item = AgreementsExport;
+    type Error = RpcMessageError;
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgreementsExport {
+    pub agreements: Vec<ExportedAgreement>,
+    pub statistics: AgreementStatistics,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportedAgreement {
+    pub agreement: Agreement,
+    pub role: Role,
+    pub peer_id: NodeId,
+    /// Time of termination, rejection or cancellation.
+    pub end_date: Option<DateTime<Utc>>,
+    /// Side, that terminated, rejected or cancelled Agreement.
+    pub ended_by: Option<Role>,
+    pub reason: Option<Reason>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgreementStatistics {
+    pub count: u64,
+    /// Number of Agreements in each state.
+    pub states: BTreeMap<String, u64>,
+    /// Average time between approval and termination of terminated Agreements.
+    pub average_duration_secs: Option<f64>,
+    /// Number of terminated Agreements for each termination reason code.
+    pub termination_reasons: BTreeMap<String, u64>,
+}
+
+/// Explains why Demand does (not) match Offer.
+/// Demand must be subscribed on this node and Offer must be known to local market.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainMatch {
+    pub demand_id: String,
+    pub offer_id: String,
+}
+
+impl RpcMessage for ExplainMatch {
+    const ID: &'static str = "ExplainMatch";
+    type Item = MatchExplanation;
+    type Error = RpcMessageError;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchExplanation {
+    pub demand_id: String,
+    pub offer_id: String,
+    pub matching: bool,
+    /// Demand and Offer were published by the same identity, so they never match.
+    pub same_identity: bool,
+    /// Demand constraints resolved against Offer properties.
+    pub demand_constraints: ConstraintsExplanation,
+    /// Offer constraints resolved against Demand properties.
+    pub offer_constraints: ConstraintsExplanation,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintsExplanation {
+    /// Constraints sub-expressions resolved to false.
+    pub failing: Vec<String>,
+    /// Constraints sub-expressions, which couldn't be resolved.
+    pub undefined: Vec<String>,
+    /// Properties referenced by constraints, but not found on the other side.
+    pub missing_properties: Vec<String>,
+}
+
+/// Node on market blocklist. Offers from blocked nodes are never matched
+/// with local Demands and counter Proposals from them are rejected.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedNode {
+    pub node_id: NodeId,
+    pub reason: Option<String>,
+    pub blocked_date: DateTime<Utc>,
+}
+
+/// Adds node to market blocklist. Blocking already blocked node updates reason.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockNode {
+    pub node_id: NodeId,
+    pub reason: Option<String>,
+}
+
+impl RpcMessage for BlockNode {
+    const ID: &'static str = "BlockNode";
+    type Item = BlockedNode;
+    type Error = RpcMessageError;
+}
+
+/// Removes node from market blocklist.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnblockNode {
+    pub node_id: NodeId,
+}
+
+impl RpcMessage for UnblockNode {
+    const ID: &'static str = "UnblockNode";
+    type Item = ();
+    type Error = RpcMessageError;
+}
+
+/// Lists nodes on market blocklist.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ListBlockedNodes {}
+
+impl RpcMessage for ListBlockedNodes {
+    const ID: &'static str = "ListBlockedNodes";
+    type Item = Vec<BlockedNode>;
+    type Error = RpcMessageError;
+}
+
 /// Error message for market service bus API.
 #[derive(thiserror::Error, Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
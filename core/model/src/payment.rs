@@ -458,6 +458,8 @@ pub mod local {
         Polygon,
         #[strum(props(token = "tGLM"))]
         Mumbai,
+        #[strum(props(token = "tGLM"))]
+        Dev,
     }
 
     /// Experimental. In future releases this might change or be removed.
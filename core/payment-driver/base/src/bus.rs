@@ -80,6 +80,9 @@ pub async fn bind_service<Driver: PaymentDriver + 'static>(
         )
         .bind_with_processor(
             move |db, dr, c, m| async move { dr.shut_down(db, c, m).await }
+        )
+        .bind_with_processor(
+            move |db, dr, c, m| async move { dr.status(db, c, m).await }
         );
 
     log::debug!("Successfully bound payment driver service to service bus.");
@@ -12,7 +12,9 @@ use ya_persistence::executor::{do_with_transaction, readonly_transaction, AsDao,
 use crate::{
     dao::DbResult,
     db::{
-        models::{Network, PaymentEntity, PAYMENT_STATUS_NOT_YET, PAYMENT_STATUS_OK},
+        models::{
+            Network, PaymentEntity, TransactionEntity, PAYMENT_STATUS_NOT_YET, PAYMENT_STATUS_OK,
+        },
         schema::{payment, payment::dsl, transaction},
     },
 };
@@ -76,6 +78,26 @@ impl<'c> PaymentDao<'c> {
         .await
     }
 
+    /// Inserts transaction and links it with all payments settled by it
+    /// in single database transaction.
+    pub async fn insert_tx_for_payments(
+        &self,
+        tx: TransactionEntity,
+        order_ids: Vec<String>,
+    ) -> DbResult<()> {
+        do_with_transaction(self.pool, move |conn| {
+            let tx_id = tx.tx_id.clone();
+            diesel::insert_into(transaction::table)
+                .values(tx)
+                .execute(conn)?;
+            diesel::update(dsl::payment.filter(dsl::order_id.eq_any(order_ids)))
+                .set((dsl::tx_id.eq(tx_id), dsl::status.eq(PAYMENT_STATUS_OK)))
+                .execute(conn)?;
+            Ok(())
+        })
+        .await
+    }
+
     pub async fn get_by_tx_id(&self, tx_id: String) -> DbResult<Vec<PaymentEntity>> {
         readonly_transaction(self.pool, move |conn| {
             let payments: Vec<PaymentEntity> =
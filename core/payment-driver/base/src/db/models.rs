@@ -105,6 +105,7 @@ pub enum Network {
     Goerli = 5,     //Goerli is another Ethereum testnet
     Mumbai = 80001, //Mumbai is testnet for Polygon network
     Polygon = 137,  //Polygon is Polygon production network
+    Dev = 31337,    //Local development chain (e.g. anvil), chain id is configurable
 }
 
 impl Default for Network {
@@ -123,6 +124,7 @@ impl FromStr for Network {
             "goerli" => Ok(Network::Goerli),
             "polygon" => Ok(Network::Polygon),
             "mumbai" => Ok(Network::Mumbai),
+            "dev" => Ok(Network::Dev),
             _ => Err(DbError::InvalidData(format!("Invalid network: {}", s))),
         }
     }
@@ -136,6 +138,7 @@ impl Display for Network {
             Network::Goerli => f.write_str("goerli"),
             Network::Mumbai => f.write_str("mumbai"),
             Network::Polygon => f.write_str("polygon"),
+            Network::Dev => f.write_str("dev"),
         }
     }
 }
@@ -161,6 +164,7 @@ where
             5 => Network::Goerli,
             137 => Network::Polygon,
             80001 => Network::Mumbai,
+            31337 => Network::Dev,
             _ => return Err(anyhow::anyhow!("invalid value").into()),
         })
     }
@@ -139,4 +139,14 @@ pub trait PaymentDriver {
         caller: String,
         msg: ShutDown,
     ) -> Result<(), GenericError>;
+
+    /// Drivers not using RPC endpoints have nothing to report.
+    async fn status(
+        &self,
+        _db: DbExecutor,
+        _caller: String,
+        _msg: DriverStatus,
+    ) -> Result<Vec<RpcEndpointStatus>, GenericError> {
+        Ok(vec![])
+    }
 }
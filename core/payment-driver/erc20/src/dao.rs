@@ -203,15 +203,28 @@ impl Erc20Dao {
         }
     }
 
-    pub async fn transaction_saved(&self, tx_id: &str, order_id: &str) {
+    /// Stores transaction together with all orders it settles. Either all orders
+    /// are linked with transaction or none, so no order is paid twice.
+    pub async fn transaction_saved_for_orders(
+        &self,
+        tx: TransactionEntity,
+        order_ids: &[String],
+    ) -> Result<String, GenericError> {
+        let tx_id = tx.tx_id.clone();
         if let Err(e) = self
             .payment()
-            .update_tx_id(order_id.to_string(), tx_id.to_string())
+            .insert_tx_for_payments(tx, order_ids.to_vec())
             .await
         {
-            log::error!("Failed to update for transaction {:?} : {:?}", tx_id, e)
-            // TO CHECK: Should it continue or stop the process...
+            log::error!(
+                "Failed to store transaction {:?} for orders {:?} : {:?}",
+                tx_id,
+                order_ids,
+                e
+            );
+            return Err(GenericError::new(e));
         }
+        Ok(tx_id)
     }
 
     pub async fn retry_send_transaction(&self, tx_id: &str, bump_gas: bool) {
@@ -351,3 +364,70 @@ impl Erc20Dao {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use web3::types::H160;
+
+    use ya_payment_driver::dao::init;
+    use ya_payment_driver::db::models::TxType;
+
+    use super::*;
+    use crate::erc20::ethereum::create_dao_entity;
+
+    const SENDER: &str = "0xfeaed3f817169c012d040f05c6c52bce5740fc37";
+
+    fn payment(order_id: &str) -> PaymentEntity {
+        PaymentEntity {
+            order_id: order_id.to_string(),
+            amount: utils::u256_to_big_endian_hex(U256::from(1)),
+            gas: utils::u256_to_big_endian_hex(U256::zero()),
+            sender: SENDER.to_string(),
+            recipient: "0xd4EA255B238E214A9A0E5656eC36Fe27CD14adAC".to_string(),
+            payment_due_date: Utc::now().naive_utc(),
+            status: PAYMENT_STATUS_NOT_YET,
+            tx_id: None,
+            network: Network::Dev,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_transaction_saved_for_orders() {
+        let db = DbExecutor::in_memory("erc20-dao").unwrap();
+        init(&db).await.unwrap();
+        let dao = Erc20Dao::new(db);
+        for order_id in ["1", "2", "3"] {
+            dao.payment().insert(payment(order_id)).await.unwrap();
+        }
+
+        let tx = create_dao_entity(
+            U256::zero(),
+            H160::zero(),
+            "0".to_string(),
+            None,
+            0,
+            String::new(),
+            Network::Dev,
+            Utc::now(),
+            TxType::Transfer,
+            None,
+        );
+        let orders = vec!["1".to_string(), "2".to_string()];
+        let tx_id = dao
+            .transaction_saved_for_orders(tx.clone(), &orders)
+            .await
+            .unwrap();
+        assert_eq!(dao.get_payments_based_on_tx(&tx_id).await.len(), 2);
+
+        // Transaction with the same id can't be stored again, so order stays pending.
+        assert!(dao
+            .transaction_saved_for_orders(tx, &["3".to_string()])
+            .await
+            .is_err());
+        let pending = dao.get_pending_payments(SENDER, Network::Dev).await;
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].order_id, "3");
+        assert_eq!(pending[0].tx_id, None);
+    }
+}
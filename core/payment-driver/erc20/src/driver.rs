@@ -224,6 +224,15 @@ impl PaymentDriver for Erc20Driver {
         }
         Ok(())
     }
+
+    async fn status(
+        &self,
+        _db: DbExecutor,
+        _caller: String,
+        msg: DriverStatus,
+    ) -> Result<Vec<RpcEndpointStatus>, GenericError> {
+        api::driver_status(msg).await
+    }
 }
 
 #[async_trait(?Send)]
@@ -4,14 +4,16 @@
 // Extrnal crates
 // use lazy_static::lazy_static;
 // use num_bigint::BigInt;
+use std::str::FromStr;
 use uuid::Uuid;
 
 // Workspace uses
 use ya_payment_driver::{
+    db::models::Network,
     driver::BigDecimal,
     model::{
-        GasDetails, GenericError, GetAccountBalance, GetAccountGasBalance, SchedulePayment,
-        ValidateAllocation, VerifyPayment,
+        DriverStatus, GasDetails, GenericError, GetAccountBalance, GetAccountGasBalance,
+        RpcEndpointStatus, SchedulePayment, ValidateAllocation, VerifyPayment,
     },
 };
 
@@ -19,7 +21,7 @@ use ya_payment_driver::{
 use crate::{
     dao::Erc20Dao,
     driver::PaymentDetails,
-    erc20::{utils, wallet},
+    erc20::{ethereum, utils, wallet},
     network,
 };
 
@@ -126,3 +128,12 @@ pub async fn validate_allocation(msg: ValidateAllocation) -> Result<bool, Generi
     // Ok(msg.amount <= (account_balance - total_allocated_amount - allocation_surcharge))
     Ok(msg.amount <= account_balance - total_allocated_amount)
 }
+
+pub async fn driver_status(msg: DriverStatus) -> Result<Vec<RpcEndpointStatus>, GenericError> {
+    log::debug!("driver_status: {:?}", msg);
+    let network = match msg.network {
+        Some(network) => Some(Network::from_str(&network).map_err(GenericError::new)?),
+        None => None,
+    };
+    Ok(ethereum::get_rpc_endpoints_status(network).await)
+}
@@ -3,8 +3,9 @@
 */
 // Extrnal crates
 use anyhow::anyhow;
-use chrono::{Duration, TimeZone, Utc};
+use chrono::{DateTime, Duration, TimeZone, Utc};
 use lazy_static::lazy_static;
+use std::collections::HashMap;
 use std::str::FromStr;
 use web3::types::{H256, U256};
 
@@ -47,6 +48,13 @@ lazy_static! {
         Ok(Ok(seconds)) => Duration::seconds(seconds),
         _ => Duration::seconds(200),
     };
+    /// Time, for which due payments are held back to be sent together with
+    /// later payments to the same recipient.
+    static ref ERC20_PAYMENT_BATCH_WINDOW: Duration =
+        match std::env::var("ERC20_PAYMENT_BATCH_WINDOW_SECS").map(|str| str.parse::<i64>()) {
+            Ok(Ok(seconds)) => Duration::seconds(seconds),
+            _ => Duration::seconds(0),
+        };
 }
 
 pub async fn confirm_payments(dao: &Erc20Dao, name: &str, network_key: &str) {
@@ -340,13 +348,49 @@ pub async fn process_payments_for_account(
         })?;
 
         log::debug!("Payments: nonce={}, details={:?}", &nonce, payments);
-        for payment in payments {
-            handle_payment(dao, payment, &mut nonce).await;
+        for batch in batch_payments(payments, *ERC20_PAYMENT_BATCH_WINDOW, Utc::now()) {
+            handle_payment_batch(dao, batch, &mut nonce).await;
         }
     }
     Ok(())
 }
 
+/// Groups payments by recipient, so each group can be settled with single transaction.
+/// Group is returned only if its oldest payment has been due for at least `window`,
+/// otherwise it waits for more payments to the same recipient.
+/// Groups are ordered by oldest payment due date.
+fn batch_payments(
+    payments: Vec<PaymentEntity>,
+    window: Duration,
+    now: DateTime<Utc>,
+) -> Vec<Vec<PaymentEntity>> {
+    let mut batches: Vec<Vec<PaymentEntity>> = vec![];
+    let mut by_recipient: HashMap<String, usize> = HashMap::new();
+
+    for payment in payments {
+        let recipient = payment.recipient.to_lowercase();
+        match by_recipient.get(&recipient) {
+            Some(idx) => batches[*idx].push(payment),
+            None => {
+                by_recipient.insert(recipient, batches.len());
+                batches.push(vec![payment]);
+            }
+        }
+    }
+
+    batches
+        .into_iter()
+        .filter_map(|mut batch| {
+            batch.sort_by_key(|payment| payment.payment_due_date);
+            let oldest = Utc.from_utc_datetime(&batch[0].payment_due_date);
+            match now >= oldest + window {
+                true => Some(batch),
+                false => None,
+            }
+        })
+        .collect()
+}
+
 pub async fn process_transactions(dao: &Erc20Dao, network: Network) {
     let transactions: Vec<TransactionEntity> = dao.get_unsent_txs(network).await;
 
@@ -359,27 +403,111 @@ pub async fn process_transactions(dao: &Erc20Dao, network: Network) {
     }
 }
 
-async fn handle_payment(dao: &Erc20Dao, payment: PaymentEntity, nonce: &mut U256) {
-    let details = utils::db_to_payment_details(&payment);
+/// Sends single transfer settling all payments in the batch.
+/// Batch must be non-empty and contain payments to the same recipient.
+async fn handle_payment_batch(dao: &Erc20Dao, batch: Vec<PaymentEntity>, nonce: &mut U256) {
+    let first_payment = &batch[0];
+    let network = first_payment.network;
     let tx_nonce = nonce.to_owned();
 
-    match wallet::make_transfer(&details, tx_nonce, payment.network, None, None, None).await {
+    let mut details = utils::db_to_payment_details(first_payment);
+    details.amount = batch
+        .iter()
+        .map(|payment| utils::db_amount_to_big_dec(payment.amount.clone()))
+        .sum::<BigDecimal>();
+
+    if batch.len() > 1 {
+        log::info!(
+            "Batching {} payments to {} into single transfer. amount={}",
+            batch.len(),
+            details.recipient,
+            details.amount
+        );
+    }
+
+    match wallet::make_transfer(&details, tx_nonce, network, None, None, None).await {
         Ok(db_tx) => {
-            let tx_id = dao.insert_raw_transaction(db_tx).await;
-            dao.transaction_saved(&tx_id, &payment.order_id).await;
-            *nonce += U256::from(1);
+            let order_ids: Vec<String> = batch.iter().map(|p| p.order_id.clone()).collect();
+            // If transaction wasn't stored, payments stay pending and will be
+            // retried in next send-out, so nonce can be reused.
+            if dao
+                .transaction_saved_for_orders(db_tx, &order_ids)
+                .await
+                .is_ok()
+            {
+                *nonce += U256::from(1);
+            }
         }
         Err(e) => {
-            let deadline = Utc.from_utc_datetime(&payment.payment_due_date) + *TX_SUMBIT_TIMEOUT;
-            if Utc::now() > deadline {
-                log::error!("Failed to submit erc20 transaction. Retry deadline reached. details={:?} error={}", payment, e);
-                dao.payment_failed(&payment.order_id).await;
-            } else {
-                log::warn!(
-                    "Failed to submit erc20 transaction. Payment will be retried until {}. details={:?} error={}",
-                    deadline, payment, e
-                );
-            };
+            for payment in batch {
+                let deadline =
+                    Utc.from_utc_datetime(&payment.payment_due_date) + *TX_SUMBIT_TIMEOUT;
+                if Utc::now() > deadline {
+                    log::error!("Failed to submit erc20 transaction. Retry deadline reached. details={:?} error={}", payment, e);
+                    dao.payment_failed(&payment.order_id).await;
+                } else {
+                    log::warn!(
+                        "Failed to submit erc20 transaction. Payment will be retried until {}. details={:?} error={}",
+                        deadline, payment, e
+                    );
+                };
+            }
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payment(order_id: &str, recipient: &str, due: DateTime<Utc>) -> PaymentEntity {
+        PaymentEntity {
+            order_id: order_id.to_string(),
+            amount: utils::u256_to_big_endian_hex(U256::from(1)),
+            gas: utils::u256_to_big_endian_hex(U256::zero()),
+            sender: "0xsender".to_string(),
+            recipient: recipient.to_string(),
+            payment_due_date: due.naive_utc(),
+            status: 1,
+            tx_id: None,
+            network: Network::Rinkeby,
+        }
+    }
+
+    fn order_ids(batches: &[Vec<PaymentEntity>]) -> Vec<Vec<&str>> {
+        batches
+            .iter()
+            .map(|batch| batch.iter().map(|p| p.order_id.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_payments_by_recipient() {
+        let now = Utc::now();
+        let payments = vec![
+            payment("1", "0xAA", now - Duration::seconds(30)),
+            payment("2", "0xbb", now - Duration::seconds(20)),
+            payment("3", "0xaa", now - Duration::seconds(10)),
+        ];
+
+        let batches = batch_payments(payments, Duration::zero(), now);
+        assert_eq!(order_ids(&batches), vec![vec!["1", "3"], vec!["2"]]);
+    }
+
+    #[test]
+    fn test_batch_payments_window() {
+        let now = Utc::now();
+        let payments = vec![
+            payment("1", "0xaa", now - Duration::seconds(120)),
+            payment("2", "0xbb", now - Duration::seconds(30)),
+            payment("3", "0xaa", now - Duration::seconds(10)),
+        ];
+
+        // Payments to 0xbb wait for more payments, until window passes.
+        let batches = batch_payments(payments.clone(), Duration::seconds(60), now);
+        assert_eq!(order_ids(&batches), vec![vec!["1", "3"]]);
+
+        let batches = batch_payments(payments, Duration::seconds(60), now + Duration::seconds(30));
+        assert_eq!(order_ids(&batches), vec![vec!["1", "3"], vec!["2"]]);
+    }
+}
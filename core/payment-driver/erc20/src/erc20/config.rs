@@ -17,6 +17,7 @@ pub struct EnvConfiguration {
     pub glm_contract_address: Address,
     pub glm_faucet_address: Option<Address>,
     pub required_confirmations: u64,
+    pub chain_id: u64,
 }
 
 lazy_static! {
@@ -38,7 +39,8 @@ lazy_static! {
                 Ok(Ok(x)) => x,
                 _ => 3,
             }
-        }
+        },
+        chain_id: 4,
     };
     pub static ref MAINNET_CONFIG: EnvConfiguration = EnvConfiguration {
         glm_contract_address: utils::str_to_addr(
@@ -52,7 +54,8 @@ lazy_static! {
                 Ok(Ok(x)) => x,
                 _ => 5,
             }
-        }
+        },
+        chain_id: 1,
     };
     pub static ref GOERLI_CONFIG: EnvConfiguration = EnvConfiguration {
         glm_contract_address: utils::str_to_addr(
@@ -66,7 +69,8 @@ lazy_static! {
                 Ok(Ok(x)) => x,
                 _ => 3,
             }
-        }
+        },
+        chain_id: 5,
     };
     pub static ref MUMBAI_CONFIG: EnvConfiguration = EnvConfiguration {
         glm_contract_address: utils::str_to_addr(
@@ -80,7 +84,8 @@ lazy_static! {
                 Ok(Ok(x)) => x,
                 _ => 3,
             }
-        }
+        },
+        chain_id: 80001,
     };
     pub static ref POLYGON_MAINNET_CONFIG: EnvConfiguration = EnvConfiguration {
         glm_contract_address: utils::str_to_addr(
@@ -94,6 +99,31 @@ lazy_static! {
                 Ok(Ok(x)) => x,
                 _ => 5,
             }
-        }
+        },
+        chain_id: 137,
+    };
+    /// Local development chain, like anvil or ganache. Default contract address
+    /// is the address of first contract deployed by default anvil account.
+    pub static ref DEV_CONFIG: EnvConfiguration = EnvConfiguration {
+        glm_contract_address: utils::str_to_addr(
+            &env::var("DEV_TGLM_CONTRACT_ADDRESS")
+                .unwrap_or_else(|_| "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string())
+        )
+        .unwrap(),
+        glm_faucet_address: env::var("DEV_TGLM_FAUCET_ADDRESS")
+            .ok()
+            .map(|addr| utils::str_to_addr(&addr).unwrap()),
+        required_confirmations: {
+            match env::var("ERC20_DEV_REQUIRED_CONFIRMATIONS").map(|s| s.parse()) {
+                Ok(Ok(x)) => x,
+                _ => 1,
+            }
+        },
+        chain_id: {
+            match env::var("DEV_CHAIN_ID").map(|s| s.parse()) {
+                Ok(Ok(x)) => x,
+                _ => 31337,
+            }
+        },
     };
 }
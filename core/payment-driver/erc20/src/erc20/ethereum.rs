@@ -1,13 +1,12 @@
 #![allow(clippy::too_many_arguments)]
 
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::time::Instant;
 
 use bigdecimal::BigDecimal;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use ethabi::Token;
 use lazy_static::lazy_static;
-use tokio::sync::RwLock;
+use tokio::time::timeout;
 use uuid::Uuid;
 use web3::{
     contract::{tokens::Tokenize, Contract, Options},
@@ -20,11 +19,14 @@ use web3::{
 use ya_client_model::NodeId;
 use ya_payment_driver::db::models::{Network, TransactionEntity, TransactionStatus, TxType};
 use ya_payment_driver::utils::big_dec_to_u256;
-use ya_payment_driver::{bus, model::GenericError};
+use ya_payment_driver::{
+    bus,
+    model::{GenericError, RpcEndpointStatus},
+};
 
 use crate::erc20::eth_utils::keccak256_hash;
 use crate::erc20::transaction::YagnaRawTransaction;
-use crate::erc20::{config, eth_utils};
+use crate::erc20::{config, eth_utils, rpc};
 
 #[derive(Clone, Debug, thiserror::Error)]
 pub enum ClientError {
@@ -74,7 +76,6 @@ lazy_static! {
     pub static ref GLM_FAUCET_GAS: U256 = U256::from(90_000);
     pub static ref GLM_TRANSFER_GAS: U256 = U256::from(55_000);
     pub static ref GLM_POLYGON_GAS_LIMIT: U256 = U256::from(100_000);
-    static ref WEB3_CLIENT_MAP: Arc<RwLock<HashMap<String, Web3<Http>>>> = Default::default();
 }
 const CREATE_FAUCET_FUNCTION: &str = "create";
 const BALANCE_ERC20_FUNCTION: &str = "balanceOf";
@@ -201,17 +202,41 @@ where
     F: FnMut(Web3<Http>) -> R,
     R: futures::Future<Output = Result<T, ClientError>>,
 {
-    let clients = get_clients(network).await?;
+    let endpoints = rpc::endpoints(network);
     let mut last_err: Option<ClientError> = None;
 
-    for client in clients {
-        match f(client).await {
-            Ok(result) => return Ok(result),
+    for endpoint in endpoints {
+        let start = Instant::now();
+        let result = match timeout(*rpc::ERC20_RPC_TIMEOUT, f(endpoint.client.clone())).await {
+            Ok(result) => result,
+            Err(_) => {
+                let e = format!(
+                    "RPC call to [{}] timed out after {:?}",
+                    endpoint.url,
+                    *rpc::ERC20_RPC_TIMEOUT
+                );
+                endpoint.record_failure(&e);
+                last_err.replace(ClientError::new(e));
+                continue;
+            }
+        };
+
+        match result {
+            Ok(result) => {
+                endpoint.record_success(start.elapsed());
+                return Ok(result);
+            }
             Err(ClientError::Web3(e)) => match e {
+                // Endpoint responded, so it's not the reason of failure.
                 Error::Internal | Error::Recovery(_) | Error::Rpc(_) | Error::Decoder(_) => {
-                    return Err(GenericError::new(e))
+                    endpoint.record_success(start.elapsed());
+                    return Err(GenericError::new(e));
+                }
+                e => {
+                    log::debug!("RPC endpoint [{}] failed: {}", endpoint.url, e);
+                    endpoint.record_failure(&e);
+                    last_err.replace(ClientError::Web3(e))
                 }
-                _ => continue,
             },
             Err(e) => last_err.replace(e),
         };
@@ -223,6 +248,43 @@ where
     }
 }
 
+/// Checks RPC endpoints, which weren't used recently.
+pub async fn check_rpc_endpoints() {
+    rpc::check_all(*rpc::ERC20_RPC_HEALTH_CHECK_INTERVAL).await
+}
+
+/// Starts job checking RPC endpoints. It runs independently of payment jobs,
+/// so unreachable endpoints don't delay payments confirmation and send-out.
+pub fn start_rpc_health_checks() {
+    let period = rpc::ERC20_RPC_HEALTH_CHECK_INTERVAL
+        .to_std()
+        .unwrap_or_default()
+        .max(std::time::Duration::from_secs(1));
+    tokio::task::spawn_local(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            check_rpc_endpoints().await;
+        }
+    });
+}
+
+/// Checks and returns health of RPC endpoints of given network,
+/// or of all networks used so far.
+pub async fn get_rpc_endpoints_status(network: Option<Network>) -> Vec<RpcEndpointStatus> {
+    let networks = match network {
+        Some(network) => vec![network],
+        None => rpc::used_networks(),
+    };
+
+    let mut status = vec![];
+    for network in networks {
+        rpc::check_health(network, Duration::zero()).await;
+        status.extend(rpc::status(network));
+    }
+    status
+}
+
 pub async fn block_number(network: Network) -> Result<U64, GenericError> {
     with_clients(network, block_number_with).await
 }
@@ -292,7 +354,7 @@ pub async fn sign_raw_transfer_transaction(
     network: Network,
     tx: &YagnaRawTransaction,
 ) -> Result<Vec<u8>, GenericError> {
-    let chain_id = network as u64;
+    let chain_id = get_chain_id(network);
     let node_id = NodeId::from(address.as_ref());
     let signature = bus::sign(node_id, eth_utils::get_tx_hash(tx, chain_id)).await?;
     Ok(signature)
@@ -526,67 +588,6 @@ async fn get_tx_receipt_with(
         .map_err(Into::into)
 }
 
-fn get_rpc_addr_from_env(network: Network) -> Vec<String> {
-    match network {
-        Network::Mainnet => {
-            collect_rpc_addr_from("MAINNET_GETH_ADDR", "https://geth.golem.network:55555")
-        }
-        Network::Rinkeby => collect_rpc_addr_from(
-            "RINKEBY_GETH_ADDR",
-            "http://geth.testnet.golem.network:55555",
-        ),
-        Network::Goerli => {
-            collect_rpc_addr_from("GOERLI_GETH_ADDR", "https://rpc.goerli.mudit.blog")
-        }
-        Network::Polygon => collect_rpc_addr_from(
-            "POLYGON_GETH_ADDR",
-            "https://bor.golem.network,https://polygon-rpc.com",
-        ),
-        Network::Mumbai => collect_rpc_addr_from(
-            "MUMBAI_GETH_ADDR",
-            "https://matic-mumbai.chainstacklabs.com",
-        ),
-    }
-}
-
-fn collect_rpc_addr_from(env: &str, default: &str) -> Vec<String> {
-    std::env::var(env)
-        .ok()
-        .unwrap_or_else(|| default.to_string())
-        .split(',')
-        .map(|path| path.to_string())
-        .collect()
-}
-
-async fn get_clients(network: Network) -> Result<Vec<Web3<Http>>, GenericError> {
-    let geth_addrs = get_rpc_addr_from_env(network);
-    let mut clients: Vec<Web3<Http>> = Default::default();
-
-    for geth_addr in geth_addrs {
-        {
-            let client_map = WEB3_CLIENT_MAP.read().await;
-            if let Some(client) = client_map.get(&geth_addr).cloned() {
-                clients.push(client);
-                continue;
-            }
-        }
-
-        let transport = match web3::transports::Http::new(&geth_addr) {
-            Ok(t) => t,
-            Err(_) => continue,
-        };
-
-        let client = Web3::new(transport);
-
-        let mut client_map = WEB3_CLIENT_MAP.write().await;
-        client_map.insert(geth_addr, client.clone());
-
-        clients.push(client);
-    }
-
-    Ok(clients)
-}
-
 fn get_env(network: Network) -> config::EnvConfiguration {
     match network {
         Network::Mainnet => *config::MAINNET_CONFIG,
@@ -594,9 +595,14 @@ fn get_env(network: Network) -> config::EnvConfiguration {
         Network::Goerli => *config::GOERLI_CONFIG,
         Network::Mumbai => *config::MUMBAI_CONFIG,
         Network::Polygon => *config::POLYGON_MAINNET_CONFIG,
+        Network::Dev => *config::DEV_CONFIG,
     }
 }
 
+pub fn get_chain_id(network: Network) -> u64 {
+    get_env(network).chain_id
+}
+
 fn prepare_contract(
     ethereum_client: &Web3<Http>,
     address: H160,
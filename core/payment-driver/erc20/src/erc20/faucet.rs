@@ -39,6 +39,12 @@ pub async fn request_glm(
     let balance = ethereum::get_balance(address, network).await?;
     if balance >= *MIN_ETH_BALANCE {
         log::info!("Enough tETH balance.");
+    } else if network == Network::Dev {
+        // Dev chains have no faucet service, but come with prefunded accounts.
+        return Err(GenericError::new(format!(
+            "Not enough tETH balance. Transfer some from one of dev chain's prefunded accounts to {}",
+            str_addr
+        )));
     } else {
         log::info!(
             "Requesting tETH from erc20 faucet... address = {}",
@@ -0,0 +1,306 @@
+/*
+    RPC endpoints of ethereum networks with health and latency tracking.
+
+    Endpoints are configured per network as comma separated list of urls.
+    Calls go to healthy endpoints with the lowest latency first, failing over
+    to next endpoints on transport errors and timeouts.
+*/
+
+// External crates
+use chrono::{DateTime, Duration, Utc};
+use futures::future::join_all;
+use lazy_static::lazy_static;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use web3::{transports::Http, Web3};
+
+// Workspace uses
+use ya_payment_driver::{db::models::Network, model::RpcEndpointStatus};
+
+lazy_static! {
+    static ref ENDPOINTS: Mutex<HashMap<String, Vec<Arc<Endpoint>>>> = Default::default();
+    /// Number of failures in a row, after which endpoint is moved to the end of the list.
+    static ref ERC20_RPC_MAX_FAILURES: u32 =
+        match std::env::var("ERC20_RPC_MAX_FAILURES").map(|str| str.parse()) {
+            Ok(Ok(failures)) => failures,
+            _ => 3,
+        };
+    pub static ref ERC20_RPC_TIMEOUT: std::time::Duration =
+        match std::env::var("ERC20_RPC_TIMEOUT_SECS").map(|str| str.parse()) {
+            Ok(Ok(seconds)) => std::time::Duration::from_secs(seconds),
+            _ => std::time::Duration::from_secs(10),
+        };
+    /// Endpoints not used for that long are checked by health check job.
+    pub static ref ERC20_RPC_HEALTH_CHECK_INTERVAL: Duration =
+        match std::env::var("ERC20_RPC_HEALTH_CHECK_INTERVAL_SECS").map(|str| str.parse()) {
+            Ok(Ok(seconds)) => Duration::seconds(seconds),
+            _ => Duration::seconds(60),
+        };
+}
+
+/// Weight of the newest sample in average latency.
+const LATENCY_SMOOTHING: f64 = 0.3;
+
+#[derive(Debug, Default)]
+struct Health {
+    successes: u64,
+    failures: u64,
+    failures_in_row: u32,
+    latency_ms: Option<f64>,
+    last_error: Option<String>,
+    last_checked: Option<DateTime<Utc>>,
+}
+
+impl Health {
+    fn is_healthy(&self) -> bool {
+        self.failures_in_row < *ERC20_RPC_MAX_FAILURES
+    }
+
+    /// Lower is better.
+    fn score(&self) -> f64 {
+        match self.is_healthy() {
+            true => self.latency_ms.unwrap_or(0.),
+            false => f64::INFINITY,
+        }
+    }
+}
+
+pub struct Endpoint {
+    pub url: String,
+    pub client: Web3<Http>,
+    health: Mutex<Health>,
+}
+
+impl Endpoint {
+    fn new(url: String) -> Option<Endpoint> {
+        let transport = match Http::new(&url) {
+            Ok(transport) => transport,
+            Err(e) => {
+                log::warn!("Invalid RPC endpoint [{}]: {}", url, e);
+                return None;
+            }
+        };
+        Some(Endpoint {
+            url,
+            client: Web3::new(transport),
+            health: Default::default(),
+        })
+    }
+
+    pub fn record_success(&self, latency: std::time::Duration) {
+        let mut health = self.health.lock().unwrap();
+        let latency = latency.as_secs_f64() * 1000.;
+        health.latency_ms = Some(match health.latency_ms {
+            Some(avg) => avg + LATENCY_SMOOTHING * (latency - avg),
+            None => latency,
+        });
+        health.successes += 1;
+        health.failures_in_row = 0;
+        health.last_checked = Some(Utc::now());
+    }
+
+    pub fn record_failure(&self, error: impl Display) {
+        let mut health = self.health.lock().unwrap();
+        health.failures += 1;
+        health.failures_in_row += 1;
+        health.last_error = Some(error.to_string());
+        health.last_checked = Some(Utc::now());
+
+        if health.failures_in_row == *ERC20_RPC_MAX_FAILURES {
+            log::warn!(
+                "RPC endpoint [{}] marked as unhealthy after {} failures. Last error: {}",
+                self.url,
+                health.failures_in_row,
+                error
+            );
+        }
+    }
+
+    fn score(&self) -> f64 {
+        self.health.lock().unwrap().score()
+    }
+
+    fn needs_check(&self, max_age: Duration) -> bool {
+        match self.health.lock().unwrap().last_checked {
+            Some(last_checked) => Utc::now() - last_checked >= max_age,
+            None => true,
+        }
+    }
+
+    async fn check(&self) {
+        let start = Instant::now();
+        match tokio::time::timeout(*ERC20_RPC_TIMEOUT, self.client.eth().block_number()).await {
+            Ok(Ok(_)) => self.record_success(start.elapsed()),
+            Ok(Err(e)) => self.record_failure(e),
+            Err(_) => self.record_failure(format!("Timed out after {:?}", *ERC20_RPC_TIMEOUT)),
+        }
+    }
+
+    fn status(&self, network: Network) -> RpcEndpointStatus {
+        let health = self.health.lock().unwrap();
+        RpcEndpointStatus {
+            network: network.to_string(),
+            url: self.url.clone(),
+            is_healthy: health.is_healthy(),
+            latency_ms: health.latency_ms.map(|latency| latency.round() as u64),
+            successes: health.successes,
+            failures: health.failures,
+            last_error: health.last_error.clone(),
+            last_checked: health.last_checked,
+        }
+    }
+}
+
+/// Returns endpoints of the network ordered by preference: healthy endpoints
+/// with the lowest latency first. Unhealthy endpoints are left as last resort.
+pub fn endpoints(network: Network) -> Vec<Arc<Endpoint>> {
+    let mut endpoints = ENDPOINTS
+        .lock()
+        .unwrap()
+        .entry(network.to_string())
+        .or_insert_with(|| {
+            get_rpc_addr_from_env(network)
+                .into_iter()
+                .filter_map(Endpoint::new)
+                .map(Arc::new)
+                .collect()
+        })
+        .clone();
+    sort_by_preference(&mut endpoints);
+    endpoints
+}
+
+fn sort_by_preference(endpoints: &mut [Arc<Endpoint>]) {
+    // Stable sort keeps configured order for endpoints with equal score.
+    endpoints.sort_by(|a, b| a.score().partial_cmp(&b.score()).unwrap_or(Ordering::Equal));
+}
+
+/// Checks endpoints of the network, which weren't used for `max_age`.
+/// Endpoints are checked concurrently, so unreachable ones don't delay the others.
+pub async fn check_health(network: Network, max_age: Duration) {
+    let checks = endpoints(network)
+        .into_iter()
+        .filter(|endpoint| endpoint.needs_check(max_age))
+        .map(|endpoint| async move { endpoint.check().await });
+    join_all(checks).await;
+}
+
+/// Checks endpoints of all networks used so far.
+pub async fn check_all(max_age: Duration) {
+    let checks = used_networks()
+        .into_iter()
+        .map(|network| check_health(network, max_age));
+    join_all(checks).await;
+}
+
+pub fn status(network: Network) -> Vec<RpcEndpointStatus> {
+    endpoints(network)
+        .iter()
+        .map(|endpoint| endpoint.status(network))
+        .collect()
+}
+
+pub fn used_networks() -> Vec<Network> {
+    ENDPOINTS
+        .lock()
+        .unwrap()
+        .keys()
+        .filter_map(|network| Network::from_str(network).ok())
+        .collect()
+}
+
+fn get_rpc_addr_from_env(network: Network) -> Vec<String> {
+    match network {
+        Network::Mainnet => {
+            collect_rpc_addr_from("MAINNET_GETH_ADDR", "https://geth.golem.network:55555")
+        }
+        Network::Rinkeby => collect_rpc_addr_from(
+            "RINKEBY_GETH_ADDR",
+            "http://geth.testnet.golem.network:55555",
+        ),
+        Network::Goerli => {
+            collect_rpc_addr_from("GOERLI_GETH_ADDR", "https://rpc.goerli.mudit.blog")
+        }
+        Network::Polygon => collect_rpc_addr_from(
+            "POLYGON_GETH_ADDR",
+            "https://bor.golem.network,https://polygon-rpc.com",
+        ),
+        Network::Mumbai => collect_rpc_addr_from(
+            "MUMBAI_GETH_ADDR",
+            "https://matic-mumbai.chainstacklabs.com",
+        ),
+        Network::Dev => collect_rpc_addr_from("DEV_GETH_ADDR", "http://127.0.0.1:8545"),
+    }
+}
+
+fn collect_rpc_addr_from(env: &str, default: &str) -> Vec<String> {
+    std::env::var(env)
+        .ok()
+        .unwrap_or_else(|| default.to_string())
+        .split(',')
+        .map(|path| path.trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint(url: &str) -> Endpoint {
+        Endpoint::new(url.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_endpoint_health() {
+        let endpoint = endpoint("http://127.0.0.1:1");
+        assert!(endpoint.needs_check(Duration::seconds(60)));
+
+        endpoint.record_success(std::time::Duration::from_millis(100));
+        endpoint.record_success(std::time::Duration::from_millis(200));
+        let status = endpoint.status(Network::Dev);
+        assert!(status.is_healthy);
+        assert_eq!(status.latency_ms, Some(130));
+        assert!(!endpoint.needs_check(Duration::seconds(60)));
+
+        for _ in 0..*ERC20_RPC_MAX_FAILURES {
+            endpoint.record_failure("connection refused");
+        }
+        let status = endpoint.status(Network::Dev);
+        assert!(!status.is_healthy);
+        assert_eq!(status.failures, *ERC20_RPC_MAX_FAILURES as u64);
+        assert_eq!(status.last_error, Some("connection refused".to_string()));
+        assert_eq!(endpoint.score(), f64::INFINITY);
+
+        // Single success makes endpoint healthy again.
+        endpoint.record_success(std::time::Duration::from_millis(100));
+        assert!(endpoint.status(Network::Dev).is_healthy);
+    }
+
+    #[test]
+    fn test_endpoints_order() {
+        let fast = endpoint("http://127.0.0.1:1");
+        let slow = endpoint("http://127.0.0.1:2");
+        let broken = endpoint("http://127.0.0.1:3");
+        fast.record_success(std::time::Duration::from_millis(50));
+        slow.record_success(std::time::Duration::from_millis(500));
+        for _ in 0..*ERC20_RPC_MAX_FAILURES {
+            broken.record_failure("timeout");
+        }
+
+        let mut endpoints = vec![Arc::new(broken), Arc::new(slow), Arc::new(fast)];
+        sort_by_preference(&mut endpoints);
+        let urls: Vec<&str> = endpoints.iter().map(|e| e.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "http://127.0.0.1:1",
+                "http://127.0.0.1:2",
+                "http://127.0.0.1:3"
+            ]
+        );
+    }
+}
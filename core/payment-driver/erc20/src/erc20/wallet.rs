@@ -350,7 +350,8 @@ pub async fn send_transactions(
         )
         .await;
 
-        let signed = eth_utils::encode_signed_tx(&raw_tx, signature, network as u64);
+        let signed =
+            eth_utils::encode_signed_tx(&raw_tx, signature, ethereum::get_chain_id(network));
 
         match ethereum::send_tx(signed, network).await {
             Ok(tx_hash) => {
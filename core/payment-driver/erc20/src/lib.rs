@@ -37,6 +37,13 @@ pub const POLYGON_MAINNET_PLATFORM: &str = "erc20-polygon-glm";
 pub const POLYGON_MAINNET_CURRENCY_SHORT: &str = "MATIC";
 pub const POLYGON_MAINNET_CURRENCY_LONG: &str = "Polygon";
 
+pub const DEV_NETWORK: &str = "dev";
+pub const DEV_TOKEN: &str = "tGLM";
+pub const DEV_PLATFORM: &str = "erc20-dev-tglm";
+pub const DEV_CURRENCY_SHORT: &str = "tETH";
+pub const DEV_CURRENCY_LONG: &str = "Dev Ether";
+
+pub use driver::Erc20Driver;
 pub use service::Erc20Service as PaymentDriverService;
 
 // Private
@@ -7,6 +7,7 @@ use ya_payment_driver::{db::models::Network as DbNetwork, driver::Network, model
 
 // Local uses
 use crate::{
+    DEV_CURRENCY_LONG, DEV_CURRENCY_SHORT, DEV_NETWORK, DEV_PLATFORM, DEV_TOKEN,
     GOERLI_CURRENCY_LONG, GOERLI_CURRENCY_SHORT, GOERLI_NETWORK, GOERLI_PLATFORM, GOERLI_TOKEN,
     MAINNET_CURRENCY_LONG, MAINNET_CURRENCY_SHORT, MAINNET_NETWORK, MAINNET_PLATFORM,
     MAINNET_TOKEN, MUMBAI_CURRENCY_LONG, MUMBAI_CURRENCY_SHORT, MUMBAI_NETWORK, MUMBAI_PLATFORM,
@@ -47,6 +48,12 @@ lazy_static::lazy_static! {
             tokens: hashmap! {
                 POLYGON_MAINNET_TOKEN.to_string() => POLYGON_MAINNET_PLATFORM.to_string()
             }
+        },
+        DEV_NETWORK.to_string() => Network {
+            default_token: DEV_TOKEN.to_string(),
+            tokens: hashmap! {
+                DEV_TOKEN.to_string() => DEV_PLATFORM.to_string()
+            }
         }
     };
     pub static ref RINKEBY_DB_NETWORK: DbNetwork = DbNetwork::from_str(RINKEBY_NETWORK).unwrap();
@@ -54,6 +61,7 @@ lazy_static::lazy_static! {
     pub static ref MAINNET_DB_NETWORK: DbNetwork = DbNetwork::from_str(MAINNET_NETWORK).unwrap();
     pub static ref MUMBAI_DB_NETWORK: DbNetwork = DbNetwork::from_str(MUMBAI_NETWORK).unwrap();
     pub static ref POLYGON_MAINNET_DB_NETWORK: DbNetwork = DbNetwork::from_str(POLYGON_MAINNET_NETWORK).unwrap();
+    pub static ref DEV_DB_NETWORK: DbNetwork = DbNetwork::from_str(DEV_NETWORK).unwrap();
 }
 
 pub fn platform_to_network_token(platform: String) -> Result<(DbNetwork, String), GenericError> {
@@ -66,6 +74,7 @@ pub fn platform_to_network_token(platform: String) -> Result<(DbNetwork, String)
             *POLYGON_MAINNET_DB_NETWORK,
             POLYGON_MAINNET_TOKEN.to_owned(),
         )),
+        DEV_PLATFORM => Ok((*DEV_DB_NETWORK, DEV_TOKEN.to_owned())),
         other => Err(GenericError::new(format!(
             "Unable to find network for platform: {}",
             other
@@ -125,6 +134,7 @@ pub fn platform_to_currency(platform: String) -> Result<(String, String), Generi
             POLYGON_MAINNET_CURRENCY_SHORT.to_owned(),
             POLYGON_MAINNET_CURRENCY_LONG.to_owned(),
         )),
+        DEV_PLATFORM => Ok((DEV_CURRENCY_SHORT.to_owned(), DEV_CURRENCY_LONG.to_owned())),
         other => Err(GenericError::new(format!(
             "Unable to find network currency for platform: {}",
             other
@@ -16,6 +16,7 @@ use ya_service_api_interfaces::Provider;
 
 // Local uses
 use crate::driver::Erc20Driver;
+use crate::erc20::ethereum;
 
 pub struct Erc20Service;
 
@@ -42,6 +43,9 @@ impl Erc20Service {
         Cron::new(driver_rc.clone());
         log::debug!("Cron started");
 
+        ethereum::start_rpc_health_checks();
+        log::debug!("RPC health checks started");
+
         log::info!("Successfully connected Erc20Service to gsb.");
         Ok(())
     }
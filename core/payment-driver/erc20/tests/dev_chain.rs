@@ -0,0 +1,149 @@
+/*
+    End-to-end tests of erc20 driver against local development chain.
+
+    Require running chain (e.g. `anvil`) and GLM token contract deployed on it,
+    with balance on account used by tests. See Readme for details.
+*/
+
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use ethsign::SecretKey;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use web3::types::H160;
+
+use ya_client_model::NodeId;
+use ya_core_model::{identity, payment::local as payment_srv};
+use ya_erc20_driver::{erc20::ethereum, Erc20Driver, DEV_PLATFORM, DRIVER_NAME};
+use ya_payment_driver::{
+    cron::PaymentDriverCron,
+    dao::{init, DbExecutor},
+    db::models::Network,
+    driver::{IdentityEvent, PaymentDriver},
+    model::SchedulePayment,
+};
+use ya_service_bus::typed as bus;
+
+/// First of default anvil accounts.
+const DEFAULT_PRIVATE_KEY: &str =
+    "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const RECIPIENT: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+const MAX_ATTEMPTS: u32 = 30;
+
+fn secret_key() -> SecretKey {
+    let key = std::env::var("DEV_PRIVATE_KEY").unwrap_or_else(|_| DEFAULT_PRIVATE_KEY.into());
+    let key = hex::decode(key.trim_start_matches("0x")).unwrap();
+    SecretKey::from_raw(&key).unwrap()
+}
+
+/// Replaces identity service, signing with single key.
+fn mock_identity(key: SecretKey) {
+    bus::bind(identity::BUS_ID, move |msg: identity::Sign| {
+        let signature = key.sign(&msg.payload).map(|s| {
+            let mut v = vec![s.v];
+            v.extend_from_slice(&s.r[..]);
+            v.extend_from_slice(&s.s[..]);
+            v
+        });
+        async move { signature.map_err(identity::Error::new_err_msg) }
+    });
+}
+
+/// Replaces payment service, passing notifications to returned channel.
+fn mock_payment() -> mpsc::UnboundedReceiver<payment_srv::NotifyPayment> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    bus::bind(
+        payment_srv::BUS_ID,
+        move |msg: payment_srv::NotifyPayment| {
+            let result = sender.send(msg).map_err(payment_srv::GenericError::new);
+            async move { result }
+        },
+    );
+    receiver
+}
+
+#[cfg_attr(not(feature = "dev-chain-tests"), ignore)]
+#[actix_rt::test]
+async fn test_schedule_and_confirm_payments() {
+    let _ = env_logger::builder().is_test(true).try_init();
+
+    let key = secret_key();
+    let sender = format!("0x{}", hex::encode(key.public().address()));
+    let recipient = H160::from_str(RECIPIENT).unwrap();
+    let node_id = NodeId::from_str(&sender).unwrap();
+
+    mock_identity(key);
+    let mut notifications = mock_payment();
+
+    let db = DbExecutor::in_memory("erc20-dev-chain").unwrap();
+    init(&db).await.unwrap();
+    let driver = Erc20Driver::new(db.clone());
+    driver
+        .account_event(
+            db.clone(),
+            String::new(),
+            IdentityEvent::AccountUnlocked { identity: node_id },
+        )
+        .await
+        .unwrap();
+
+    let balance_before = ethereum::get_glm_balance(recipient, Network::Dev)
+        .await
+        .unwrap();
+
+    // Both payments go to the same recipient, so they should be batched.
+    let amounts = vec![BigDecimal::from_str("1.5").unwrap(), BigDecimal::from(2)];
+    let mut order_ids = vec![];
+    for amount in &amounts {
+        let order_id = driver
+            .schedule_payment(
+                db.clone(),
+                String::new(),
+                SchedulePayment::new(
+                    amount.clone(),
+                    sender.clone(),
+                    RECIPIENT.to_lowercase(),
+                    DEV_PLATFORM.to_string(),
+                    Utc::now(),
+                ),
+            )
+            .await
+            .unwrap();
+        order_ids.push(order_id);
+    }
+
+    let mut notification = None;
+    for _ in 0..MAX_ATTEMPTS {
+        driver.send_out_payments().await;
+        driver.confirm_payments().await;
+        if let Ok(msg) = notifications.try_recv() {
+            notification = Some(msg);
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    let notification = notification.expect("Payments weren't confirmed");
+    let total: BigDecimal = amounts.into_iter().sum();
+    assert_eq!(notification.driver, DRIVER_NAME);
+    assert_eq!(notification.platform, DEV_PLATFORM);
+    assert_eq!(notification.amount, total);
+    assert_eq!(
+        notification.recipient.to_lowercase(),
+        RECIPIENT.to_lowercase()
+    );
+
+    let mut notified_orders = notification.order_ids.clone();
+    notified_orders.sort();
+    order_ids.sort();
+    assert_eq!(notified_orders, order_ids);
+
+    let balance_after = ethereum::get_glm_balance(recipient, Network::Dev)
+        .await
+        .unwrap();
+    assert_eq!(
+        ya_payment_driver::utils::u256_to_big_dec(balance_after - balance_before),
+        total
+    );
+}
@@ -103,10 +103,29 @@ pub enum PaymentCli {
     /// List registered drivers, networks, tokens and platforms
     Drivers,
 
+    /// Payment driver diagnostics
+    Driver {
+        #[structopt(subcommand)]
+        command: DriverCommand,
+    },
+
     /// Clear all existing allocations
     ReleaseAllocations,
 }
 
+#[derive(StructOpt, Debug)]
+pub enum DriverCommand {
+    /// Check health of blockchain RPC endpoints used by the driver
+    Status {
+        /// Payment driver
+        #[structopt(long, default_value = pay::DEFAULT_PAYMENT_DRIVER)]
+        driver: String,
+        /// Payment network [default: all networks used by the driver]
+        #[structopt(long)]
+        network: Option<String>,
+    },
+}
+
 #[derive(StructOpt, Debug)]
 pub enum InvoiceCommand {
     Status {
@@ -380,6 +399,40 @@ impl PaymentCli {
                         .collect(),
                 }.into())
             }
+            PaymentCli::Driver {
+                command: DriverCommand::Status { driver, network },
+            } => {
+                let endpoints = wallet::driver_status(driver, network).await?;
+                if ctx.json_output {
+                    return CommandOutput::object(endpoints);
+                }
+                Ok(ResponseTable {
+                    columns: vec![
+                        "network".to_owned(),
+                        "endpoint".to_owned(),
+                        "healthy".to_owned(),
+                        "latency [ms]".to_owned(),
+                        "ok".to_owned(),
+                        "failed".to_owned(),
+                        "last error".to_owned(),
+                    ],
+                    values: endpoints
+                        .into_iter()
+                        .map(|endpoint| {
+                            serde_json::json! {[
+                                endpoint.network,
+                                endpoint.url,
+                                if endpoint.is_healthy { "yes" } else { "no" },
+                                endpoint.latency_ms.map(|l| l.to_string()).unwrap_or_default(),
+                                endpoint.successes,
+                                endpoint.failures,
+                                endpoint.last_error.unwrap_or_default(),
+                            ]}
+                        })
+                        .collect(),
+                }
+                .into())
+            }
             PaymentCli::ReleaseAllocations => {
                 let _ = bus::service(pay::BUS_ID)
                     .call(pay::ReleaseAllocations {})
@@ -2,7 +2,9 @@
 use bigdecimal::BigDecimal;
 
 // Workspace uses
-use ya_core_model::driver::{driver_bus_id, Enter, Exit, Fund, Transfer};
+use ya_core_model::driver::{
+    driver_bus_id, DriverStatus, Enter, Exit, Fund, RpcEndpointStatus, Transfer,
+};
 use ya_service_bus::typed as bus;
 
 pub async fn fund(
@@ -72,3 +74,13 @@ pub async fn transfer(
     let tx_id = bus::service(driver_id).call(message).await??;
     Ok(tx_id)
 }
+
+pub async fn driver_status(
+    driver: String,
+    network: Option<String>,
+) -> anyhow::Result<Vec<RpcEndpointStatus>> {
+    let driver_id = driver_bus_id(driver);
+    let message = DriverStatus::new(network);
+    let status = bus::service(driver_id).call(message).await??;
+    Ok(status)
+}
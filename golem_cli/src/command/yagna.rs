@@ -93,6 +93,14 @@ lazy_static! {
                 token: "GLM",
             },
         );
+        erc20.insert(
+            NetworkName::Dev.into(),
+            PaymentPlatform {
+                platform: "erc20-dev-tglm",
+                driver: "erc20",
+                token: "tGLM",
+            },
+        );
 
         PaymentDriver {
             platforms: erc20,
@@ -156,6 +164,7 @@ lazy_static! {
                 NetworkName::Rinkeby,
                 NetworkName::Mumbai,
                 NetworkName::Goerli,
+                NetworkName::Dev,
             ],
         );
         ngm
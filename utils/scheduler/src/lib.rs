@@ -3,9 +3,11 @@ use std::time::Duration;
 
 mod task;
 mod trigger;
+mod window;
 
 pub use task::Task;
 pub use trigger::{Interval, Trigger};
+pub use window::{Schedule, Window, WindowParseError};
 
 pub struct Job {
     task: Task,
@@ -0,0 +1,409 @@
+use chrono::prelude::{DateTime, Datelike, NaiveDateTime, TimeZone, Timelike};
+use chrono::{Duration, LocalResult};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+const DAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const MINUTES_PER_WEEK: u32 = 7 * MINUTES_PER_DAY;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum WindowParseError {
+    #[error("expected '<days> <HH:MM>-<HH:MM>', got '{0}'")]
+    Format(String),
+    #[error("invalid day: '{0}'")]
+    Day(String),
+    #[error("invalid time: '{0}'")]
+    Time(String),
+}
+
+/// Recurring time window in local time, written in cron-like form:
+/// `<days> <HH:MM>-<HH:MM>`, where days are `*`, list or range of
+/// day names, e.g. `mon-fri 18:00-08:00` or `sat,sun 00:00-24:00`.
+/// If end is before start, window lasts until next day.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Window {
+    /// Days on which window starts, from Monday.
+    days: [bool; 7],
+    /// Minutes from midnight.
+    start: u32,
+    end: u32,
+}
+
+impl Window {
+    fn is_overnight(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// Checks if `time` falls within this window.
+    pub fn contains<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        let day = time.weekday().num_days_from_monday() as usize;
+        let previous = (day + 6) % 7;
+        let minute = time.hour() * 60 + time.minute();
+
+        if self.is_overnight() {
+            (self.days[day] && minute >= self.start) || (self.days[previous] && minute < self.end)
+        } else {
+            self.days[day] && minute >= self.start && minute < self.end
+        }
+    }
+
+    /// Returns end of window occurrence containing `time`.
+    pub fn end_of<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        if !self.contains(time) {
+            return None;
+        }
+
+        let midnight = time.naive_local().date().and_hms(0, 0, 0);
+        let minute = time.hour() * 60 + time.minute();
+        let end = match self.is_overnight() && minute >= self.start {
+            true => self.end + MINUTES_PER_DAY,
+            false => self.end,
+        };
+        Some(to_timezone(
+            &time.timezone(),
+            midnight + Duration::minutes(end as i64),
+        ))
+    }
+
+    /// Marks minutes of week (starting from Monday midnight) covered by window.
+    fn cover(&self, covered: &mut [bool]) {
+        let length = match self.is_overnight() {
+            true => self.end + MINUTES_PER_DAY - self.start,
+            false => self.end - self.start,
+        };
+        for day in (0..7).filter(|day| self.days[*day as usize]) {
+            let start = day * MINUTES_PER_DAY + self.start;
+            for minute in start..start + length {
+                covered[(minute % MINUTES_PER_WEEK) as usize] = true;
+            }
+        }
+    }
+}
+
+fn minute_of_week<Tz: TimeZone>(time: &DateTime<Tz>) -> u32 {
+    time.weekday().num_days_from_monday() * MINUTES_PER_DAY + time.hour() * 60 + time.minute()
+}
+
+/// Converts wall clock time to `tz`. Windows are defined in wall clock time,
+/// so their ends don't move on DST changes. Time skipped by DST change
+/// is moved forward to first existing minute.
+fn to_timezone<Tz: TimeZone>(tz: &Tz, mut time: NaiveDateTime) -> DateTime<Tz> {
+    loop {
+        match tz.from_local_datetime(&time) {
+            LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => return time,
+            LocalResult::None => time += Duration::minutes(1),
+        }
+    }
+}
+
+/// Set of windows. Empty schedule means always available.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Schedule {
+    pub windows: Vec<Window>,
+}
+
+impl Schedule {
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    pub fn contains<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        self.is_empty() || self.windows.iter().any(|window| window.contains(time))
+    }
+
+    /// Returns time, when schedule stops covering `time`. Windows
+    /// following each other are treated as continuous period.
+    /// Returns `None` if `time` isn't covered and for schedules without
+    /// gaps (including empty schedule), which never end.
+    pub fn end_of<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let mut covered = vec![false; MINUTES_PER_WEEK as usize];
+        for window in self.windows.iter() {
+            window.cover(&mut covered);
+        }
+
+        let now = minute_of_week(time);
+        if !covered[now as usize] {
+            return None;
+        }
+        let gap = (1..MINUTES_PER_WEEK)
+            .find(|offset| !covered[((now + offset) % MINUTES_PER_WEEK) as usize])?;
+
+        let minute = time
+            .naive_local()
+            .date()
+            .and_hms(time.hour(), time.minute(), 0);
+        Some(to_timezone(
+            &time.timezone(),
+            minute + Duration::minutes(gap as i64),
+        ))
+    }
+}
+
+fn parse_day(day: &str) -> Result<usize, WindowParseError> {
+    DAYS.iter()
+        .position(|d| d.eq_ignore_ascii_case(day))
+        .ok_or_else(|| WindowParseError::Day(day.to_string()))
+}
+
+fn parse_days(days: &str) -> Result<[bool; 7], WindowParseError> {
+    if days == "*" {
+        return Ok([true; 7]);
+    }
+
+    let mut result = [false; 7];
+    for part in days.split(',') {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse_day(from)?, parse_day(to)?);
+                let mut day = from;
+                loop {
+                    result[day] = true;
+                    if day == to {
+                        break;
+                    }
+                    day = (day + 1) % 7;
+                }
+            }
+            None => result[parse_day(part)?] = true,
+        }
+    }
+    Ok(result)
+}
+
+fn parse_time(time: &str) -> Result<u32, WindowParseError> {
+    let error = || WindowParseError::Time(time.to_string());
+    let (hours, minutes) = time.split_once(':').ok_or_else(error)?;
+    let hours: u32 = hours.parse().map_err(|_| error())?;
+    let minutes: u32 = minutes.parse().map_err(|_| error())?;
+
+    let total = hours * 60 + minutes;
+    if minutes >= 60 || total > MINUTES_PER_DAY {
+        return Err(error());
+    }
+    Ok(total)
+}
+
+impl FromStr for Window {
+    type Err = WindowParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let format_error = || WindowParseError::Format(s.to_string());
+        let mut parts = s.split_whitespace();
+        let (days, hours) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(days), Some(hours), None) => (days, hours),
+            _ => return Err(format_error()),
+        };
+        let (start, end) = hours.split_once('-').ok_or_else(format_error)?;
+
+        let window = Window {
+            days: parse_days(days)?,
+            start: parse_time(start)?,
+            end: parse_time(end)?,
+        };
+        if window.start == MINUTES_PER_DAY {
+            return Err(WindowParseError::Time(start.to_string()));
+        }
+        Ok(window)
+    }
+}
+
+impl TryFrom<String> for Window {
+    type Error = WindowParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = match self.days {
+            [true, true, true, true, true, true, true] => "*".to_string(),
+            days => DAYS
+                .iter()
+                .zip(days.iter())
+                .filter(|(_, enabled)| **enabled)
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(","),
+        };
+        write!(
+            f,
+            "{} {:02}:{:02}-{:02}:{:02}",
+            days,
+            self.start / 60,
+            self.start % 60,
+            self.end / 60,
+            self.end % 60
+        )
+    }
+}
+
+impl fmt::Debug for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Window({})", self)
+    }
+}
+
+impl From<Window> for String {
+    fn from(window: Window) -> Self {
+        window.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Local, NaiveDate};
+
+    fn time(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // 2024-01-01 was Monday.
+        Local.ymd(2024, 1, day).and_hms(hour, minute, 0)
+    }
+
+    /// Time zone moving clocks from 02:00 to 03:00 on Sunday, 2024-03-31
+    /// and changing offset from UTC+1 to UTC+2.
+    #[derive(Clone, Copy, Debug)]
+    struct Dst;
+
+    impl Dst {
+        fn change() -> NaiveDateTime {
+            NaiveDate::from_ymd(2024, 3, 31).and_hms(2, 0, 0)
+        }
+    }
+
+    impl TimeZone for Dst {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            Dst
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms(0, 0, 0))
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            if *local < Dst::change() {
+                LocalResult::Single(FixedOffset::east(3600))
+            } else if *local < Dst::change() + Duration::hours(1) {
+                LocalResult::None
+            } else {
+                LocalResult::Single(FixedOffset::east(7200))
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_hms(0, 0, 0))
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            match *utc < Dst::change() - Duration::hours(1) {
+                true => FixedOffset::east(3600),
+                false => FixedOffset::east(7200),
+            }
+        }
+    }
+
+    #[test]
+    fn test_window_parse() {
+        let window: Window = "mon-fri 18:00-08:30".parse().unwrap();
+        assert_eq!(window.to_string(), "mon,tue,wed,thu,fri 18:00-08:30");
+        assert_eq!(
+            "* 00:00-24:00".parse::<Window>().unwrap().to_string(),
+            "* 00:00-24:00"
+        );
+        assert_eq!(
+            "sat-mon 10:00-12:00".parse::<Window>().unwrap().to_string(),
+            "mon,sat,sun 10:00-12:00"
+        );
+
+        assert!("mon-fri".parse::<Window>().is_err());
+        assert!("xyz 10:00-12:00".parse::<Window>().is_err());
+        assert!("mon 10:70-12:00".parse::<Window>().is_err());
+        assert!("mon 24:00-12:00".parse::<Window>().is_err());
+    }
+
+    #[test]
+    fn test_window_contains() {
+        let window: Window = "mon-fri 09:00-17:00".parse().unwrap();
+        assert!(window.contains(&time(1, 9, 0)));
+        assert!(window.contains(&time(5, 16, 59)));
+        assert!(!window.contains(&time(1, 17, 0)));
+        assert!(!window.contains(&time(6, 12, 0)));
+
+        let overnight: Window = "fri 22:00-06:00".parse().unwrap();
+        assert!(overnight.contains(&time(5, 23, 0)));
+        assert!(overnight.contains(&time(6, 5, 59)));
+        assert!(!overnight.contains(&time(5, 5, 0)));
+        assert!(!overnight.contains(&time(6, 22, 0)));
+    }
+
+    #[test]
+    fn test_window_end() {
+        let overnight: Window = "fri 22:00-06:00".parse().unwrap();
+        assert_eq!(overnight.end_of(&time(5, 23, 0)), Some(time(6, 6, 0)));
+        assert_eq!(overnight.end_of(&time(6, 1, 0)), Some(time(6, 6, 0)));
+        assert_eq!(overnight.end_of(&time(6, 7, 0)), None);
+    }
+
+    #[test]
+    fn test_schedule_end() {
+        let schedule = Schedule {
+            windows: vec![
+                "mon-fri 18:00-24:00".parse().unwrap(),
+                "tue-sat 00:00-08:00".parse().unwrap(),
+            ],
+        };
+        assert!(schedule.contains(&time(1, 20, 0)));
+        assert!(!schedule.contains(&time(1, 12, 0)));
+        assert_eq!(schedule.end_of(&time(1, 20, 0)), Some(time(2, 8, 0)));
+        assert_eq!(schedule.end_of(&time(1, 12, 0)), None);
+        assert_eq!(Schedule::default().end_of(&time(1, 12, 0)), None);
+    }
+
+    #[test]
+    fn test_continuous_schedule_end() {
+        let schedules = vec![
+            vec!["* 00:00-24:00"],
+            vec!["* 12:00-12:00"],
+            vec!["mon-fri 00:00-24:00", "sat,sun 00:00-24:00"],
+            vec!["* 18:00-08:00", "* 08:00-18:00"],
+        ];
+        for windows in schedules {
+            let schedule = Schedule {
+                windows: windows.iter().map(|w| w.parse().unwrap()).collect(),
+            };
+            assert!(schedule.contains(&time(3, 12, 0)));
+            assert_eq!(schedule.end_of(&time(3, 12, 0)), None, "{:?}", windows);
+        }
+    }
+
+    #[test]
+    fn test_end_across_dst_change() {
+        // 2024-03-30 was Saturday.
+        let start = Dst.ymd(2024, 3, 30).and_hms(23, 0, 0);
+
+        let window: Window = "sat 22:00-08:00".parse().unwrap();
+        let schedule = Schedule {
+            windows: vec![window],
+        };
+        let end = Dst.ymd(2024, 3, 31).and_hms(8, 0, 0);
+        assert_eq!(window.end_of(&start), Some(end));
+        assert_eq!(schedule.end_of(&start), Some(end));
+
+        // End skipped by DST change is moved to first existing time.
+        let window: Window = "sat 22:00-02:30".parse().unwrap();
+        let schedule = Schedule {
+            windows: vec![window],
+        };
+        let end = Dst.ymd(2024, 3, 31).and_hms(3, 0, 0);
+        assert_eq!(window.end_of(&start), Some(end));
+        assert_eq!(schedule.end_of(&start), Some(end));
+    }
+}